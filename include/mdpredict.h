@@ -0,0 +1,150 @@
+/*
+ * mdpredict - C interface to the structural speech analyzer (Russian)
+ *
+ * Link against the cdylib built by `cargo build --release`
+ * (libmdpredict_russian.so / .dylib / mdpredict_russian.dll).
+ *
+ * Every fallible function returns an MdpStatus; on failure a message is
+ * available from mdp_last_error() on the same thread. Panics never cross
+ * the boundary: they are reported as MDP_PANIC.
+ *
+ * Handles are thread-safe: one analyzer and one classifier may be shared
+ * by all threads. Strings returned by the library are freed with
+ * mdp_string_free(), handles with their own free function.
+ */
+
+#ifndef MDPREDICT_H
+#define MDPREDICT_H
+
+#include <stdbool.h>
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum MdpStatus {
+    MDP_OK = 0,
+    MDP_NULL_POINTER = 1,     /* a required pointer argument was null */
+    MDP_INVALID_UTF8 = 2,     /* text or a string argument is not valid UTF-8 */
+    MDP_INVALID_ARGUMENT = 3, /* unknown backend name */
+    MDP_LOAD = 4,             /* dictionary, lexicon or model could not be loaded */
+    MDP_EMPTY_TEXT = 5,       /* text is empty or has no words */
+    MDP_SERIALIZATION = 6,    /* result could not be serialized to JSON */
+    MDP_PANIC = 7,            /* internal error; the call had no effect */
+    MDP_NOT_RUSSIAN = 8,      /* fewer than half of the letters are Cyrillic */
+    MDP_TOO_SHORT = 9         /* fewer words than the analyzer's minimum */
+} MdpStatus;
+
+typedef enum MdpGroup {
+    MDP_GROUP_HEALTHY = 0,
+    MDP_GROUP_SCHIZOPHRENIA = 1,
+    MDP_GROUP_PERSONALITY_DISORDER = 2,
+    MDP_GROUP_BIPOLAR_DISORDER = 3
+} MdpGroup;
+
+/* Metrics of one text; counts are absolute, the rest are percentages of
+ * words unless noted (see TextMetrics in the Rust documentation) */
+typedef struct MdpMetrics {
+    size_t total_words;
+    size_t total_sentences;
+    size_t run_on_sentences;
+    size_t compound_sentences;
+    size_t complex_sentences;
+    size_t simple_sentences;
+    size_t mixed_sentences;
+    double lexical_diversity_index;
+    double external_predicates;
+    double internal_predicates;
+    double active_voice_verbs;
+    double passive_voice_verbs;
+    double reflexive_verbs;
+    double perfective_verbs;
+    double imperfective_verbs;
+    double perfective_aspect_ratio;
+    double past_tense_verbs;
+    double present_tense_verbs;
+    double future_tense_verbs;
+    double infinitives;
+    double non_finite_verb_forms;
+    double adjectives;
+    double nouns;
+    double adverbs;
+    double first_person_singular_pronouns;
+    double first_person_plural_pronouns;
+    double second_person_singular_pronouns;
+    double second_person_plural_pronouns;
+    double third_person_singular_pronouns;
+    double third_person_plural_pronouns;
+    double filler_words_index;
+    double stop_words_index;
+    double prepositions;
+    double conjunctions;
+    double social_interaction_words;
+    double emotion_words;
+    double positive_emotion_share;
+    double negative_emotion_share;
+    double mean_valence;
+    double mean_arousal;
+    double emotion_diversity;
+    double negation_density;
+    double negated_internal_predicates;
+    double negated_emotion_words;
+    double direct_speech_share;
+    double reported_speech_share;
+    double egocentrism_index;
+} MdpMetrics;
+
+typedef struct MdpClassification {
+    MdpGroup primary_group;
+    double confidence; /* probability of the primary group */
+    double p_healthy;
+    double p_schizophrenia;
+    double p_personality_disorder;
+    double p_bipolar_disorder;
+} MdpClassification;
+
+typedef struct MdpAnalyzer MdpAnalyzer;
+typedef struct MdpClassifier MdpClassifier;
+
+/* Message of the last failed call on this thread ("" if none); valid until
+ * the next failing call on the same thread */
+const char *mdp_last_error(void);
+
+/* Library version, a static string */
+const char *mdp_version(void);
+
+/* backend: "rsmorphy" or "rules", NULL for "rsmorphy";
+ * lexicon_path: .toml/.json/.txt word lists, NULL for the built-in lists */
+MdpStatus mdp_analyzer_new(const char *backend, const char *lexicon_path,
+                           bool narrative_only, MdpAnalyzer **out);
+/* Minimum number of words a text needs (default 5, 0 for none); call
+ * before sharing the analyzer between threads */
+MdpStatus mdp_analyzer_set_min_words(MdpAnalyzer *analyzer, size_t min_words);
+void mdp_analyzer_free(MdpAnalyzer *analyzer);
+
+/* model_path: model saved by mdpredict (.json or .bin), NULL for the
+ * coefficients of the paper */
+MdpStatus mdp_classifier_new(const char *model_path, MdpClassifier **out);
+void mdp_classifier_free(MdpClassifier *classifier);
+
+/* Analyze len bytes of UTF-8 text (no NUL terminator needed) */
+MdpStatus mdp_analyze(const MdpAnalyzer *analyzer, const char *text, size_t len,
+                      MdpMetrics *out);
+
+MdpStatus mdp_classify(const MdpClassifier *classifier, const MdpMetrics *metrics,
+                       MdpClassification *out);
+
+/* JSON of `mdpredict --json` (metrics, classification, explanation,
+ * profile), or the metrics alone if classifier is NULL; free *out_json
+ * with mdp_string_free */
+MdpStatus mdp_analyze_json(const MdpAnalyzer *analyzer, const MdpClassifier *classifier,
+                           const char *text, size_t len, char **out_json);
+
+void mdp_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MDPREDICT_H */
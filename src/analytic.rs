@@ -0,0 +1,139 @@
+//! Analytic verb forms spanning several words
+//! The passive "быть + short participle" ("дом был построен") and the compound
+//! future "быть + infinitive" ("буду делать") are recognized within a sentence,
+//! so each construction is counted as one verb
+
+use crate::backend::{Aspect, PartOfSpeech, VerbForm, VerbTense, Voice, WordAnalysis};
+
+/// Forms of "быть" that can serve as an auxiliary
+const AUXILIARY_FORMS: [&str; 13] = [
+    "быть", "был", "была", "было", "были", "буду", "будешь", "будет", "будем", "будете", "будут",
+    "будь", "будьте",
+];
+
+/// Future forms of "быть" that form the compound future
+const FUTURE_AUXILIARY_FORMS: [&str; 6] = ["буду", "будешь", "будет", "будем", "будете", "будут"];
+
+/// Adverbs and particles allowed between the auxiliary and the main verb ("был уже построен")
+const MAX_GAP: usize = 2;
+
+/// Whether the word is a form of "быть" that can be an auxiliary
+#[must_use]
+pub fn is_auxiliary_form(analysis: &WordAnalysis) -> bool {
+    match analysis.lemma.as_deref() {
+        Some(lemma) => lemma == "быть",
+        None => AUXILIARY_FORMS.contains(&analysis.word.as_str()),
+    }
+}
+
+/// Tense of an auxiliary; future forms are recognized by spelling, since the
+/// rule-based backend cannot tell "будет" from a present form
+fn auxiliary_tense(analysis: &WordAnalysis) -> Option<VerbTense> {
+    if FUTURE_AUXILIARY_FORMS.contains(&analysis.word.as_str()) {
+        Some(VerbTense::Future)
+    } else {
+        analysis.verb_tense
+    }
+}
+
+/// Whether a word ends like a short passive participle ("построен", "убита", "решены")
+///
+/// Only meaningful next to an auxiliary; on its own the ending is ambiguous.
+#[must_use]
+pub fn looks_like_short_participle(word: &str) -> bool {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.len() < 4 {
+        return false;
+    }
+    let stem_end = match chars[chars.len() - 1] {
+        'а' | 'о' | 'ы' => chars.len() - 1,
+        _ => chars.len(),
+    };
+    matches!(
+        &chars[..stem_end],
+        [.., 'а' | 'я' | 'е' | 'ё', 'н'] | [.., 'и' | 'ы' | 'у' | 'о', 'т']
+    )
+}
+
+/// Whether a passive participle is in the short form
+fn is_short_participle(analysis: &WordAnalysis) -> bool {
+    analysis.is_passive_participle() && looks_like_short_participle(&analysis.word)
+}
+
+/// Index of the word that can complete an auxiliary at `i`: the first word
+/// after it that is not an adverb or particle, at most [`MAX_GAP`] words away
+#[must_use]
+pub fn main_verb_slot(analyses: &[WordAnalysis], i: usize) -> Option<usize> {
+    analyses
+        .get(i + 1..)?
+        .iter()
+        .take(MAX_GAP + 1)
+        .position(|a| !matches!(a.pos, PartOfSpeech::Adverb | PartOfSpeech::Particle))
+        .map(|offset| i + 1 + offset)
+}
+
+/// Mark all analytic verb forms of one sentence
+pub fn mark_analytic_forms(analyses: &mut [WordAnalysis]) {
+    mark_compound_futures(analyses);
+    mark_analytic_passives(analyses);
+}
+
+/// Mark auxiliaries of analytic passives in one sentence
+///
+/// The auxiliary gets passive voice and [`WordAnalysis::is_auxiliary`]; the
+/// participle takes the auxiliary's tense ("будет построен" is future), so the
+/// construction counts once.
+pub fn mark_analytic_passives(analyses: &mut [WordAnalysis]) {
+    for i in 0..analyses.len() {
+        if analyses[i].pos != PartOfSpeech::Verb || !is_auxiliary_form(&analyses[i]) {
+            continue;
+        }
+        if let Some(j) = main_verb_slot(analyses, i)
+            && is_short_participle(&analyses[j])
+        {
+            analyses[i].is_auxiliary = true;
+            analyses[i].voice = Some(Voice::Passive);
+            if let Some(tense) = auxiliary_tense(&analyses[i]) {
+                analyses[j].verb_tense = Some(tense);
+            }
+        }
+    }
+}
+
+/// Mark compound futures ("буду делать") in one sentence
+///
+/// The infinitive becomes an imperfective future verb and the auxiliary is
+/// marked with [`WordAnalysis::is_auxiliary`], so the construction counts as
+/// one future verb rather than a future plus an infinitive.
+pub fn mark_compound_futures(analyses: &mut [WordAnalysis]) {
+    for i in 0..analyses.len() {
+        if analyses[i].pos != PartOfSpeech::Verb
+            || !FUTURE_AUXILIARY_FORMS.contains(&analyses[i].word.as_str())
+        {
+            continue;
+        }
+        if let Some(j) = main_verb_slot(analyses, i)
+            && analyses[j].pos == PartOfSpeech::Verb
+            && analyses[j].verb_form == Some(VerbForm::Infinitive)
+        {
+            analyses[i].is_auxiliary = true;
+            analyses[j].verb_tense = Some(VerbTense::Future);
+            analyses[j].aspect = Some(Aspect::Imperfective);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_participle_endings() {
+        for word in ["построен", "построена", "решены", "убит", "закрыто", "влюблён"] {
+            assert!(looks_like_short_participle(word), "{word}");
+        }
+        for word in ["построенного", "построенная", "красив", "рад", "видно", "один"] {
+            assert!(!looks_like_short_participle(word), "{word}");
+        }
+    }
+}
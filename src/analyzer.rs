@@ -1,68 +1,397 @@
 //! Main text analyzer that computes all metrics
 //! Based on the methodology from the research paper
 
-use crate::metrics::TextMetrics;
-use crate::rsmorph::{
-    PartOfSpeech, PredicateType, PronounNumber, PronounPerson, RsMorphAnalyzer, VerbForm, VerbTense,
+use crate::analytic;
+use crate::backend::{
+    Aspect, MorphologyBackend, PartOfSpeech, PredicateType, PronounNumber, PronounPerson,
+    VerbForm, VerbTense, Voice, WordAnalysis,
 };
-use crate::sentence::{SentenceAnalyzer, SentenceType};
+use crate::batch::{default_threads, parallel_map};
+use crate::emotion::{self, EmotionCategory};
+use crate::error::{Error, Result};
+use crate::metrics::TextMetrics;
+use crate::lexicon::Lexicon;
+use crate::negation;
+use crate::phrases::{PhraseKind, PhraseToken};
+use crate::rsmorph::RsMorphAnalyzer;
+use crate::segmenter;
+use crate::sentence::{SentenceAnalysis, SentenceAnalyzer, SentenceType};
+use crate::speech::{self, SpeechKind};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use unicode_segmentation::UnicodeSegmentation;
+use std::sync::Arc;
+
+/// A single word token with the analysis the metrics were computed from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenAnnotation {
+    /// Surface form as written in the text
+    pub text: String,
+    /// Byte offset of the token start in the analyzed text
+    pub start: usize,
+    /// Byte offset of the token end in the analyzed text
+    pub end: usize,
+    /// Index of the sentence containing the token
+    pub sentence: Option<usize>,
+    /// Morphological analysis (lemma, POS, tense, predicate type, flags)
+    #[serde(flatten)]
+    pub analysis: WordAnalysis,
+    /// Counted as an active-voice verb
+    pub active_voice: bool,
+    /// Multi-word filler or conjunction the token belongs to
+    pub phrase: Option<PhraseToken>,
+    /// Direct or reported speech the token belongs to; `None` for the narrative
+    pub speech: Option<SpeechKind>,
+}
+
+/// Full analysis result with provenance for every counted metric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetailedAnalysis {
+    pub metrics: TextMetrics,
+    pub sentences: Vec<SentenceAnalysis>,
+    pub tokens: Vec<TokenAnnotation>,
+}
 
 /// Main text analyzer
+///
+/// `Send + Sync` and cheap to clone: the morphology backend is shared,
+/// so one instance can serve any number of threads.
+#[derive(Clone)]
 pub struct TextAnalyzer {
-    morph: RsMorphAnalyzer,
+    morph: Arc<dyn MorphologyBackend>,
     sentence_analyzer: SentenceAnalyzer,
+    narrative_only: bool,
+    min_words: usize,
 }
 
+/// Fewest words [`TextAnalyzer::try_analyze`] accepts by default; the
+/// percentages of shorter texts hinge on single words
+pub const DEFAULT_MIN_WORDS: usize = 5;
+
+/// Share of Cyrillic letters below which a text is not taken for Russian
+const MIN_CYRILLIC_SHARE: f64 = 0.5;
+
 impl TextAnalyzer {
+    /// Create an analyzer backed by the rsmorphy `OpenCorpora` dictionary
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dictionary cannot be loaded; see [`Self::try_new`].
     #[must_use]
     pub fn new() -> Self {
+        Self::with_backend(RsMorphAnalyzer::new())
+    }
+
+    /// Create an analyzer backed by the rsmorphy `OpenCorpora` dictionary,
+    /// reporting a dictionary that cannot be loaded
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dictionary`] if the dictionary is missing or damaged.
+    pub fn try_new() -> Result<Self> {
+        RsMorphAnalyzer::try_new().map(Self::with_backend)
+    }
+
+    /// Create an analyzer using the given morphology backend for both
+    /// word-level metrics and sentence typing
+    #[must_use]
+    pub fn with_backend(backend: impl MorphologyBackend + 'static) -> Self {
+        let morph: Arc<dyn MorphologyBackend> = Arc::new(backend);
         Self {
-            morph: RsMorphAnalyzer::new(),
-            sentence_analyzer: SentenceAnalyzer::new(),
+            sentence_analyzer: SentenceAnalyzer::with_backend(Arc::clone(&morph)),
+            morph,
+            narrative_only: false,
+            min_words: DEFAULT_MIN_WORDS,
         }
     }
 
+    /// Create an rsmorphy-backed analyzer using the given lexicon
+    /// instead of the built-in word lists
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dictionary cannot be loaded; see [`Self::try_with_lexicon`].
+    #[must_use]
+    pub fn with_lexicon(lexicon: impl Into<Arc<Lexicon>>) -> Self {
+        Self::with_backend(RsMorphAnalyzer::with_lexicon(lexicon))
+    }
+
+    /// Create an rsmorphy-backed analyzer using the given lexicon,
+    /// reporting a dictionary that cannot be loaded
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dictionary`] if the dictionary is missing or damaged.
+    pub fn try_with_lexicon(lexicon: impl Into<Arc<Lexicon>>) -> Result<Self> {
+        RsMorphAnalyzer::try_with_lexicon(lexicon).map(Self::with_backend)
+    }
+
+    /// Fewest words [`Self::try_analyze`] accepts (0 accepts any non-empty text)
+    #[must_use]
+    pub fn with_min_words(mut self, min_words: usize) -> Self {
+        self.min_words = min_words;
+        self
+    }
+
+    /// Fewest words [`Self::try_analyze`] accepts
+    #[must_use]
+    pub fn min_words(&self) -> usize {
+        self.min_words
+    }
+
+    /// Compute the word and sentence metrics on the narrative only, leaving
+    /// out words in direct speech and sentences spoken entirely by others;
+    /// the speech shares are still measured on the whole text
+    #[must_use]
+    pub fn with_narrative_only(mut self, narrative_only: bool) -> Self {
+        self.narrative_only = narrative_only;
+        self
+    }
+
+    /// Whether metrics are computed on the narrative only
+    #[must_use]
+    pub fn narrative_only(&self) -> bool {
+        self.narrative_only
+    }
+
+    /// Name of the morphology backend in use
+    #[must_use]
+    pub fn backend_name(&self) -> &'static str {
+        self.morph.name()
+    }
+
+    /// Lexicon used for the semantic word categories
+    #[must_use]
+    pub fn lexicon(&self) -> &Lexicon {
+        self.morph.lexicon()
+    }
+
     /// Analyze text and compute all metrics
+    ///
+    /// Never fails: an empty text gives all-zero metrics. Use
+    /// [`Self::try_analyze`] to reject texts the metrics say nothing about.
     #[must_use]
     pub fn analyze(&self, text: &str) -> TextMetrics {
+        self.analyze_detailed(text).metrics
+    }
+
+    /// Analyze text, rejecting empty, non-Russian and too short texts
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyText`] if the text has no words,
+    /// [`Error::NotRussian`] if fewer than half of its letters are Cyrillic
+    /// and [`Error::TooShort`] if it has fewer words than [`Self::min_words`]
+    /// (counted on the narrative only if enabled).
+    pub fn try_analyze(&self, text: &str) -> Result<TextMetrics> {
+        self.try_analyze_detailed(text).map(|detailed| detailed.metrics)
+    }
+
+    /// Annotate text as [`Self::analyze_detailed`], rejecting the same texts
+    /// as [`Self::try_analyze`]
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::try_analyze`].
+    pub fn try_analyze_detailed(&self, text: &str) -> Result<DetailedAnalysis> {
+        if text.trim().is_empty() {
+            return Err(Error::EmptyText);
+        }
+        if let Some(cyrillic_share) = cyrillic_share(text).filter(|&share| share < MIN_CYRILLIC_SHARE) {
+            return Err(Error::NotRussian { cyrillic_share });
+        }
+        let detailed = self.analyze_detailed(text);
+        match detailed.metrics.total_words {
+            0 => Err(Error::EmptyText),
+            words if words < self.min_words => Err(Error::TooShort {
+                words,
+                min_words: self.min_words,
+            }),
+            _ => Ok(detailed),
+        }
+    }
+
+    /// Analyze many texts in parallel on all available cores, preserving order
+    #[must_use]
+    pub fn analyze_batch<T: AsRef<str> + Sync>(&self, texts: &[T]) -> Vec<TextMetrics> {
+        self.analyze_batch_with_threads(texts, default_threads())
+    }
+
+    /// Analyze many texts in parallel on `threads` worker threads, preserving order
+    #[must_use]
+    pub fn analyze_batch_with_threads<T: AsRef<str> + Sync>(
+        &self,
+        texts: &[T],
+        threads: usize,
+    ) -> Vec<TextMetrics> {
+        parallel_map(texts, threads, |text| self.analyze(text.as_ref()))
+    }
+
+    /// Analyze text and return the metrics together with the annotated
+    /// token stream and sentence spans they were computed from
+    #[must_use]
+    pub fn analyze_detailed(&self, text: &str) -> DetailedAnalysis {
         let mut metrics = TextMetrics::new();
 
         // Extract words
-        let words = Self::extract_words(text);
+        let words = segmenter::words(text);
         let total_words = words.len();
         metrics.total_words = total_words;
 
         if total_words == 0 {
-            return metrics;
+            return DetailedAnalysis {
+                metrics,
+                sentences: Vec::new(),
+                tokens: Vec::new(),
+            };
         }
 
         // Analyze sentences
         let sentence_analyses = self.sentence_analyzer.analyze_text(text);
-        metrics.total_sentences = sentence_analyses.len();
+
+        // Locate every word in its sentence and in direct speech; in
+        // narrative-only mode words in direct speech are not counted
+        let direct_speech = speech::direct_speech_spans(text);
+        let located: Vec<(Option<usize>, usize, &str)> = words
+            .iter()
+            .map(|&(start, word)| {
+                let end = start + word.len();
+                let sentence = sentence_analyses
+                    .iter()
+                    .position(|s| s.start <= start && end <= s.end);
+                (sentence, start, word)
+            })
+            .collect();
+        let counted: Vec<bool> = words
+            .iter()
+            .map(|&(start, _)| !(self.narrative_only && speech::in_spans(&direct_speech, start)))
+            .collect();
+        let counted_words = counted.iter().filter(|&&c| c).count();
+        metrics.total_words = counted_words;
 
         // Count sentence types
-        for analysis in &sentence_analyses {
+        for (i, analysis) in sentence_analyses.iter().enumerate() {
+            let narrative = located
+                .iter()
+                .zip(&counted)
+                .any(|(&(sentence, _, _), &c)| sentence == Some(i) && c);
+            if self.narrative_only && !narrative {
+                continue;
+            }
+            metrics.total_sentences += 1;
             match analysis.sentence_type {
                 SentenceType::Simple => metrics.simple_sentences += 1,
                 SentenceType::Compound => metrics.compound_sentences += 1,
                 SentenceType::Complex => metrics.complex_sentences += 1,
                 SentenceType::RunOn => metrics.run_on_sentences += 1,
+                SentenceType::Mixed => metrics.mixed_sentences += 1,
             }
         }
 
         // Calculate lexical diversity
-        let unique_words: HashSet<_> = words.iter().map(|w| w.to_lowercase()).collect();
-        metrics.lexical_diversity_index = TextMetrics::percentage(unique_words.len(), total_words);
+        let unique_words: HashSet<_> = words
+            .iter()
+            .zip(&counted)
+            .filter(|&(_, &c)| c)
+            .map(|((_, w), _)| w.to_lowercase())
+            .collect();
+        metrics.lexical_diversity_index = TextMetrics::percentage(unique_words.len(), counted_words);
 
         // Analyze each word
         let mut counters = WordCounters::default();
+        let mut skipped = WordCounters::default();
+        let mut tokens = Vec::with_capacity(total_words);
+
+        // Analyze sentence by sentence, so ambiguous words are resolved from their neighbours
+        let analyses = located.chunk_by(|a, b| a.0 == b.0).flat_map(|group| {
+            let group_words: Vec<&str> = group.iter().map(|&(_, _, word)| word).collect();
+            let phrases = self.morph.lexicon().phrases().annotate(&group_words);
+            let mut analyses = self.morph.analyze_sentence(&group_words);
+            analytic::mark_analytic_forms(&mut analyses);
+            negation::mark_negation(&mut analyses);
+            analyses.into_iter().zip(phrases)
+        });
+
+        for ((&(sentence, start, word), (mut analysis, phrase)), &counted) in
+            located.iter().zip(analyses).zip(&counted)
+        {
+            let target = if counted { &mut counters } else { &mut skipped };
+            let active_voice = Self::count_word(&mut analysis, phrase.as_ref(), target);
+
+            tokens.push(TokenAnnotation {
+                text: word.to_string(),
+                start,
+                end: start + word.len(),
+                sentence,
+                analysis,
+                active_voice,
+                phrase,
+                speech: speech::in_spans(&direct_speech, start).then_some(SpeechKind::Direct),
+            });
+        }
+        Self::mark_reported_speech(&sentence_analyses, &mut tokens);
 
-        for word in &words {
-            let analysis = self.morph.analyze(word);
+        // Convert counts to percentages
+        Self::counters_to_metrics(&counters, counted_words, &mut metrics);
+        let speech_words = |kind| tokens.iter().filter(|t| t.speech == Some(kind)).count();
+        metrics.direct_speech_share =
+            TextMetrics::percentage(speech_words(SpeechKind::Direct), total_words);
+        metrics.reported_speech_share =
+            TextMetrics::percentage(speech_words(SpeechKind::Reported), total_words);
+
+        DetailedAnalysis {
+            metrics,
+            sentences: sentence_analyses,
+            tokens,
+        }
+    }
 
-            // Count parts of speech
+    /// Mark the tokens of reported-speech clauses that are not direct speech
+    fn mark_reported_speech(sentences: &[SentenceAnalysis], tokens: &mut [TokenAnnotation]) {
+        let mut reported = Vec::new();
+        let mut offset = 0;
+        for group in tokens.chunk_by(|a, b| a.sentence == b.sentence) {
+            if let Some(sentence) = group[0].sentence.and_then(|i| sentences.get(i)) {
+                let analyses: Vec<&WordAnalysis> = group.iter().map(|t| &t.analysis).collect();
+                reported.extend(
+                    speech::reported_speech_words(sentence, &analyses)
+                        .into_iter()
+                        .filter(|&word| word < group.len())
+                        .map(|word| offset + word),
+                );
+            }
+            offset += group.len();
+        }
+        for token in reported {
+            tokens[token].speech.get_or_insert(SpeechKind::Reported);
+        }
+    }
+
+    /// Count one word, returning whether it was counted as an active-voice verb
+    ///
+    /// A multi-word filler or compound conjunction is counted once, on its
+    /// first token; the remaining tokens get no part of speech. The tokens
+    /// of a filler count only as the filler, so "так сказать" adds no verb,
+    /// infinitive or predicate.
+    fn count_word(
+        analysis: &mut WordAnalysis,
+        phrase: Option<&PhraseToken>,
+        counters: &mut WordCounters,
+    ) -> bool {
+        let mut count_pos = true;
+        let in_filler = phrase.is_some_and(|phrase| phrase.kind == PhraseKind::Filler);
+        if let Some(phrase) = phrase {
+            if in_filler {
+                analysis.is_filler = phrase.head;
+                count_pos = false;
+            } else if phrase.head {
+                analysis.pos = PartOfSpeech::Conjunction;
+            } else {
+                count_pos = false;
+            }
+        }
+
+        let mut active_voice = false;
+        if count_pos {
             match analysis.pos {
                 PartOfSpeech::Noun => counters.nouns += 1,
                 PartOfSpeech::Adjective => counters.adjectives += 1,
@@ -70,48 +399,57 @@ impl TextAnalyzer {
                 PartOfSpeech::Preposition => counters.prepositions += 1,
                 PartOfSpeech::Conjunction => counters.conjunctions += 1,
                 PartOfSpeech::Pronoun => {
-                    Self::count_pronoun(&analysis, &mut counters);
+                    Self::count_pronoun(analysis, counters);
                 }
                 PartOfSpeech::Verb => {
-                    Self::count_verb(&analysis, word, &mut counters);
+                    active_voice = Self::count_verb(analysis, counters);
                 }
                 _ => {}
             }
+        }
 
-            // Count special categories
-            if analysis.is_filler {
-                counters.filler_words += 1;
-            }
-            if analysis.is_stop_word {
-                counters.stop_words += 1;
-            }
-            if analysis.is_emotion_word {
-                counters.emotion_words += 1;
-            }
-            if analysis.is_social_interaction {
-                counters.social_interaction_words += 1;
-            }
-            if analysis.is_egocentrism_marker {
-                counters.egocentrism_markers += 1;
+        // Count special categories
+        if analysis.is_filler {
+            counters.filler_words += 1;
+        }
+        // The words of a filler phrase are not counted in any other category
+        if in_filler {
+            return false;
+        }
+        if analysis.is_stop_word {
+            counters.stop_words += 1;
+        }
+        if analysis.is_negation {
+            counters.negations += 1;
+        }
+        // A negated emotion word ("не страшно") is reported apart from the emotions
+        if analysis.is_emotion_word && analysis.is_negated {
+            counters.negated_emotion_words += 1;
+        } else if analysis.is_emotion_word {
+            counters.emotion_words += 1;
+        }
+        if let Some(tag) = analysis.emotion.filter(|_| !analysis.is_negated) {
+            counters.rated_emotions += 1;
+            counters.positive_emotions += usize::from(tag.valence > 0.0);
+            counters.negative_emotions += usize::from(tag.valence < 0.0);
+            counters.valence_sum += tag.valence;
+            counters.arousal_sum += tag.arousal;
+            if let Some(category) = tag.category {
+                counters.emotion_categories[category.index()] += 1;
             }
         }
+        if analysis.is_social_interaction {
+            counters.social_interaction_words += 1;
+        }
+        if analysis.is_egocentrism_marker {
+            counters.egocentrism_markers += 1;
+        }
 
-        // Convert counts to percentages
-        Self::counters_to_metrics(&counters, total_words, &mut metrics);
-
-        metrics
-    }
-
-    /// Extract words from text using Unicode word segmentation
-    fn extract_words(text: &str) -> Vec<String> {
-        text.unicode_words()
-            .filter(|s| s.chars().any(char::is_alphabetic))
-            .map(ToString::to_string)
-            .collect()
+        active_voice
     }
 
     /// Count pronoun types
-    fn count_pronoun(analysis: &crate::rsmorph::WordAnalysis, counters: &mut WordCounters) {
+    fn count_pronoun(analysis: &WordAnalysis, counters: &mut WordCounters) {
         match (analysis.pronoun_person, analysis.pronoun_number) {
             (Some(PronounPerson::First), Some(PronounNumber::Singular)) => {
                 counters.first_person_singular += 1;
@@ -143,12 +481,13 @@ impl TextAnalyzer {
         }
     }
 
-    /// Count verb types
-    fn count_verb(
-        analysis: &crate::rsmorph::WordAnalysis,
-        word: &str,
-        counters: &mut WordCounters,
-    ) {
+    /// Count verb types, returning whether the verb was counted as active voice
+    fn count_verb(analysis: &WordAnalysis, counters: &mut WordCounters) -> bool {
+        // An auxiliary is counted with its main verb ("был построен", "буду делать")
+        if analysis.is_auxiliary {
+            return false;
+        }
+
         // Count by tense
         match analysis.verb_tense {
             Some(VerbTense::Past) => counters.past_tense += 1,
@@ -166,17 +505,36 @@ impl TextAnalyzer {
         // Count by predicate type
         match analysis.predicate_type {
             Some(PredicateType::External) => counters.external_predicates += 1,
-            Some(PredicateType::Internal) => counters.internal_predicates += 1,
+            Some(PredicateType::Internal) => {
+                counters.internal_predicates += 1;
+                counters.negated_internal_predicates += usize::from(analysis.is_negated);
+            }
             _ => {}
         }
 
-        // Count active voice
-        if RsMorphAnalyzer::is_active_voice(word) {
+        // Count by aspect
+        match analysis.aspect {
+            Some(Aspect::Perfective) => counters.perfective += 1,
+            Some(Aspect::Imperfective) => counters.imperfective += 1,
+            None => {}
+        }
+
+        if analysis.is_reflexive {
+            counters.reflexive += 1;
+        }
+
+        // Count voice
+        if analysis.voice == Some(Voice::Passive) {
+            counters.passive_voice += 1;
+            false
+        } else {
             counters.active_voice += 1;
+            true
         }
     }
 
     /// Convert word counters to metric percentages
+    #[allow(clippy::cast_precision_loss)]
     fn counters_to_metrics(counters: &WordCounters, total: usize, metrics: &mut TextMetrics) {
         metrics.nouns = TextMetrics::percentage(counters.nouns, total);
         metrics.adjectives = TextMetrics::percentage(counters.adjectives, total);
@@ -205,6 +563,12 @@ impl TextAnalyzer {
         metrics.infinitives = TextMetrics::percentage(counters.infinitives, total);
         metrics.non_finite_verb_forms = TextMetrics::percentage(counters.non_finite_forms, total);
         metrics.active_voice_verbs = TextMetrics::percentage(counters.active_voice, total);
+        metrics.passive_voice_verbs = TextMetrics::percentage(counters.passive_voice, total);
+        metrics.reflexive_verbs = TextMetrics::percentage(counters.reflexive, total);
+        metrics.perfective_verbs = TextMetrics::percentage(counters.perfective, total);
+        metrics.imperfective_verbs = TextMetrics::percentage(counters.imperfective, total);
+        metrics.perfective_aspect_ratio =
+            TextMetrics::percentage(counters.perfective, counters.perfective + counters.imperfective);
 
         // Predicates
         metrics.external_predicates = TextMetrics::percentage(counters.external_predicates, total);
@@ -217,6 +581,25 @@ impl TextAnalyzer {
         metrics.social_interaction_words =
             TextMetrics::percentage(counters.social_interaction_words, total);
         metrics.egocentrism_index = TextMetrics::percentage(counters.egocentrism_markers, total);
+
+        // Emotion profile
+        let rated = counters.rated_emotions;
+        metrics.positive_emotion_share = TextMetrics::percentage(counters.positive_emotions, rated);
+        metrics.negative_emotion_share = TextMetrics::percentage(counters.negative_emotions, rated);
+        if rated > 0 {
+            metrics.mean_valence = counters.valence_sum / rated as f64;
+            metrics.mean_arousal = counters.arousal_sum / rated as f64;
+        }
+        metrics.emotion_diversity = emotion::diversity(&counters.emotion_categories);
+
+        // Negation
+        metrics.negation_density = TextMetrics::percentage(counters.negations, total);
+        metrics.negated_internal_predicates =
+            TextMetrics::percentage(counters.negated_internal_predicates, counters.internal_predicates);
+        metrics.negated_emotion_words = TextMetrics::percentage(
+            counters.negated_emotion_words,
+            counters.emotion_words + counters.negated_emotion_words,
+        );
     }
 }
 
@@ -251,6 +634,10 @@ struct WordCounters {
     infinitives: usize,
     non_finite_forms: usize,
     active_voice: usize,
+    passive_voice: usize,
+    reflexive: usize,
+    perfective: usize,
+    imperfective: usize,
 
     // Predicates
     external_predicates: usize,
@@ -262,12 +649,42 @@ struct WordCounters {
     emotion_words: usize,
     social_interaction_words: usize,
     egocentrism_markers: usize,
+
+    // Emotion profile
+    rated_emotions: usize,
+    positive_emotions: usize,
+    negative_emotions: usize,
+    valence_sum: f64,
+    arousal_sum: f64,
+    emotion_categories: [usize; EmotionCategory::ALL.len()],
+
+    // Negation
+    negations: usize,
+    negated_internal_predicates: usize,
+    negated_emotion_words: usize,
+}
+
+/// Share of Cyrillic letters among all letters of the text, `None` if it has none
+fn cyrillic_share(text: &str) -> Option<f64> {
+    let letters = text.chars().filter(|c| c.is_alphabetic());
+    let (cyrillic, total) = letters.fold((0, 0), |(cyrillic, total), c| {
+        (cyrillic + usize::from(matches!(c, '\u{0400}'..='\u{04FF}')), total + 1)
+    });
+    (total > 0).then(|| TextMetrics::percentage(cyrillic, total) / 100.0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Analyzers with the rsmorphy and the rule-based backend
+    fn both_backends() -> [TextAnalyzer; 2] {
+        [
+            TextAnalyzer::new(),
+            TextAnalyzer::with_backend(crate::morphology::MorphAnalyzer::new()),
+        ]
+    }
+
     #[test]
     fn test_basic_analysis() {
         let analyzer = TextAnalyzer::new();
@@ -279,6 +696,235 @@ mod tests {
         assert!(metrics.total_sentences > 0);
     }
 
+    #[test]
+    fn test_rule_based_backend() {
+        let [rsmorphy, rules] = both_backends();
+        assert_eq!(rsmorphy.backend_name(), "rsmorphy");
+        assert_eq!(rules.backend_name(), "rules");
+
+        let text = "Я помню, как мы гуляли в парке. Я думаю о маме.";
+        let a = rsmorphy.analyze(text);
+        let b = rules.analyze(text);
+
+        assert_eq!(a.total_words, b.total_words);
+        assert_eq!(a.total_sentences, b.total_sentences);
+        assert!(b.first_person_singular_pronouns > 0.0);
+        assert!(b.internal_predicates > 0.0);
+        assert!(rules.analyze_detailed(text).tokens.iter().all(|t| t.analysis.lemma.is_none()));
+    }
+
+    #[test]
+    fn test_multiword_fillers_counted_once() {
+        let analyzer = TextAnalyzer::new();
+
+        let detailed = analyzer.analyze_detailed("Ну, в общем, я как бы устал.");
+        assert_eq!(detailed.metrics.total_words, 7);
+        let fillers = detailed.tokens.iter().filter(|t| t.analysis.is_filler).count();
+        assert_eq!(fillers, 3);
+        assert!((detailed.metrics.filler_words_index - 300.0 / 7.0).abs() < 1e-9);
+        let phrase = detailed.tokens[1].phrase.as_ref().unwrap();
+        assert_eq!(phrase.phrase, "в общем");
+        assert!(phrase.head);
+    }
+
+    #[test]
+    fn test_filler_words_have_no_part_of_speech() {
+        for analyzer in both_backends() {
+            let plain = analyzer.analyze("Я пошёл домой вчера вечером.");
+            assert_eq!(plain.total_words, 5);
+            for text in [
+                "Я, так сказать, пошёл домой вчера вечером.",
+                "Я, в общем, пошёл домой вчера вечером.",
+                "Я, это самое, пошёл домой вчера вечером.",
+            ] {
+                let filled = analyzer.analyze(text);
+                assert_eq!(filled.total_words, 7, "{text}");
+                for (share, filled_share) in [
+                    (plain.past_tense_verbs, filled.past_tense_verbs),
+                    (plain.infinitives, filled.infinitives),
+                    (plain.external_predicates, filled.external_predicates),
+                    (plain.prepositions, filled.prepositions),
+                    (plain.adjectives, filled.adjectives),
+                    (plain.first_person_singular_pronouns, filled.first_person_singular_pronouns),
+                ] {
+                    // Same absolute count over 5 and 7 words
+                    assert!((share * 5.0 - filled_share * 7.0).abs() < 1e-9, "{text}");
+                }
+                assert!((filled.filler_words_index - 100.0 / 7.0).abs() < 1e-9, "{text}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_lexicon() {
+        use crate::lexicon::LexiconFormat;
+
+        let lexicon = Lexicon::builtin()
+            .extended("[emotion_words]\nхандра\n[filler_words]\nскажем честно\n", LexiconFormat::Text)
+            .unwrap();
+        let text = "Скажем честно, меня грызёт хандра.";
+
+        let builtin = TextAnalyzer::new().analyze(text);
+        assert!(builtin.emotion_words.abs() < f64::EPSILON);
+        assert!(builtin.filler_words_index.abs() < f64::EPSILON);
+
+        for analyzer in [
+            TextAnalyzer::with_lexicon(lexicon.clone()),
+            TextAnalyzer::with_backend(crate::morphology::MorphAnalyzer::with_lexicon(lexicon.clone())),
+        ] {
+            assert_eq!(analyzer.lexicon().info(), lexicon.info());
+            let detailed = analyzer.analyze_detailed(text);
+            assert!(detailed.tokens[4].analysis.is_emotion_word);
+            assert!(detailed.tokens[0].analysis.is_filler);
+            assert!(!detailed.tokens[1].analysis.is_filler);
+        }
+    }
+
+    #[test]
+    fn test_voice_metrics() {
+        let text = "Дом был построен, а мы катались и смеялись.";
+        for analyzer in both_backends() {
+            let detailed = analyzer.analyze_detailed(text);
+            let m = &detailed.metrics;
+            assert_eq!(m.total_words, 8);
+            assert!((m.passive_voice_verbs - 12.5).abs() < 1e-9);
+            assert!((m.active_voice_verbs - 25.0).abs() < 1e-9);
+            assert!((m.reflexive_verbs - 25.0).abs() < 1e-9);
+            assert!(detailed.tokens[1].analysis.is_auxiliary);
+            assert!(!detailed.tokens[1].active_voice);
+        }
+    }
+
+    #[test]
+    fn test_compound_future_and_aspect() {
+        let text = "Завтра я буду долго делать уроки, а потом пойду гулять.";
+        let m = TextAnalyzer::new().analyze(text);
+        assert_eq!(m.total_words, 10);
+        assert!((m.future_tense_verbs - 20.0).abs() < 1e-9);
+        assert!((m.infinitives - 10.0).abs() < 1e-9);
+        assert!(m.present_tense_verbs.abs() < f64::EPSILON);
+        assert!((m.perfective_verbs - 10.0).abs() < 1e-9);
+        assert!((m.imperfective_verbs - 20.0).abs() < 1e-9);
+        assert!((m.perfective_aspect_ratio - 100.0 / 3.0).abs() < 1e-9);
+
+        // Without a dictionary only the compound future is recognized
+        let rules = TextAnalyzer::with_backend(crate::morphology::MorphAnalyzer::new());
+        let m = rules.analyze(text);
+        assert!((m.future_tense_verbs - 10.0).abs() < 1e-9);
+        assert!((m.infinitives - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_emotion_profile() {
+        let text = "Мне было страшно и тоскливо, а потом я обрадовалась.";
+        let rated = |detailed: &DetailedAnalysis| {
+            detailed.tokens.iter().filter(|t| t.analysis.emotion.is_some()).count()
+        };
+
+        let detailed = TextAnalyzer::new().analyze_detailed(text);
+        let m = &detailed.metrics;
+        assert_eq!(rated(&detailed), 3);
+        assert!((m.negative_emotion_share - 200.0 / 3.0).abs() < 1e-9);
+        assert!((m.positive_emotion_share - 100.0 / 3.0).abs() < 1e-9);
+        assert!((m.mean_valence - (-0.8 - 0.8 + 0.8) / 3.0).abs() < 1e-9);
+        assert!((m.emotion_diversity - 3.0_f64.ln() / 6.0_f64.ln()).abs() < 1e-9);
+
+        // The rule-based backend has no lemma for "обрадовалась"
+        let rules = TextAnalyzer::with_backend(crate::morphology::MorphAnalyzer::new());
+        let detailed = rules.analyze_detailed(text);
+        let m = &detailed.metrics;
+        assert_eq!(rated(&detailed), 2);
+        assert!((m.negative_emotion_share - 100.0).abs() < 1e-9);
+        assert!(m.positive_emotion_share.abs() < f64::EPSILON);
+
+        let neutral = TextAnalyzer::new().analyze("Я пошёл в магазин.");
+        assert!(neutral.mean_valence.abs() < f64::EPSILON);
+        assert!(neutral.emotion_diversity.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_direct_speech_and_narrative_only() {
+        let text = "Мама сказала: «Ты не плачь». Я успокоился.";
+        for analyzer in both_backends() {
+            let detailed = analyzer.analyze_detailed(text);
+            let m = &detailed.metrics;
+            assert_eq!(m.total_words, 7);
+            assert!((m.direct_speech_share - 300.0 / 7.0).abs() < 1e-9);
+            assert!(m.second_person_singular_pronouns > 0.0);
+            assert_eq!(detailed.tokens[2].speech, Some(SpeechKind::Direct));
+            assert_eq!(detailed.tokens[0].speech, None);
+
+            let narrative = analyzer.with_narrative_only(true).analyze(text);
+            assert_eq!(narrative.total_words, 4);
+            assert_eq!(narrative.total_sentences, 2);
+            assert!(narrative.second_person_singular_pronouns.abs() < f64::EPSILON);
+            assert!((narrative.first_person_singular_pronouns - 25.0).abs() < 1e-9);
+            assert!((narrative.direct_speech_share - 300.0 / 7.0).abs() < 1e-9);
+        }
+
+        // A dialogue line spoken entirely by someone else is not a narrative sentence
+        let dialogue = TextAnalyzer::new().with_narrative_only(true).analyze("Я упал.\n— Не плачь!");
+        assert_eq!(dialogue.total_sentences, 1);
+        assert_eq!(dialogue.total_words, 2);
+    }
+
+    #[test]
+    fn test_reported_speech() {
+        for analyzer in both_backends() {
+            let detailed = analyzer.analyze_detailed("Мама сказала, что я скоро вырасту.");
+            let reported: Vec<&str> = detailed
+                .tokens
+                .iter()
+                .filter(|t| t.speech == Some(SpeechKind::Reported))
+                .map(|t| t.text.as_str())
+                .collect();
+            assert_eq!(reported, vec!["что", "я", "скоро", "вырасту"]);
+            assert!((detailed.metrics.reported_speech_share - 400.0 / 6.0).abs() < 1e-9);
+            assert!(detailed.metrics.direct_speech_share.abs() < f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_negation_metrics() {
+        for analyzer in both_backends() {
+            let detailed = analyzer.analyze_detailed("Я не помню маму. Мне было не страшно, а весело.");
+            let negated: Vec<&str> = detailed
+                .tokens
+                .iter()
+                .filter(|t| t.analysis.is_negated)
+                .map(|t| t.text.as_str())
+                .collect();
+            assert_eq!(negated, vec!["помню", "страшно"]);
+
+            let m = &detailed.metrics;
+            assert!((m.negation_density - 20.0).abs() < 1e-9);
+            assert!((m.negated_internal_predicates - 100.0).abs() < 1e-9);
+            // "страшно" is reported apart, only "весело" counts as an emotion word
+            assert!((m.negated_emotion_words - 50.0).abs() < 1e-9);
+            assert!((m.emotion_words - 10.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_detailed_analysis_spans() {
+        let analyzer = TextAnalyzer::new();
+
+        let text = "Я упал. Мама плакала!";
+        let detailed = analyzer.analyze_detailed(text);
+
+        assert_eq!(detailed.tokens.len(), detailed.metrics.total_words);
+        assert_eq!(detailed.sentences.len(), 2);
+        for token in &detailed.tokens {
+            assert_eq!(&text[token.start..token.end], token.text);
+        }
+        assert_eq!(detailed.tokens[0].sentence, Some(0));
+        assert_eq!(detailed.tokens[3].sentence, Some(1));
+
+        let upal = &detailed.tokens[1];
+        assert_eq!(upal.analysis.pos, PartOfSpeech::Verb);
+        assert_eq!(upal.analysis.verb_tense, Some(VerbTense::Past));
+    }
+
     #[test]
     fn test_pronoun_counting() {
         let analyzer = TextAnalyzer::new();
@@ -291,6 +937,26 @@ mod tests {
         assert!(metrics.third_person_plural_pronouns > 0.0);
     }
 
+    #[test]
+    fn test_try_analyze() {
+        for analyzer in both_backends() {
+            assert!(matches!(analyzer.try_analyze("  \n"), Err(Error::EmptyText)));
+            assert!(matches!(analyzer.try_analyze("... !!!"), Err(Error::EmptyText)));
+            assert!(matches!(
+                analyzer.try_analyze("I remember riding my bike and falling."),
+                Err(Error::NotRussian { cyrillic_share }) if cyrillic_share == 0.0
+            ));
+            assert!(matches!(
+                analyzer.try_analyze("Я упал."),
+                Err(Error::TooShort { words: 2, min_words: DEFAULT_MIN_WORDS })
+            ));
+            assert!(analyzer.clone().with_min_words(2).try_analyze("Я упал.").is_ok());
+
+            let text = "Я помню, как мы гуляли в парке с мамой.";
+            assert_eq!(analyzer.try_analyze(text).unwrap().total_words, analyzer.analyze(text).total_words);
+        }
+    }
+
     #[test]
     fn test_schizophrenia_example() {
         let analyzer = TextAnalyzer::new();
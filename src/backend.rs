@@ -0,0 +1,202 @@
+//! Pluggable morphological analysis
+//! Common word-level types and the trait every morphology backend implements
+
+use crate::emotion::EmotionTag;
+use crate::lexicon::Lexicon;
+use serde::{Deserialize, Serialize};
+
+/// Part of speech categories
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartOfSpeech {
+    Noun,
+    Verb,
+    Adjective,
+    Adverb,
+    Pronoun,
+    Preposition,
+    Conjunction,
+    Numeral,
+    Particle,
+    Interjection,
+    Unknown,
+}
+
+/// Verb tense
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VerbTense {
+    Past,
+    Present,
+    Future,
+    Infinitive,
+    Unknown,
+}
+
+/// Verb form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VerbForm {
+    Finite,
+    Infinitive,
+    Participle,
+    Gerund,
+    Unknown,
+}
+
+/// Grammatical case of a noun, pronoun or adjective
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Case {
+    Nominative,
+    Genitive,
+    Dative,
+    Accusative,
+    Instrumental,
+    Prepositional,
+}
+
+/// Verb aspect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Aspect {
+    Perfective,
+    Imperfective,
+}
+
+/// Verb voice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Voice {
+    Active,
+    Passive,
+}
+
+/// Predicate type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PredicateType {
+    External,
+    Internal,
+    Neither,
+}
+
+/// Pronoun person
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PronounPerson {
+    First,
+    Second,
+    Third,
+    Reflexive,
+    Unknown,
+}
+
+/// Pronoun number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PronounNumber {
+    Singular,
+    Plural,
+    Unknown,
+}
+
+/// Word analysis result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct WordAnalysis {
+    pub word: String,
+    pub lemma: Option<String>,
+    pub pos: PartOfSpeech,
+    /// Case of a declinable word, if the backend can tell
+    pub case: Option<Case>,
+    pub verb_tense: Option<VerbTense>,
+    pub verb_form: Option<VerbForm>,
+    /// Aspect of a verb form, if the backend can tell
+    pub aspect: Option<Aspect>,
+    /// Voice of a verb form; passive for passive participles and analytic passives
+    pub voice: Option<Voice>,
+    /// Reflexive verb form ending in "-ся"/"-сь" ("катался", "смеялись")
+    pub is_reflexive: bool,
+    /// Form of "быть" used as an auxiliary ("был построен", "буду делать"),
+    /// counted with its main verb
+    pub is_auxiliary: bool,
+    pub predicate_type: Option<PredicateType>,
+    pub pronoun_person: Option<PronounPerson>,
+    pub pronoun_number: Option<PronounNumber>,
+    pub is_filler: bool,
+    pub is_stop_word: bool,
+    pub is_emotion_word: bool,
+    pub is_social_interaction: bool,
+    pub is_egocentrism_marker: bool,
+    /// Negator: "не", "ни", "нет", "никогда", "ничего", ...
+    pub is_negation: bool,
+    /// Predicate, adjective or adverb in the scope of "не"/"ни" ("не помню", "не страшно")
+    pub is_negated: bool,
+    /// Affective ratings if the word is a rated emotion word
+    pub emotion: Option<EmotionTag>,
+}
+
+impl WordAnalysis {
+    #[must_use]
+    pub fn new(word: &str) -> Self {
+        Self {
+            word: word.to_string(),
+            lemma: None,
+            pos: PartOfSpeech::Unknown,
+            case: None,
+            verb_tense: None,
+            verb_form: None,
+            aspect: None,
+            voice: None,
+            is_reflexive: false,
+            is_auxiliary: false,
+            predicate_type: None,
+            pronoun_person: None,
+            pronoun_number: None,
+            is_filler: false,
+            is_stop_word: false,
+            is_emotion_word: false,
+            is_social_interaction: false,
+            is_egocentrism_marker: false,
+            is_negation: false,
+            is_negated: false,
+            emotion: None,
+        }
+    }
+
+    /// Whether the word is a finite (personal) verb form, i.e. a clause predicate
+    #[must_use]
+    pub fn is_finite_verb(&self) -> bool {
+        self.pos == PartOfSpeech::Verb && self.verb_form == Some(VerbForm::Finite)
+    }
+
+    /// Whether the word is a passive participle, short or full
+    #[must_use]
+    pub fn is_passive_participle(&self) -> bool {
+        self.verb_form == Some(VerbForm::Participle) && self.voice == Some(Voice::Passive)
+    }
+}
+
+/// Whether a verb form is reflexive ("-ся"/"-сь")
+#[must_use]
+pub fn is_reflexive_form(word: &str) -> bool {
+    word.ends_with("ся") || word.ends_with("сь")
+}
+
+/// A morphological analyzer that produces a [`WordAnalysis`] for every word
+///
+/// Implemented by the dictionary-backed [`RsMorphAnalyzer`](crate::rsmorph::RsMorphAnalyzer)
+/// and the rule-based [`MorphAnalyzer`](crate::morphology::MorphAnalyzer), which
+/// needs no dictionary files.
+pub trait MorphologyBackend: Send + Sync {
+    /// Short backend name used in reports and comparisons
+    fn name(&self) -> &'static str;
+
+    /// Analyze a single word
+    fn analyze(&self, word: &str) -> WordAnalysis;
+
+    /// Word lists used for the semantic categories; the built-in lexicon by default
+    fn lexicon(&self) -> &Lexicon {
+        Lexicon::builtin_ref()
+    }
+
+    /// Analyze the words of one sentence in order
+    ///
+    /// Backends that can resolve ambiguous words from context override this;
+    /// the default analyzes every word on its own.
+    fn analyze_sentence(&self, words: &[&str]) -> Vec<WordAnalysis> {
+        words.iter().map(|word| self.analyze(word)).collect()
+    }
+}
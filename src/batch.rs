@@ -0,0 +1,604 @@
+//! Batch processing of text corpora
+//! Collects input texts from a directory, a file-name pattern or a manifest
+//! and writes one CSV/TSV row per text for import into SPSS or R
+
+use crate::analyzer::TextAnalyzer;
+use crate::classifier::Classifier;
+use crate::error::Error;
+use crate::lexicon::LexiconInfo;
+use crate::metrics::{ClassificationResult, DiagnosticGroup, TextMetrics};
+use std::fs;
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Delimited output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values
+    Csv,
+    /// Tab-separated values
+    Tsv,
+}
+
+impl ExportFormat {
+    /// Guess the format from a file extension (`.tsv`/`.tab` is TSV, anything else CSV)
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("tsv" | "tab") => ExportFormat::Tsv,
+            _ => ExportFormat::Csv,
+        }
+    }
+
+    /// Field separator
+    #[must_use]
+    pub fn separator(self) -> char {
+        match self {
+            ExportFormat::Csv => ',',
+            ExportFormat::Tsv => '\t',
+        }
+    }
+}
+
+/// A single text to process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchInput {
+    /// Subject identifier written to the `id` column
+    pub id: String,
+    pub path: PathBuf,
+}
+
+/// Error when collecting batch inputs
+#[derive(Debug)]
+pub enum BatchError {
+    Io(io::Error),
+    /// Manifest has no `id`/`path` header or a malformed row
+    InvalidManifest(String),
+    /// Source matched no text files
+    NoInputs(String),
+}
+
+impl std::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::Io(e) => write!(f, "batch I/O error: {e}"),
+            BatchError::InvalidManifest(reason) => write!(f, "invalid manifest: {reason}"),
+            BatchError::NoInputs(source) => write!(f, "no input texts found in '{source}'"),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BatchError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BatchError {
+    fn from(e: io::Error) -> Self {
+        BatchError::Io(e)
+    }
+}
+
+/// Collect input texts from a source
+///
+/// The source may be:
+/// - a directory: every `*.txt` file in it, id is the file stem
+/// - a manifest (`.csv`/`.tsv`) with `id` and `path` columns; relative paths
+///   are resolved against the manifest's directory
+/// - a file-name pattern with `*`/`?` in the last component, e.g. `corpus/*.txt`
+/// - a single text file
+///
+/// Inputs are sorted by path except for manifests, which keep their order.
+///
+/// # Errors
+///
+/// Returns [`BatchError`] if the source cannot be read or matches nothing.
+pub fn collect_inputs(source: &str) -> Result<Vec<BatchInput>, BatchError> {
+    let path = Path::new(source);
+    let inputs = if path.is_dir() {
+        // Any case, so files saved as "S01.TXT" are not skipped
+        list_directory(path, |name| wildcard_match("*.txt", &name.to_lowercase()))?
+    } else if path.is_file() {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("csv" | "tsv" | "tab") => read_manifest(path)?,
+            _ => vec![input_from_path(path.to_path_buf())],
+        }
+    } else if source.contains(['*', '?']) {
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let pattern = path.file_name().and_then(|n| n.to_str()).unwrap_or("*");
+        list_directory(dir, |name| wildcard_match(pattern, name))?
+    } else {
+        return Err(BatchError::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("'{source}' does not exist"),
+        )));
+    };
+
+    if inputs.is_empty() {
+        return Err(BatchError::NoInputs(source.to_string()));
+    }
+    Ok(inputs)
+}
+
+fn input_from_path(path: PathBuf) -> BatchInput {
+    let id = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    BatchInput { id, path }
+}
+
+fn list_directory(dir: &Path, matches_name: impl Fn(&str) -> bool) -> Result<Vec<BatchInput>, BatchError> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let matches = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(&matches_name);
+        if matches && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths.into_iter().map(input_from_path).collect())
+}
+
+/// Match a file name against a pattern with `*` (any run) and `?` (one character)
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn read_manifest(path: &Path) -> Result<Vec<BatchInput>, BatchError> {
+    let separator = ExportFormat::from_path(path).separator();
+    let content = fs::read_to_string(path)?;
+    // Spreadsheet programs save UTF-8 CSV with a byte order mark
+    let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    let base = path.parent().unwrap_or(Path::new("."));
+    let mut records = split_records(content, separator).into_iter();
+
+    let (_, header) = records
+        .next()
+        .ok_or_else(|| BatchError::InvalidManifest("empty manifest".to_string()))?;
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.contains(&h.trim().to_lowercase().as_str()))
+    };
+    let (Some(id_col), Some(path_col)) = (column(&["id", "subject", "subject_id"]), column(&["path", "file"]))
+    else {
+        return Err(BatchError::InvalidManifest(
+            "header must contain 'id' and 'path' columns".to_string(),
+        ));
+    };
+
+    let mut inputs = Vec::new();
+    for (line_no, fields) in records {
+        let (Some(id), Some(file)) = (fields.get(id_col), fields.get(path_col)) else {
+            return Err(BatchError::InvalidManifest(format!(
+                "row at line {line_no} has too few columns"
+            )));
+        };
+        let file = Path::new(file.trim());
+        let path = if file.is_absolute() { file.to_path_buf() } else { base.join(file) };
+        inputs.push(BatchInput { id: id.trim().to_string(), path });
+    }
+    Ok(inputs)
+}
+
+/// Split delimited text into records as RFC 4180 does: a field starting with
+/// a double quote may contain separators, line breaks and doubled quotes
+///
+/// Blank lines are skipped; each record comes with the line it starts on.
+fn split_records(content: &str, separator: char) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let (mut quoted, mut in_quotes) = (false, false);
+    let (mut line, mut record_line) = (1, 1);
+    let mut chars = content.chars().peekable();
+
+    loop {
+        let next = chars.next();
+        let Some(c) = next.filter(|&c| c != '\n' || in_quotes) else {
+            // End of a record: an unquoted line break or the end of the text
+            if !fields.is_empty() || quoted || !field.trim().is_empty() {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut fields)));
+            }
+            if next.is_none() {
+                return records;
+            }
+            field.clear();
+            quoted = false;
+            line += 1;
+            record_line = line;
+            continue;
+        };
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() && !quoted => (quoted, in_quotes) = (true, true),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => {}
+            c if c == separator && !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            c => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+}
+
+/// Number of worker threads used by default: all available cores
+#[must_use]
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Apply `f` to every item on up to `threads` worker threads, preserving order
+///
+/// Items are handed out one at a time, so long and short texts balance
+/// across workers.
+///
+/// # Panics
+///
+/// Propagates a panic from `f`.
+pub fn parallel_map<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = threads.clamp(1, items.len().max(1));
+    if threads == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut indexed: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else { break };
+                        done.push((i, f(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("batch worker panicked"))
+            .collect()
+    });
+    indexed.sort_unstable_by_key(|(i, _)| *i);
+    indexed.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Metrics and classification of one input, or the error reading or
+/// analysing it
+pub type BatchOutcome = Result<(TextMetrics, ClassificationResult), Error>;
+
+/// Read, analyse and classify inputs on `threads` worker threads, preserving order
+///
+/// Texts rejected by [`TextAnalyzer::try_analyze`] give its input error.
+#[must_use]
+pub fn process_inputs(
+    inputs: &[BatchInput],
+    analyzer: &TextAnalyzer,
+    classifier: &Classifier,
+    threads: usize,
+) -> Vec<BatchOutcome> {
+    parallel_map(inputs, threads, |input| {
+        let text = fs::read_to_string(&input.path)?;
+        let metrics = analyzer.try_analyze(&text)?;
+        let result = classifier.classify(&metrics);
+        Ok((metrics, result))
+    })
+}
+
+/// All `TextMetrics` fields as (column name, formatted value), in declaration order
+#[must_use]
+pub fn metric_columns(m: &TextMetrics) -> Vec<(&'static str, String)> {
+    let f = |v: f64| format!("{v:.6}");
+    vec![
+        ("total_words", m.total_words.to_string()),
+        ("total_sentences", m.total_sentences.to_string()),
+        ("run_on_sentences", m.run_on_sentences.to_string()),
+        ("compound_sentences", m.compound_sentences.to_string()),
+        ("complex_sentences", m.complex_sentences.to_string()),
+        ("simple_sentences", m.simple_sentences.to_string()),
+        ("mixed_sentences", m.mixed_sentences.to_string()),
+        ("lexical_diversity_index", f(m.lexical_diversity_index)),
+        ("external_predicates", f(m.external_predicates)),
+        ("internal_predicates", f(m.internal_predicates)),
+        ("active_voice_verbs", f(m.active_voice_verbs)),
+        ("passive_voice_verbs", f(m.passive_voice_verbs)),
+        ("reflexive_verbs", f(m.reflexive_verbs)),
+        ("perfective_verbs", f(m.perfective_verbs)),
+        ("imperfective_verbs", f(m.imperfective_verbs)),
+        ("perfective_aspect_ratio", f(m.perfective_aspect_ratio)),
+        ("past_tense_verbs", f(m.past_tense_verbs)),
+        ("present_tense_verbs", f(m.present_tense_verbs)),
+        ("future_tense_verbs", f(m.future_tense_verbs)),
+        ("infinitives", f(m.infinitives)),
+        ("non_finite_verb_forms", f(m.non_finite_verb_forms)),
+        ("adjectives", f(m.adjectives)),
+        ("nouns", f(m.nouns)),
+        ("adverbs", f(m.adverbs)),
+        ("first_person_singular_pronouns", f(m.first_person_singular_pronouns)),
+        ("first_person_plural_pronouns", f(m.first_person_plural_pronouns)),
+        ("second_person_singular_pronouns", f(m.second_person_singular_pronouns)),
+        ("second_person_plural_pronouns", f(m.second_person_plural_pronouns)),
+        ("third_person_singular_pronouns", f(m.third_person_singular_pronouns)),
+        ("third_person_plural_pronouns", f(m.third_person_plural_pronouns)),
+        ("filler_words_index", f(m.filler_words_index)),
+        ("stop_words_index", f(m.stop_words_index)),
+        ("prepositions", f(m.prepositions)),
+        ("conjunctions", f(m.conjunctions)),
+        ("social_interaction_words", f(m.social_interaction_words)),
+        ("emotion_words", f(m.emotion_words)),
+        ("positive_emotion_share", f(m.positive_emotion_share)),
+        ("negative_emotion_share", f(m.negative_emotion_share)),
+        ("mean_valence", f(m.mean_valence)),
+        ("mean_arousal", f(m.mean_arousal)),
+        ("emotion_diversity", f(m.emotion_diversity)),
+        ("negation_density", f(m.negation_density)),
+        ("negated_internal_predicates", f(m.negated_internal_predicates)),
+        ("negated_emotion_words", f(m.negated_emotion_words)),
+        ("direct_speech_share", f(m.direct_speech_share)),
+        ("reported_speech_share", f(m.reported_speech_share)),
+        ("egocentrism_index", f(m.egocentrism_index)),
+    ]
+}
+
+/// Short machine-friendly label of a group used in the `predicted_group` column
+#[must_use]
+pub fn group_code(group: DiagnosticGroup) -> &'static str {
+    match group {
+        DiagnosticGroup::Healthy => "healthy",
+        DiagnosticGroup::Schizophrenia => "schizophrenia",
+        DiagnosticGroup::PersonalityDisorder => "personality_disorder",
+        DiagnosticGroup::BipolarDisorder => "bipolar_disorder",
+    }
+}
+
+/// Writes the batch table row by row
+pub struct BatchWriter<W: Write> {
+    writer: W,
+    format: ExportFormat,
+    /// Value of the trailing `lexicon` column, if recorded
+    lexicon: Option<String>,
+}
+
+impl<W: Write> BatchWriter<W> {
+    /// Create a writer and emit the header row
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the header cannot be written.
+    pub fn new(writer: W, format: ExportFormat) -> io::Result<Self> {
+        Self::create(writer, format, None)
+    }
+
+    /// Create a writer that records the lexicon in a trailing `lexicon` column
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the header cannot be written.
+    pub fn with_lexicon(writer: W, format: ExportFormat, lexicon: &LexiconInfo) -> io::Result<Self> {
+        Self::create(writer, format, Some(lexicon.to_string()))
+    }
+
+    fn create(writer: W, format: ExportFormat, lexicon: Option<String>) -> io::Result<Self> {
+        let mut batch = Self {
+            writer,
+            format,
+            lexicon,
+        };
+        let mut header = vec!["id".to_string()];
+        header.extend(
+            metric_columns(&TextMetrics::default())
+                .into_iter()
+                .map(|(name, _)| name.to_string()),
+        );
+        header.extend(DiagnosticGroup::ALL.iter().map(|&g| format!("p_{}", group_code(g))));
+        header.push("predicted_group".to_string());
+        header.push("confidence".to_string());
+        if batch.lexicon.is_some() {
+            header.push("lexicon".to_string());
+        }
+        batch.write_fields(&header)?;
+        Ok(batch)
+    }
+
+    /// Write the row for one analysed text
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the row cannot be written.
+    pub fn write_row(
+        &mut self,
+        id: &str,
+        metrics: &TextMetrics,
+        result: &ClassificationResult,
+    ) -> io::Result<()> {
+        let scores = &result.group_scores;
+        let mut row = vec![id.to_string()];
+        row.extend(metric_columns(metrics).into_iter().map(|(_, value)| value));
+        row.extend(
+            [
+                scores.healthy,
+                scores.schizophrenia,
+                scores.personality_disorder,
+                scores.bipolar_disorder,
+            ]
+            .iter()
+            .map(|p| format!("{p:.6}")),
+        );
+        row.push(group_code(result.primary_diagnosis).to_string());
+        row.push(format!("{:.6}", result.confidence));
+        row.extend(self.lexicon.clone());
+        self.write_fields(&row)
+    }
+
+    /// Flush and return the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if flushing fails.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_fields(&mut self, fields: &[String]) -> io::Result<()> {
+        let separator = self.format.separator();
+        let line: Vec<String> = fields.iter().map(|f| escape_field(f, separator)).collect();
+        writeln!(self.writer, "{}", line.join(&separator.to_string()))
+    }
+}
+
+/// Quote a field if it contains the separator, quotes or line breaks
+fn escape_field(field: &str, separator: char) -> String {
+    if field.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifier::Classifier;
+    use crate::lexicon::Lexicon;
+
+    #[test]
+    fn test_wildcard_and_quoting() {
+        assert!(wildcard_match("*.txt", "s01.txt"));
+        assert!(wildcard_match("s0?.txt", "s01.txt"));
+        assert!(wildcard_match("*_a*.txt", "x_b_a1.txt"));
+        assert!(!wildcard_match("*.txt", "s01.csv"));
+
+        assert_eq!(escape_field("a,b", ','), "\"a,b\"");
+        assert_eq!(escape_field("a,b", '\t'), "a,b");
+        let records = split_records("id,path\r\n\n1,\"x, \"\"y\"\"\",z\n2,\"a\nb\",c\"d\n\"\"", ',');
+        assert_eq!(records[0], (1, vec!["id".to_string(), "path".to_string()]));
+        assert_eq!(records[1].0, 3);
+        assert_eq!(records[1].1, ["1", "x, \"y\"", "z"]);
+        assert_eq!(records[2], (4, vec!["2".to_string(), "a\nb".to_string(), "c\"d".to_string()]));
+        assert_eq!(records[3], (6, vec![String::new()]));
+        assert_eq!(records.len(), 4);
+    }
+
+    #[test]
+    fn test_manifest_and_export() {
+        let dir = std::env::temp_dir().join(format!("mdpredict_batch_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), "Я помню как катался на велосипеде и упал.").unwrap();
+        fs::write(dir.join("b.txt"), "Мама склоняется надо мной.").unwrap();
+        fs::write(dir.join("C.TXT"), "Мы пошли гулять.").unwrap();
+        fs::write(dir.join("manifest.csv"), "\u{feff}path,id,group\nb.txt,S-2,x\n\"a.txt\",S-1,y\n").unwrap();
+
+        let from_dir = collect_inputs(dir.to_str().unwrap()).unwrap();
+        assert_eq!(from_dir.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(), ["C", "a", "b"]);
+        let pattern = dir.join("b*.txt");
+        assert_eq!(collect_inputs(pattern.to_str().unwrap()).unwrap().len(), 1);
+
+        let manifest = collect_inputs(dir.join("manifest.csv").to_str().unwrap()).unwrap();
+        assert_eq!(manifest[0].id, "S-2");
+        assert_eq!(manifest[1].path, dir.join("a.txt"));
+
+        let outcomes = process_inputs(&manifest, &TextAnalyzer::new(), &Classifier::new(), 2);
+        let lexicon = Lexicon::builtin().info();
+        let mut writer = BatchWriter::with_lexicon(Vec::new(), ExportFormat::Tsv, &lexicon).unwrap();
+        // "b.txt" has four words, fewer than the default minimum
+        assert!(matches!(outcomes[0], Err(Error::TooShort { words: 4, .. })));
+        for (input, outcome) in manifest.iter().zip(outcomes) {
+            if let Ok((metrics, result)) = outcome {
+                writer.write_row(&input.id, &metrics, &result).unwrap();
+            }
+        }
+        let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        let rows: Vec<Vec<&str>> = output.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], "id");
+        assert_eq!(rows[0].last(), Some(&"lexicon"));
+        assert_eq!(rows[1].last(), Some(&lexicon.to_string().as_str()));
+        assert!(rows.iter().all(|r| r.len() == rows[0].len()));
+        assert_eq!(rows[1][0], "S-1");
+        assert_eq!(rows[1][1], "8");
+    }
+
+    #[test]
+    fn test_parallel_batch_matches_sequential() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<TextAnalyzer>();
+        assert_send_sync::<Classifier>();
+
+        let texts = [
+            "Я помню как катался на велосипеде и упал.",
+            "Мама подходит ко мне и просит поднять руки.",
+            "Мы гуляли в парке. Было весело и немного грустно.",
+            "",
+            "Я думаю, что всё будет хорошо.",
+        ];
+        let analyzer = TextAnalyzer::new();
+        let parallel = analyzer.analyze_batch_with_threads(&texts, 3);
+        assert_eq!(parallel.len(), texts.len());
+        for (text, metrics) in texts.iter().zip(&parallel) {
+            let sequential = analyzer.analyze(text);
+            assert_eq!(metrics.total_words, sequential.total_words);
+            assert!((metrics.past_tense_verbs - sequential.past_tense_verbs).abs() < 1e-12);
+        }
+        assert_eq!(parallel_map(&[1, 2, 3, 4, 5], 8, |x| x * 10), [10, 20, 30, 40, 50]);
+    }
+}
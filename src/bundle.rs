@@ -0,0 +1,422 @@
+//! `OpenCorpora` dictionary loaded from memory
+//! The gzipped files of `rsmorphy_dict_ru::DICT_PATH` packed into one tar
+//! archive, for targets without the dictionary on disk (WebAssembly in a browser);
+//! the same loader reads the directory itself, reporting errors instead of panicking
+
+use flate2::read::GzDecoder;
+use rsmorphy::dawg::{CompletionDawg, Dawg};
+use rsmorphy::opencorpora::dictionary::{Dictionary, GrammemeMeta};
+use rsmorphy::opencorpora::grammeme::GrammemeReg;
+use rsmorphy::opencorpora::paradigm::ParadigmEntry;
+use rsmorphy::opencorpora::OpencorporaTagReg;
+use rsmorphy::{Grammeme, MorphAnalyzer};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Size of a tar header and of the blocks file data is padded to
+const BLOCK: usize = 512;
+
+/// Files of the dictionary besides the `prediction-suffixes-N.dawg.gz` series
+const DICTIONARY_FILES: [&str; 8] = [
+    "meta.json.gz",
+    "suffixes.json.gz",
+    "gramtab-opencorpora-int.json.gz",
+    "grammemes.json.gz",
+    "paradigms.array.gz",
+    "words.dawg.gz",
+    "p_t_given_w.intdawg.gz",
+    "prediction-prefixes.dawg.gz",
+];
+
+/// Error when reading a dictionary bundle
+#[derive(Debug)]
+pub enum BundleError {
+    /// Not a tar archive, or truncated
+    Archive(String),
+    /// The dictionary directory or one of its files cannot be read
+    Read { path: String, source: io::Error },
+    /// A dictionary file is not in the archive
+    Missing(String),
+    /// A dictionary file is not valid gzip
+    Gzip { file: String, source: io::Error },
+    /// A dictionary file is not valid JSON of the expected shape
+    Json { file: String, source: serde_json::Error },
+    /// A dictionary file decodes but its contents are inconsistent
+    Invalid { file: String, message: String },
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Archive(message) => write!(f, "invalid dictionary bundle: {message}"),
+            BundleError::Read { path, source } => write!(f, "cannot read '{path}': {source}"),
+            BundleError::Missing(file) => write!(f, "dictionary bundle has no '{file}'"),
+            BundleError::Gzip { file, source } => write!(f, "cannot decompress '{file}': {source}"),
+            BundleError::Json { file, source } => write!(f, "invalid JSON in '{file}': {source}"),
+            BundleError::Invalid { file, message } => write!(f, "invalid '{file}': {message}"),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BundleError::Read { source, .. } | BundleError::Gzip { source, .. } => Some(source),
+            BundleError::Json { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+fn invalid(file: &str, message: impl Into<String>) -> BundleError {
+    BundleError::Invalid {
+        file: file.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Text of a NUL-padded header field
+fn header_text(field: &[u8]) -> Result<&str, BundleError> {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    std::str::from_utf8(&field[..end]).map_err(|_| BundleError::Archive("file name is not UTF-8".to_string()))
+}
+
+/// Files of a tar archive by base name; directories and other entries are skipped
+fn tar_entries(bundle: &[u8]) -> Result<HashMap<&str, &[u8]>, BundleError> {
+    let mut entries = HashMap::new();
+    let mut offset = 0;
+    while let Some(header) = bundle.get(offset..offset + BLOCK) {
+        if header.iter().all(|&b| b == 0) {
+            return Ok(entries);
+        }
+        let name = header_text(&header[..100])?;
+        let size = header_text(&header[124..136])?.trim_matches(|c: char| c == ' ' || c == '\0');
+        let size = usize::from_str_radix(size, 8)
+            .map_err(|_| BundleError::Archive(format!("bad size of '{name}'")))?;
+        let start = offset + BLOCK;
+        let data = bundle
+            .get(start..start + size)
+            .ok_or_else(|| BundleError::Archive(format!("'{name}' is truncated")))?;
+        if matches!(header[156], b'0' | 0)
+            && let Some(base) = name.rsplit('/').next().filter(|base| !base.is_empty())
+        {
+            entries.insert(base, data);
+        }
+        offset = start + size.div_ceil(BLOCK) * BLOCK;
+    }
+    if entries.is_empty() {
+        Err(BundleError::Archive("not a tar archive".to_string()))
+    } else {
+        Ok(entries)
+    }
+}
+
+/// Dictionary files of a bundle, decompressed on request
+struct Files<'a> {
+    entries: HashMap<&'a str, &'a [u8]>,
+}
+
+impl Files<'_> {
+    /// Decompressed contents of one file
+    ///
+    /// Files are decompressed in full before rsmorphy parses them, so a
+    /// damaged archive is reported here instead of panicking inside rsmorphy.
+    fn read(&self, file: &str) -> Result<Vec<u8>, BundleError> {
+        let compressed = self.entries.get(file).ok_or_else(|| BundleError::Missing(file.to_string()))?;
+        let mut data = Vec::new();
+        GzDecoder::new(*compressed)
+            .read_to_end(&mut data)
+            .map_err(|source| BundleError::Gzip {
+                file: file.to_string(),
+                source,
+            })?;
+        Ok(data)
+    }
+
+    fn json<T: DeserializeOwned>(&self, file: &str) -> Result<T, BundleError> {
+        serde_json::from_slice(&self.read(file)?).map_err(|source| BundleError::Json {
+            file: file.to_string(),
+            source,
+        })
+    }
+}
+
+/// Paradigm tables: a count, then per paradigm its length and `u16` entries
+fn parse_paradigms(data: &[u8]) -> Result<Vec<Vec<ParadigmEntry>>, BundleError> {
+    const FILE: &str = "paradigms.array.gz";
+    let mut values = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]));
+    let mut next = || values.next().ok_or_else(|| invalid(FILE, "unexpected end of data"));
+    let count = next()?;
+    (0..count)
+        .map(|_| {
+            let len = next()?;
+            let paradigm = (0..len).map(|_| next()).collect::<Result<Vec<u16>, _>>()?;
+            if paradigm.len() % 3 == 0 {
+                Ok(ParadigmEntry::build(paradigm))
+            } else {
+                Err(invalid(FILE, "paradigm length is not a multiple of 3"))
+            }
+        })
+        .collect()
+}
+
+/// Grammeme hierarchy and incompatibilities, as rsmorphy computes them
+fn grammeme_metas(grammemes: &HashMap<Grammeme, GrammemeReg>) -> Result<HashMap<Grammeme, GrammemeMeta>, BundleError> {
+    const FILE: &str = "grammemes.json.gz";
+    let mut metas: HashMap<Grammeme, GrammemeMeta> = grammemes
+        .keys()
+        .enumerate()
+        .map(|(index, grammeme)| {
+            let meta = GrammemeMeta {
+                index,
+                ..GrammemeMeta::default()
+            };
+            (grammeme.clone(), meta)
+        })
+        .collect();
+    for (grammeme, reg) in grammemes {
+        if let Some(parent) = &reg.parent {
+            metas
+                .get_mut(parent)
+                .ok_or_else(|| invalid(FILE, format!("unknown parent of grammeme {:?}", reg.alias)))?
+                .children
+                .insert(grammeme.clone());
+        }
+    }
+
+    // Plural forms have no gender: "plur" is incompatible with GNdr and its children
+    let plur = Grammeme::new("plur");
+    let gender = Grammeme::new("GNdr");
+    let mut plural_incompatible: HashSet<Grammeme> = metas
+        .get(&gender)
+        .ok_or_else(|| invalid(FILE, "no GNdr grammeme"))?
+        .children
+        .clone();
+    plural_incompatible.insert(gender);
+
+    for (grammeme, meta) in &mut metas {
+        if *grammeme == plur {
+            meta.incompatible.extend(plural_incompatible.iter().filter(|g| *g != grammeme).cloned());
+        }
+        let children: Vec<Grammeme> = meta.children.iter().filter(|g| *g != grammeme).cloned().collect();
+        meta.incompatible.extend(children);
+    }
+    Ok(metas)
+}
+
+/// Load the rsmorphy analyzer from a dictionary bundle
+///
+/// The bundle is a tar archive of the files in `rsmorphy_dict_ru::DICT_PATH`
+/// (see [`write_bundle`]); the directory layout inside it does not matter.
+///
+/// # Errors
+///
+/// Returns [`BundleError`] if the archive is malformed or a file is missing
+/// or cannot be decoded.
+pub fn load_dictionary(bundle: &[u8]) -> Result<MorphAnalyzer, BundleError> {
+    load_files(&Files {
+        entries: tar_entries(bundle)?,
+    })
+}
+
+/// Load the rsmorphy analyzer from a dictionary directory such as
+/// `rsmorphy_dict_ru::DICT_PATH`
+///
+/// Unlike `rsmorphy::MorphAnalyzer::from_file`, a missing or damaged
+/// dictionary is reported as an error.
+///
+/// # Errors
+///
+/// Returns [`BundleError`] if the directory cannot be read or a file is
+/// missing or cannot be decoded.
+pub fn load_dictionary_dir(dir: impl AsRef<Path>) -> Result<MorphAnalyzer, BundleError> {
+    let mut contents = Vec::new();
+    for path in dictionary_files(dir.as_ref())? {
+        let data = std::fs::read(&path).map_err(|source| BundleError::Read {
+            path: path.display().to_string(),
+            source,
+        })?;
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+        contents.push((name, data));
+    }
+    load_files(&Files {
+        entries: contents.iter().map(|(name, data)| (name.as_str(), data.as_slice())).collect(),
+    })
+}
+
+/// Gzipped files of a dictionary directory, sorted by name
+fn dictionary_files(dir: &Path) -> Result<Vec<PathBuf>, BundleError> {
+    let read_error = |source| BundleError::Read {
+        path: dir.display().to_string(),
+        source,
+    };
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .and_then(|entries| entries.map(|entry| entry.map(|e| e.path())).collect())
+        .map_err(read_error)?;
+    paths.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "gz"));
+    paths.sort();
+    Ok(paths)
+}
+
+fn load_files(files: &Files<'_>) -> Result<MorphAnalyzer, BundleError> {
+    if let Some(file) = DICTIONARY_FILES.iter().find(|file| !files.entries.contains_key(*file)) {
+        return Err(BundleError::Missing((*file).to_string()));
+    }
+
+    let meta: Vec<(String, Value)> = files.json("meta.json.gz")?;
+    let meta: HashMap<String, Value> = meta.into_iter().collect();
+    let compile_options = &meta["compile_options"];
+    let paradigm_prefixes: Vec<String> = compile_options["paradigm_prefixes"]
+        .as_array()
+        .and_then(|prefixes| prefixes.iter().map(|p| p.as_str().map(ToString::to_string)).collect())
+        .ok_or_else(|| invalid("meta.json.gz", "no paradigm_prefixes"))?;
+    let max_suffix_length = meta
+        .get("prediction_options")
+        .unwrap_or(compile_options)["max_suffix_length"]
+        .as_u64()
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or_else(|| invalid("meta.json.gz", "no max_suffix_length"))?;
+    let paradigm_prefixes_rev = paradigm_prefixes
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, prefix)| u16::try_from(i).map(|i| (i, prefix.clone())))
+        .collect::<Result<_, _>>()
+        .map_err(|_| invalid("meta.json.gz", "too many paradigm prefixes"))?;
+
+    let gramtab: Vec<String> = files.json("gramtab-opencorpora-int.json.gz")?;
+    let grammemes: Vec<Vec<Value>> = files.json("grammemes.json.gz")?;
+    if grammemes.iter().any(|g| g.len() != 4) {
+        return Err(invalid("grammemes.json.gz", "expected 4 fields per grammeme"));
+    }
+    let grammemes: HashMap<Grammeme, GrammemeReg> = grammemes
+        .into_iter()
+        .map(GrammemeReg::from_json)
+        .map(|reg| (reg.name.clone(), reg))
+        .collect();
+    let grammeme_metas = grammeme_metas(&grammemes)?;
+
+    let prediction_suffixes_dawgs = (0..paradigm_prefixes.len())
+        .map(|i| Ok(CompletionDawg::from_reader(&mut files.read(&format!("prediction-suffixes-{i}.dawg.gz"))?.as_slice())))
+        .collect::<Result<_, BundleError>>()?;
+
+    let dictionary = Dictionary {
+        suffixes: files.json("suffixes.json.gz")?,
+        gramtab: gramtab.into_iter().map(OpencorporaTagReg::new).collect(),
+        paradigms: parse_paradigms(&files.read("paradigms.array.gz")?)?,
+        words: CompletionDawg::from_reader(&mut files.read("words.dawg.gz")?.as_slice()),
+        p_t_given_w: CompletionDawg::from_reader(&mut files.read("p_t_given_w.intdawg.gz")?.as_slice()),
+        prediction_prefixes: Dawg::from_reader(&mut files.read("prediction-prefixes.dawg.gz")?.as_slice()),
+        prediction_suffixes_dawgs,
+        meta,
+        grammemes,
+        grammeme_metas,
+        paradigm_prefixes,
+        paradigm_prefixes_rev,
+        prediction_splits: (1..=max_suffix_length).rev().collect(),
+        char_substitutes: BTreeMap::from([("е".to_string(), "ё".to_string())]),
+    };
+    Ok(MorphAnalyzer::new(dictionary))
+}
+
+/// Pack the dictionary files of `dir` into a bundle for [`load_dictionary`]
+///
+/// Writes an uncompressed tar archive; the files are gzipped already.
+///
+/// # Errors
+///
+/// Returns an I/O error if the directory cannot be read or writing fails.
+pub fn write_bundle(dir: impl AsRef<Path>, out: &mut impl Write) -> io::Result<()> {
+    let paths = dictionary_files(dir.as_ref()).map_err(|e| match e {
+        BundleError::Read { source, .. } => source,
+        other => io::Error::other(other.to_string()),
+    })?;
+    for path in paths {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        write_entry(out, name, &std::fs::read(&path)?)?;
+    }
+    out.write_all(&[0; 2 * BLOCK])
+}
+
+/// Write one file of a tar archive: a ustar header and the padded data
+fn write_entry(out: &mut impl Write, name: &str, data: &[u8]) -> io::Result<()> {
+    if name.len() >= 100 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("file name too long: {name}")));
+    }
+    let mut header = [0u8; BLOCK];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+    header[136..148].copy_from_slice(b"00000000000\0");
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    // The checksum is computed with its own field set to spaces
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+
+    out.write_all(&header)?;
+    out.write_all(data)?;
+    out.write_all(&vec![0; data.len().div_ceil(BLOCK) * BLOCK - data.len()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsmorphy::prelude::*;
+
+    fn bundle() -> Vec<u8> {
+        let mut bundle = Vec::new();
+        write_bundle(rsmorphy_dict_ru::DICT_PATH, &mut bundle).unwrap();
+        bundle
+    }
+
+    #[test]
+    fn test_load_dictionary() {
+        let analyzer = load_dictionary(&bundle()).unwrap();
+        let reference = MorphAnalyzer::from_file(rsmorphy_dict_ru::DICT_PATH);
+        for word in ["помню", "страшно", "мама", "катался", "велосипеде"] {
+            let parse = |a: &MorphAnalyzer| {
+                a.parse(word)
+                    .iter()
+                    .map(|p| p.lex.get_normal_form(a).into_owned())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(parse(&analyzer), parse(&reference), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_bundle_errors() {
+        assert!(matches!(load_dictionary(b"not a tar archive"), Err(BundleError::Archive(_))));
+        let missing = Path::new(rsmorphy_dict_ru::DICT_PATH).join("missing");
+        assert!(matches!(load_dictionary_dir(missing), Err(BundleError::Read { .. })));
+
+        let meta = std::fs::read(Path::new(rsmorphy_dict_ru::DICT_PATH).join("meta.json.gz")).unwrap();
+        let mut bundle = Vec::new();
+        write_entry(&mut bundle, "data/meta.json.gz", &meta).unwrap();
+        bundle.extend_from_slice(&[0; 2 * BLOCK]);
+        let error = load_dictionary(&bundle).unwrap_err();
+        assert!(matches!(&error, BundleError::Missing(file) if file == "suffixes.json.gz"), "{error}");
+
+        let mut bundle = Vec::new();
+        for file in DICTIONARY_FILES {
+            write_entry(&mut bundle, file, b"not gzip").unwrap();
+        }
+        bundle.extend_from_slice(&[0; 2 * BLOCK]);
+        let error = load_dictionary(&bundle).unwrap_err();
+        assert!(matches!(&error, BundleError::Gzip { file, .. } if file == "meta.json.gz"), "{error}");
+
+        let full = self::bundle();
+        assert!(matches!(load_dictionary(&full[..BLOCK + 10]), Err(BundleError::Archive(_))));
+    }
+}
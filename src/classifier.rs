@@ -6,8 +6,53 @@
 //! - Function 2 (30.6% variance): Past tense, Present tense verbs
 //! - Function 3 (15.0% variance): External predicates, Emotion words, Social interaction
 
-use crate::metrics::{ClassificationResult, DiagnosticGroup, GroupScores, TextMetrics};
+use crate::linalg;
+use crate::metrics::{
+    ClassificationExplanation, ClassificationResult, DiagnosticGroup, FeatureContribution,
+    FeatureDifference, GroupExplanation, GroupScores, TextMetrics,
+};
+use crate::model::{
+    ClassifierModel, GroupModel, MODEL_SCHEMA_VERSION, ModelError, ModelFormat, TrainingMetadata,
+};
+use crate::profile::ProfileComparison;
+use serde::{Deserialize, Serialize};
 use std::fmt::Write;
+use std::path::Path;
+
+/// Number of features used by the discriminant functions
+pub const FEATURE_COUNT: usize = 10;
+
+/// Feature names in the order used by coefficient vectors
+pub const FEATURE_NAMES: [&str; FEATURE_COUNT] = [
+    "log_volume",
+    "non_finite_verbs",
+    "first_person_sing",
+    "past_tense",
+    "present_tense",
+    "external_pred",
+    "internal_pred",
+    "emotion_words",
+    "social_interaction",
+    "lexical_diversity",
+];
+
+/// Human-readable (Russian) feature labels, in `FEATURE_NAMES` order
+const FEATURE_LABELS: [&str; FEATURE_COUNT] = [
+    "Объём текста (log)",
+    "Отглагольные формы",
+    "Местоимения 1л. ед.ч.",
+    "Глаголы прош. времени",
+    "Глаголы наст. времени",
+    "Внешние предикаты",
+    "Внутренние предикаты",
+    "Слова эмоций",
+    "Социальное взаимодействие",
+    "Лексическое разнообразие",
+];
+
+/// Relative ridge added to the pooled covariance diagonal so that features
+/// which never vary in the training set do not make it singular
+const COVARIANCE_RIDGE: f64 = 1e-6;
 
 /// Feature vector for LDA
 #[derive(Debug, Clone)]
@@ -50,6 +95,22 @@ impl FeatureVector {
             lexical_diversity: metrics.lexical_diversity_index,
         }
     }
+
+    /// Features as an array in `FEATURE_NAMES` order
+    fn to_array(&self) -> [f64; FEATURE_COUNT] {
+        [
+            self.log_volume,
+            self.non_finite_verbs,
+            self.first_person_sing,
+            self.past_tense,
+            self.present_tense,
+            self.external_pred,
+            self.internal_pred,
+            self.emotion_words,
+            self.social_interaction,
+            self.lexical_diversity,
+        ]
+    }
 }
 
 /// Discriminant function coefficients for a group
@@ -96,6 +157,39 @@ impl DiscriminantCoefficients {
             + self.constant
     }
 
+    /// Weights in `FEATURE_NAMES` order
+    fn weights(&self) -> [f64; FEATURE_COUNT] {
+        [
+            self.log_volume,
+            self.non_finite_verbs,
+            self.first_person_sing,
+            self.past_tense,
+            self.present_tense,
+            self.external_pred,
+            self.internal_pred,
+            self.emotion_words,
+            self.social_interaction,
+            self.lexical_diversity,
+        ]
+    }
+
+    /// Build coefficients from weights in `FEATURE_NAMES` order
+    fn from_weights(weights: &[f64], constant: f64) -> Self {
+        Self {
+            log_volume: weights[0],
+            non_finite_verbs: weights[1],
+            first_person_sing: weights[2],
+            past_tense: weights[3],
+            present_tense: weights[4],
+            external_pred: weights[5],
+            internal_pred: weights[6],
+            emotion_words: weights[7],
+            social_interaction: weights[8],
+            lexical_diversity: weights[9],
+            constant,
+        }
+    }
+
     /// Coefficients for Healthy group
     /// Derived from paper's reference values using LDA principles
     fn healthy() -> Self {
@@ -172,10 +266,67 @@ impl DiscriminantCoefficients {
     }
 }
 
+/// Error returned when a classifier cannot be fitted to training data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FitError {
+    /// No training samples were provided
+    EmptyTrainingSet,
+    /// Training data contains fewer than two diagnostic groups
+    SingleGroup,
+    /// Not enough samples to estimate the pooled covariance
+    InsufficientSamples { samples: usize, groups: usize },
+    /// The pooled within-class covariance matrix could not be inverted
+    SingularCovariance,
+}
+
+impl std::fmt::Display for FitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FitError::EmptyTrainingSet => write!(f, "training set is empty"),
+            FitError::SingleGroup => {
+                write!(f, "training set must contain at least two diagnostic groups")
+            }
+            FitError::InsufficientSamples { samples, groups } => write!(
+                f,
+                "{samples} samples are not enough to fit {groups} groups (need more samples than groups)"
+            ),
+            FitError::SingularCovariance => {
+                write!(f, "pooled within-class covariance matrix is singular")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FitError {}
+
+/// Canonical discriminant function, as reported in the paper (Function 1/2/3)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanonicalFunction {
+    /// Eigenvalue of `W^-1 B`
+    pub eigenvalue: f64,
+    /// Share of the between-group variance explained by this function (0.0 - 1.0)
+    pub explained_variance: f64,
+    /// Canonical correlation
+    pub canonical_correlation: f64,
+    /// Unstandardized coefficients in `FEATURE_NAMES` order
+    pub coefficients: Vec<f64>,
+    /// Constant term (centers scores on the grand mean)
+    pub constant: f64,
+    /// Mean function score of each group (group centroids)
+    pub group_centroids: Vec<(DiagnosticGroup, f64)>,
+}
+
 /// Classifier based on Linear Discriminant Analysis
+#[derive(Clone)]
 pub struct Classifier {
     /// LDA coefficients for each group
     coefficients: Vec<(DiagnosticGroup, DiscriminantCoefficients)>,
+    /// Prior probability of each group
+    priors: Vec<(DiagnosticGroup, f64)>,
+    /// Canonical discriminant functions (only available for fitted models)
+    canonical_functions: Vec<CanonicalFunction>,
+    /// Training data summary (only available for fitted models)
+    training: Option<TrainingMetadata>,
 }
 
 impl Classifier {
@@ -188,7 +339,311 @@ impl Classifier {
                 (DiagnosticGroup::PersonalityDisorder, DiscriminantCoefficients::personality_disorder()),
                 (DiagnosticGroup::BipolarDisorder, DiscriminantCoefficients::bipolar_disorder()),
             ],
+            priors: DiagnosticGroup::ALL.iter().map(|&g| (g, 0.25)).collect(),
+            canonical_functions: Vec::new(),
+            training: None,
+        }
+    }
+
+    /// Fit Fisher's linear discriminant functions to labelled training data
+    ///
+    /// Estimates class means, the pooled within-class covariance and priors
+    /// (from class frequencies), then derives one classification function per
+    /// group present in the data, plus the canonical discriminant functions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FitError`] if the data is empty, contains a single group,
+    /// has no more samples than groups, or the covariance cannot be inverted.
+    #[allow(clippy::cast_precision_loss, clippy::many_single_char_names)]
+    pub fn fit(samples: &[(TextMetrics, DiagnosticGroup)]) -> Result<Self, FitError> {
+        if samples.is_empty() {
+            return Err(FitError::EmptyTrainingSet);
+        }
+
+        // Group feature vectors by diagnosis, keeping the canonical group order
+        let groups: Vec<(DiagnosticGroup, Vec<[f64; FEATURE_COUNT]>)> = DiagnosticGroup::ALL
+            .iter()
+            .map(|&group| {
+                let rows = samples
+                    .iter()
+                    .filter(|(_, g)| *g == group)
+                    .map(|(m, _)| FeatureVector::from_metrics(m).to_array())
+                    .collect::<Vec<_>>();
+                (group, rows)
+            })
+            .filter(|(_, rows)| !rows.is_empty())
+            .collect();
+
+        let n = samples.len();
+        let g = groups.len();
+        if g < 2 {
+            return Err(FitError::SingleGroup);
+        }
+        if n <= g {
+            return Err(FitError::InsufficientSamples { samples: n, groups: g });
+        }
+
+        // Class means and grand mean
+        let means: Vec<Vec<f64>> = groups
+            .iter()
+            .map(|(_, rows)| {
+                let mut mean = vec![0.0; FEATURE_COUNT];
+                for row in rows {
+                    for (m, x) in mean.iter_mut().zip(row) {
+                        *m += x;
+                    }
+                }
+                for m in &mut mean {
+                    *m /= rows.len() as f64;
+                }
+                mean
+            })
+            .collect();
+        let mut grand_mean = vec![0.0; FEATURE_COUNT];
+        for ((_, rows), mean) in groups.iter().zip(&means) {
+            for (gm, m) in grand_mean.iter_mut().zip(mean) {
+                *gm += m * rows.len() as f64 / n as f64;
+            }
         }
+
+        // Within-class (W) and between-class (B) sums of squares and cross-products
+        let mut within = linalg::zeros(FEATURE_COUNT);
+        let mut between = linalg::zeros(FEATURE_COUNT);
+        for ((_, rows), mean) in groups.iter().zip(&means) {
+            for row in rows {
+                let d: Vec<f64> = row.iter().zip(mean).map(|(x, m)| x - m).collect();
+                linalg::add_outer(&mut within, &d, &d, 1.0);
+            }
+            let d: Vec<f64> = mean.iter().zip(&grand_mean).map(|(m, gm)| m - gm).collect();
+            linalg::add_outer(&mut between, &d, &d, rows.len() as f64);
+        }
+
+        let dof = (n - g) as f64;
+        let mean_diag = (0..FEATURE_COUNT).map(|i| within[i][i]).sum::<f64>() / FEATURE_COUNT as f64;
+        if mean_diag <= 0.0 {
+            return Err(FitError::SingularCovariance);
+        }
+        for (i, row) in within.iter_mut().enumerate() {
+            row[i] += COVARIANCE_RIDGE * mean_diag;
+        }
+
+        // Pooled covariance S = W / (n - g), inverted via Cholesky of W
+        let l = linalg::cholesky(&within).ok_or(FitError::SingularCovariance)?;
+        let l_inv = linalg::invert_lower(&l);
+        let within_inv = linalg::mat_mul(&linalg::transpose(&l_inv), &l_inv);
+        let pooled_inv: linalg::Matrix = within_inv
+            .iter()
+            .map(|row| row.iter().map(|v| v * dof).collect())
+            .collect();
+
+        // Fisher classification functions: w_k = S^-1 mu_k, c_k = -1/2 mu_k' S^-1 mu_k + ln(p_k)
+        let mut coefficients = Vec::with_capacity(g);
+        let mut priors = Vec::with_capacity(g);
+        for ((group, rows), mean) in groups.iter().zip(&means) {
+            let prior = rows.len() as f64 / n as f64;
+            let weights = linalg::mat_vec(&pooled_inv, mean);
+            let constant = -0.5 * linalg::dot(mean, &weights) + prior.ln();
+            coefficients.push((*group, DiscriminantCoefficients::from_weights(&weights, constant)));
+            priors.push((*group, prior));
+        }
+
+        let canonical_functions =
+            Self::fit_canonical_functions(&l_inv, &between, &groups, &means, &grand_mean, dof);
+
+        let trained_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+        let training = TrainingMetadata {
+            samples: n,
+            group_counts: groups.iter().map(|(group, rows)| (*group, rows.len())).collect(),
+            trained_at,
+            description: None,
+        };
+
+        Ok(Self {
+            coefficients,
+            priors,
+            canonical_functions,
+            training: Some(training),
+        })
+    }
+
+    /// Canonical functions: eigenvectors of `W^-1 B`, via the symmetric form `L^-1 B L^-T`
+    fn fit_canonical_functions(
+        l_inv: &linalg::Matrix,
+        between: &linalg::Matrix,
+        groups: &[(DiagnosticGroup, Vec<[f64; FEATURE_COUNT]>)],
+        means: &[Vec<f64>],
+        grand_mean: &[f64],
+        dof: f64,
+    ) -> Vec<CanonicalFunction> {
+        let symmetric = linalg::mat_mul(
+            &linalg::mat_mul(l_inv, between),
+            &linalg::transpose(l_inv),
+        );
+        let (eigenvalues, eigenvectors) = linalg::symmetric_eigen(&symmetric);
+        let function_count = (groups.len() - 1).min(FEATURE_COUNT);
+        let total: f64 = eigenvalues
+            .iter()
+            .take(function_count)
+            .map(|v| v.max(0.0))
+            .sum();
+        let l_inv_t = linalg::transpose(l_inv);
+
+        eigenvalues
+            .iter()
+            .zip(&eigenvectors)
+            .take(function_count)
+            .filter(|(value, _)| **value > 0.0)
+            .map(|(&eigenvalue, vector)| {
+                // Scale so that the pooled within-group variance of scores is 1
+                let mut coeffs: Vec<f64> = linalg::mat_vec(&l_inv_t, vector)
+                    .iter()
+                    .map(|a| a * dof.sqrt())
+                    .collect();
+                // Fix the arbitrary eigenvector sign: largest coefficient positive
+                let largest = coeffs.iter().copied().fold(0.0_f64, |acc, c| {
+                    if c.abs() > acc.abs() { c } else { acc }
+                });
+                if largest < 0.0 {
+                    for c in &mut coeffs {
+                        *c = -*c;
+                    }
+                }
+                let constant = -linalg::dot(&coeffs, grand_mean);
+                let group_centroids = groups
+                    .iter()
+                    .zip(means)
+                    .map(|((group, _), mean)| (*group, linalg::dot(&coeffs, mean) + constant))
+                    .collect();
+                CanonicalFunction {
+                    eigenvalue,
+                    explained_variance: if total > 0.0 { eigenvalue / total } else { 0.0 },
+                    canonical_correlation: (eigenvalue / (1.0 + eigenvalue)).sqrt(),
+                    coefficients: coeffs,
+                    constant,
+                    group_centroids,
+                }
+            })
+            .collect()
+    }
+
+    /// Names of the features used by the discriminant functions
+    #[must_use]
+    pub fn feature_names() -> &'static [&'static str] {
+        &FEATURE_NAMES
+    }
+
+    /// Prior probability of each group
+    #[must_use]
+    pub fn priors(&self) -> &[(DiagnosticGroup, f64)] {
+        &self.priors
+    }
+
+    /// Canonical discriminant functions, ordered by explained variance.
+    /// Empty for the built-in model, which has no training data.
+    #[must_use]
+    pub fn canonical_functions(&self) -> &[CanonicalFunction] {
+        &self.canonical_functions
+    }
+
+    /// Training data summary, `None` for the built-in model
+    #[must_use]
+    pub fn training_metadata(&self) -> Option<&TrainingMetadata> {
+        self.training.as_ref()
+    }
+
+    /// Attach a free-form description to the training metadata
+    pub fn set_description(&mut self, description: impl Into<String>) {
+        self.training
+            .get_or_insert_with(TrainingMetadata::default)
+            .description = Some(description.into());
+    }
+
+    /// Export the classifier as a serializable model
+    #[must_use]
+    pub fn to_model(&self) -> ClassifierModel {
+        let groups = self
+            .coefficients
+            .iter()
+            .map(|(group, coeffs)| GroupModel {
+                group: *group,
+                coefficients: coeffs.weights().to_vec(),
+                constant: coeffs.constant,
+                prior: self
+                    .priors
+                    .iter()
+                    .find(|(g, _)| g == group)
+                    .map_or(0.0, |(_, p)| *p),
+            })
+            .collect();
+
+        ClassifierModel {
+            schema_version: MODEL_SCHEMA_VERSION,
+            library_version: env!("CARGO_PKG_VERSION").to_string(),
+            features: FEATURE_NAMES.iter().map(ToString::to_string).collect(),
+            groups,
+            canonical_functions: self.canonical_functions.clone(),
+            training: self.training.clone(),
+        }
+    }
+
+    /// Build a classifier from a serialized model
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError`] if the schema version or features are incompatible.
+    pub fn from_model(model: ClassifierModel) -> Result<Self, ModelError> {
+        model.validate(&FEATURE_NAMES)?;
+
+        Ok(Self {
+            coefficients: model
+                .groups
+                .iter()
+                .map(|g| {
+                    (g.group, DiscriminantCoefficients::from_weights(&g.coefficients, g.constant))
+                })
+                .collect(),
+            priors: model.groups.iter().map(|g| (g.group, g.prior)).collect(),
+            canonical_functions: model.canonical_functions,
+            training: model.training,
+        })
+    }
+
+    /// Save the model to a `.json` or `.bin` file
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError`] if the extension is unknown or writing fails.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ModelError> {
+        let path = path.as_ref();
+        let format = ModelFormat::from_path(path)
+            .ok_or_else(|| ModelError::UnknownFormat(path.display().to_string()))?;
+        self.to_model().save(path, format)
+    }
+
+    /// Load a model from a `.json` or `.bin` file
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError`] if the file cannot be read or is incompatible.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ModelError> {
+        let path = path.as_ref();
+        let format = ModelFormat::from_path(path)
+            .ok_or_else(|| ModelError::UnknownFormat(path.display().to_string()))?;
+        Self::from_model(ClassifierModel::load(path, format)?)
+    }
+
+    /// Canonical function scores for the given metrics
+    #[must_use]
+    pub fn canonical_scores(&self, metrics: &TextMetrics) -> Vec<f64> {
+        let features = FeatureVector::from_metrics(metrics).to_array();
+        self.canonical_functions
+            .iter()
+            .map(|f| linalg::dot(&f.coefficients, &features) + f.constant)
+            .collect()
     }
 
     /// Classify text based on computed metrics using LDA
@@ -204,10 +659,111 @@ impl Classifier {
         }
     }
 
+    /// Explain a classification: per-group feature contributions and the features
+    /// that separate the winning group from the runner-up
+    #[must_use]
+    pub fn explain(&self, metrics: &TextMetrics) -> ClassificationExplanation {
+        let feature_vector = FeatureVector::from_metrics(metrics);
+        let features = feature_vector.to_array();
+
+        let mut groups: Vec<GroupExplanation> = self
+            .coefficients
+            .iter()
+            .map(|(group, coeffs)| {
+                let mut contributions: Vec<FeatureContribution> = FEATURE_NAMES
+                    .iter()
+                    .zip(coeffs.weights())
+                    .zip(features)
+                    .map(|((name, coefficient), value)| FeatureContribution {
+                        feature: (*name).to_string(),
+                        value,
+                        coefficient,
+                        contribution: coefficient * value,
+                    })
+                    .collect();
+                contributions.sort_by(|a, b| b.contribution.abs().total_cmp(&a.contribution.abs()));
+                GroupExplanation {
+                    group: *group,
+                    score: coeffs.score(&feature_vector),
+                    constant: coeffs.constant,
+                    contributions,
+                }
+            })
+            .collect();
+        groups.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let winner = groups.first().map_or(DiagnosticGroup::Healthy, |g| g.group);
+        let runner_up = groups.get(1).map(|g| g.group);
+
+        let mut differences: Vec<FeatureDifference> = Vec::new();
+        let mut constant_difference = 0.0;
+        if let (Some(first), Some(second)) = (groups.first(), groups.get(1)) {
+            constant_difference = first.constant - second.constant;
+            differences = first
+                .contributions
+                .iter()
+                .filter_map(|c| {
+                    let other = second.contributions.iter().find(|o| o.feature == c.feature)?;
+                    Some(FeatureDifference {
+                        feature: c.feature.clone(),
+                        value: c.value,
+                        difference: c.contribution - other.contribution,
+                    })
+                })
+                .collect();
+            differences.sort_by(|a, b| b.difference.abs().total_cmp(&a.difference.abs()));
+        }
+        let (toward_winner, toward_runner_up) =
+            differences.into_iter().partition(|d| d.difference >= 0.0);
+
+        ClassificationExplanation {
+            winner,
+            runner_up,
+            groups,
+            toward_winner,
+            toward_runner_up,
+            constant_difference,
+        }
+    }
+
+    /// Append the features that separated the winner from the runner-up
+    fn write_explanation(&self, report: &mut String, metrics: &TextMetrics) {
+        let explanation = self.explain(metrics);
+        if let Some(runner_up) = explanation.runner_up {
+            let _ = writeln!(
+                report,
+                "\n--- Признаки, определившие результат ({} vs {}) ---",
+                explanation.winner, runner_up
+            );
+            report.push_str("В пользу результата:\n");
+            for d in explanation.toward_winner.iter().take(3) {
+                let _ = writeln!(report, "  {}: {:+.2}", Self::feature_label(&d.feature), d.difference);
+            }
+            report.push_str("В пользу альтернативы:\n");
+            for d in explanation.toward_runner_up.iter().take(3) {
+                let _ = writeln!(report, "  {}: {:+.2}", Self::feature_label(&d.feature), d.difference);
+            }
+        }
+    }
+
+    /// Human-readable label for a feature name
+    fn feature_label(feature: &str) -> &str {
+        FEATURE_NAMES
+            .iter()
+            .position(|name| *name == feature)
+            .map_or(feature, |i| FEATURE_LABELS[i])
+    }
+
     /// Compute LDA discriminant scores for each group
     fn compute_lda_scores(&self, metrics: &TextMetrics) -> GroupScores {
         let features = FeatureVector::from_metrics(metrics);
-        let mut raw_scores = GroupScores::default();
+        // Groups without a discriminant function get zero probability
+        let mut raw_scores = GroupScores {
+            healthy: f64::NEG_INFINITY,
+            schizophrenia: f64::NEG_INFINITY,
+            personality_disorder: f64::NEG_INFINITY,
+            bipolar_disorder: f64::NEG_INFINITY,
+        };
 
         // Calculate discriminant score for each group
         for (group, coeffs) in &self.coefficients {
@@ -271,6 +827,7 @@ impl Classifier {
 
     /// Get detailed classification report
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub fn get_detailed_report(
         &self,
         metrics: &TextMetrics,
@@ -294,7 +851,8 @@ impl Classifier {
         let _ = writeln!(report, "Простые: {}", metrics.simple_sentences);
         let _ = writeln!(report, "Сложносочинённые: {}", metrics.compound_sentences);
         let _ = writeln!(report, "Сложноподчинённые: {}", metrics.complex_sentences);
-        let _ = writeln!(report, "Бессоюзные: {}\n", metrics.run_on_sentences);
+        let _ = writeln!(report, "Бессоюзные: {}", metrics.run_on_sentences);
+        let _ = writeln!(report, "Сложные с разными видами связи: {}\n", metrics.mixed_sentences);
 
         // Key discriminant features
         report.push_str("--- Ключевые диагностические показатели ---\n");
@@ -324,11 +882,31 @@ impl Classifier {
             metrics.social_interaction_words
         );
         let _ = writeln!(report, "Слова эмоций: {:.1}%", metrics.emotion_words);
+        let _ = writeln!(
+            report,
+            "Эмоции: положительные {:.1}%, отрицательные {:.1}%, валентность {:+.2}, разнообразие {:.2}",
+            metrics.positive_emotion_share,
+            metrics.negative_emotion_share,
+            metrics.mean_valence,
+            metrics.emotion_diversity
+        );
+        let _ = writeln!(
+            report,
+            "Отрицания: {:.1}%, под отрицанием внутренние предикаты {:.1}%, слова эмоций {:.1}%",
+            metrics.negation_density,
+            metrics.negated_internal_predicates,
+            metrics.negated_emotion_words
+        );
         let _ = writeln!(
             report,
             "Местоимения 1-го лица ед.ч.: {:.1}%",
             metrics.first_person_singular_pronouns
         );
+        let _ = writeln!(
+            report,
+            "Чужая речь: прямая {:.1}%, косвенная {:.1}%",
+            metrics.direct_speech_share, metrics.reported_speech_share
+        );
         let _ = writeln!(
             report,
             "Отглагольные формы: {:.1}%",
@@ -375,6 +953,12 @@ impl Classifier {
             result.group_scores.bipolar_disorder * 100.0
         );
 
+        self.write_explanation(&mut report, metrics);
+
+        // Interpretable profile against the paper's reference groups
+        report.push_str("\n--- Профиль относительно референтных групп (z-оценки, Таблица 2) ---\n");
+        report.push_str(&ProfileComparison::compare(metrics).render_table());
+
         report.push_str("\n=== ВАЖНОЕ ПРИМЕЧАНИЕ ===\n");
         report.push_str("Данный анализ носит исследовательский характер и НЕ является\n");
         report.push_str("медицинским диагнозом. Для постановки диагноза необходимо\n");
@@ -394,6 +978,176 @@ impl Default for Classifier {
 mod tests {
     use super::*;
     use crate::analyzer::TextAnalyzer;
+    use crate::metrics::ReferenceValues;
+
+    /// Deterministic synthetic corpus drawn around the paper's reference values
+    fn synthetic_corpus(per_group: usize) -> Vec<(TextMetrics, DiagnosticGroup)> {
+        let mut state: u64 = 42;
+        let mut noise = move || {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            #[allow(clippy::cast_precision_loss)]
+            let unit = (state >> 11) as f64 / (1u64 << 53) as f64;
+            (unit - 0.5) * 0.6
+        };
+
+        let mut corpus = Vec::new();
+        for reference in ReferenceValues::all() {
+            let (mean, sd) = (&reference.metrics, &reference.std_dev);
+            for _ in 0..per_group {
+                let mut m = mean.clone();
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+                {
+                    m.total_words = (mean.total_words as f64 + sd.total_words as f64 * noise()).max(1.0) as usize;
+                }
+                m.non_finite_verb_forms += sd.non_finite_verb_forms * noise();
+                m.first_person_singular_pronouns += sd.first_person_singular_pronouns * noise();
+                m.past_tense_verbs += sd.past_tense_verbs * noise();
+                m.present_tense_verbs += sd.present_tense_verbs * noise();
+                m.external_predicates += sd.external_predicates * noise();
+                m.internal_predicates += sd.internal_predicates * noise();
+                m.emotion_words += sd.emotion_words * noise();
+                m.social_interaction_words += sd.social_interaction_words * noise();
+                m.lexical_diversity_index += sd.lexical_diversity_index * noise();
+                corpus.push((m, reference.group));
+            }
+        }
+        corpus
+    }
+
+    #[test]
+    fn test_fit_recovers_group_means() {
+        let corpus = synthetic_corpus(40);
+        let classifier = Classifier::fit(&corpus).unwrap();
+
+        for reference in ReferenceValues::all() {
+            let result = classifier.classify(&reference.metrics);
+            assert_eq!(result.primary_diagnosis, reference.group);
+        }
+
+        let priors: f64 = classifier.priors().iter().map(|(_, p)| p).sum();
+        assert!((priors - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_canonical_functions() {
+        let corpus = synthetic_corpus(40);
+        let classifier = Classifier::fit(&corpus).unwrap();
+
+        let functions = classifier.canonical_functions();
+        assert_eq!(functions.len(), 3);
+        let explained: f64 = functions.iter().map(|f| f.explained_variance).sum();
+        assert!((explained - 1.0).abs() < 1e-9);
+        assert!(functions[0].explained_variance >= functions[1].explained_variance);
+        assert_eq!(functions[0].coefficients.len(), Classifier::feature_names().len());
+        assert_eq!(classifier.canonical_scores(&corpus[0].0).len(), 3);
+    }
+
+    #[test]
+    fn test_explanation_matches_scores() {
+        let classifier = Classifier::new();
+        let metrics = ReferenceValues::schizophrenia().metrics;
+        let result = classifier.classify(&metrics);
+        let explanation = classifier.explain(&metrics);
+
+        assert_eq!(explanation.winner, result.primary_diagnosis);
+        assert_eq!(explanation.groups.len(), 4);
+        for group in &explanation.groups {
+            let total: f64 =
+                group.contributions.iter().map(|c| c.contribution).sum::<f64>() + group.constant;
+            assert!((total - group.score).abs() < 1e-9);
+            assert!(group.contributions.windows(2).all(|w| {
+                w[0].contribution.abs() >= w[1].contribution.abs()
+            }));
+        }
+
+        // Score gap is fully accounted for by feature and constant differences
+        let gap = explanation.groups[0].score - explanation.groups[1].score;
+        let explained: f64 = explanation
+            .toward_winner
+            .iter()
+            .chain(&explanation.toward_runner_up)
+            .map(|d| d.difference)
+            .sum::<f64>()
+            + explanation.constant_difference;
+        assert!((gap - explained).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_model_roundtrip() {
+        let corpus = synthetic_corpus(10);
+        let mut classifier = Classifier::fit(&corpus).unwrap();
+        classifier.set_description("synthetic");
+
+        let dir = std::env::temp_dir();
+        for ext in ["json", "bin"] {
+            let path = dir.join(format!("mdpredict_model_test_{}.{ext}", std::process::id()));
+            classifier.save(&path).unwrap();
+            let loaded = Classifier::load(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+
+            let original = classifier.classify(&corpus[0].0);
+            let restored = loaded.classify(&corpus[0].0);
+            assert_eq!(original.primary_diagnosis, restored.primary_diagnosis);
+            assert!((original.confidence - restored.confidence).abs() < 1e-12);
+            assert_eq!(loaded.canonical_functions().len(), 3);
+            let training = loaded.training_metadata().unwrap();
+            assert_eq!(training.samples, 40);
+            assert_eq!(training.description.as_deref(), Some("synthetic"));
+        }
+    }
+
+    #[test]
+    fn test_model_version_check() {
+        let mut model = Classifier::new().to_model();
+        model.schema_version = MODEL_SCHEMA_VERSION + 1;
+        assert!(matches!(
+            Classifier::from_model(model),
+            Err(ModelError::UnsupportedSchemaVersion { .. })
+        ));
+
+        let mut model = Classifier::new().to_model();
+        model.features.pop();
+        assert!(matches!(
+            Classifier::from_model(model),
+            Err(ModelError::FeatureMismatch { .. })
+        ));
+
+        let mut model = Classifier::new().to_model();
+        model.groups[1].group = model.groups[0].group;
+        assert!(matches!(
+            Classifier::from_model(model),
+            Err(ModelError::Invalid(_))
+        ));
+
+        let mut model = Classifier::new().to_model();
+        model.groups[0].coefficients[0] = f64::NAN;
+        assert!(matches!(
+            Classifier::from_model(model),
+            Err(ModelError::Invalid(_))
+        ));
+
+        let mut model = Classifier::new().to_model();
+        model.groups[2].constant = f64::INFINITY;
+        assert!(matches!(
+            Classifier::from_model(model),
+            Err(ModelError::Invalid(_))
+        ));
+
+        let mut model = Classifier::new().to_model();
+        model.groups[3].prior = f64::NAN;
+        assert!(matches!(
+            Classifier::from_model(model),
+            Err(ModelError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_fit_errors() {
+        assert_eq!(Classifier::fit(&[]).err(), Some(FitError::EmptyTrainingSet));
+
+        let single = vec![(TextMetrics::new(), DiagnosticGroup::Healthy); 5];
+        assert_eq!(Classifier::fit(&single).err(), Some(FitError::SingleGroup));
+    }
 
     #[test]
     fn test_classification() {
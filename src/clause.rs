@@ -0,0 +1,257 @@
+//! Clause segmentation of a sentence
+//! Finds the grammatical bases (subject + finite predicate), attaches every
+//! clause to its connector and derives the sentence type from the kinds of
+//! links between clauses, so mixed sentences are recognized as such
+
+use crate::analytic;
+use crate::backend::{Case, PartOfSpeech, WordAnalysis};
+use crate::dictionaries::{PREPOSITIONS, SUBORDINATING_CONJUNCTIONS};
+use crate::phrases::{PhraseKind, PhraseMatcher};
+use crate::sentence::SentenceType;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Coordinating conjunctions that can join clauses; particles such as "же"
+/// or "тоже" are listed as conjunctions elsewhere but never open a clause
+const CLAUSE_COORDINATORS: [&str; 8] = ["и", "да", "а", "но", "однако", "зато", "или", "либо"];
+
+/// Personal pronouns in the nominative case
+const NOMINATIVE_PRONOUNS: [&str; 8] = ["я", "ты", "он", "она", "оно", "мы", "вы", "они"];
+
+/// How a clause is attached to the rest of the sentence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClauseConnector {
+    /// No connector: the first clause, a main clause, or an asyndetic link
+    None,
+    /// Coordinating conjunction ("и", "а", "но")
+    Coordinating,
+    /// Subordinating conjunction ("что", "когда", "потому что")
+    Subordinating,
+    /// Relative pronoun "который"
+    Relative,
+}
+
+impl ClauseConnector {
+    /// Whether the clause is subordinate to another one
+    #[must_use]
+    pub fn is_subordinate(self) -> bool {
+        matches!(self, ClauseConnector::Subordinating | ClauseConnector::Relative)
+    }
+}
+
+/// One clause of a sentence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clause {
+    pub connector: ClauseConnector,
+    /// The conjunction or relative word introducing the clause
+    pub conjunction: Option<String>,
+    /// Indices of the clause's words in the sentence, in order; not contiguous
+    /// when another clause is embedded ("дом, который построил Джек, стоит")
+    pub words: Vec<usize>,
+    /// Index of the subject, if one was found
+    pub subject: Option<usize>,
+    /// Indices of the predicates (homogeneous predicates share one clause)
+    pub predicates: Vec<usize>,
+}
+
+impl Clause {
+    fn has_predicate(&self) -> bool {
+        !self.predicates.is_empty()
+    }
+
+    fn absorb(&mut self, other: Clause) {
+        self.words.extend(other.words);
+        self.words.sort_unstable();
+        self.predicates.extend(other.predicates);
+        self.predicates.sort_unstable();
+        self.subject = self.subject.or(other.subject);
+    }
+}
+
+/// A run of words between punctuation marks or connectors
+struct Chunk {
+    words: Range<usize>,
+    connector: ClauseConnector,
+    conjunction: Option<String>,
+}
+
+/// Split a sentence into clauses
+///
+/// `words` are the lowercase words of the sentence with their `analyses`;
+/// `punctuation[i]` tells whether a comma, semicolon, colon, dash or bracket
+/// precedes word `i`. `phrases` recognizes compound conjunctions and keeps
+/// fillers such as "как бы" from being read as connectors.
+#[must_use]
+pub fn segment(
+    words: &[String],
+    analyses: &[WordAnalysis],
+    punctuation: &[bool],
+    phrases: &PhraseMatcher,
+) -> Vec<Clause> {
+    let mut clauses: Vec<Clause> = Vec::new();
+
+    for chunk in chunks(words, punctuation, phrases) {
+        let predicates: Vec<usize> = chunk.words.clone().filter(|&i| is_predicate(&analyses[i])).collect();
+        let subject = find_subject(words, analyses, &chunk.words, predicates.first().copied());
+        let clause = Clause {
+            connector: chunk.connector,
+            conjunction: chunk.conjunction,
+            words: chunk.words.collect(),
+            subject,
+            predicates,
+        };
+        // A clause still waiting for its predicate ("мама, папа и я пошли")
+        let open = clauses.iter().rposition(|c| !c.has_predicate());
+
+        if clauses.is_empty() || clause.connector.is_subordinate() {
+            clauses.push(clause);
+        } else if let Some(open) = open {
+            clauses[open].absorb(clause);
+        } else if clause.has_predicate() && clause.subject.is_some() {
+            clauses.push(clause);
+        } else if let Some(last) = clauses.last_mut() {
+            // Homogeneous predicate or a fragment of the previous clause
+            last.absorb(clause);
+        }
+    }
+
+    // Connectors without a predicate ("белый, как снег") do not form a clause
+    let mut merged: Vec<Clause> = Vec::with_capacity(clauses.len());
+    let mut pending: Option<Clause> = None;
+    for mut clause in clauses {
+        if let Some(fragment) = pending.take() {
+            clause.absorb(fragment);
+        }
+        if clause.has_predicate() {
+            merged.push(clause);
+        } else if let Some(last) = merged.last_mut() {
+            last.absorb(clause);
+        } else {
+            pending = Some(clause);
+        }
+    }
+    if let Some(fragment) = pending {
+        merged.push(fragment);
+    }
+    merged
+}
+
+/// Sentence type from the links between clauses
+///
+/// Two or more kinds of links (coordinating, subordinating, asyndetic) make
+/// a mixed sentence. A connector-less clause right after a subordinate one
+/// is the main clause resuming ("когда я пришёл, мама спала"), not an
+/// asyndetic link.
+#[must_use]
+pub fn sentence_type(clauses: &[Clause]) -> SentenceType {
+    if clauses.len() <= 1 {
+        return SentenceType::Simple;
+    }
+    let (mut coordinating, mut subordinating, mut asyndetic) = (false, false, false);
+    for (i, clause) in clauses.iter().enumerate() {
+        match clause.connector {
+            ClauseConnector::Subordinating | ClauseConnector::Relative => subordinating = true,
+            ClauseConnector::Coordinating if i > 0 => coordinating = true,
+            ClauseConnector::None if i > 0 && !clauses[i - 1].connector.is_subordinate() => {
+                asyndetic = true;
+            }
+            _ => {}
+        }
+    }
+    match (coordinating, subordinating, asyndetic) {
+        (true, false, false) => SentenceType::Compound,
+        (false, true, false) => SentenceType::Complex,
+        (false, false, _) => SentenceType::RunOn,
+        _ => SentenceType::Mixed,
+    }
+}
+
+/// Finite verbs and short passive participles ("дом построен")
+fn is_predicate(analysis: &WordAnalysis) -> bool {
+    analysis.is_finite_verb()
+        || (analysis.is_passive_participle() && analytic::looks_like_short_participle(&analysis.word))
+}
+
+/// Connector starting at each word: kind, normalized text and length in words
+fn connectors(words: &[String], phrases: &PhraseMatcher) -> Vec<Option<(ClauseConnector, String, usize)>> {
+    let mut found = vec![None; words.len()];
+    let mut covered = vec![false; words.len()];
+    for phrase in phrases.find(words) {
+        let kind = match phrase.kind {
+            PhraseKind::SubordinatingConjunction => Some(ClauseConnector::Subordinating),
+            PhraseKind::CoordinatingConjunction => Some(ClauseConnector::Coordinating),
+            PhraseKind::Filler => None,
+        };
+        if let Some(kind) = kind {
+            found[phrase.start] = Some((kind, phrase.phrase, phrase.len));
+        }
+        covered[phrase.start..phrase.start + phrase.len].fill(true);
+    }
+    for (i, word) in words.iter().enumerate() {
+        if covered[i] {
+            continue;
+        }
+        let kind = if word.starts_with("котор") {
+            ClauseConnector::Relative
+        } else if SUBORDINATING_CONJUNCTIONS.contains(word.as_str()) {
+            ClauseConnector::Subordinating
+        } else if CLAUSE_COORDINATORS.contains(&word.as_str()) {
+            ClauseConnector::Coordinating
+        } else {
+            continue;
+        };
+        found[i] = Some((kind, word.clone(), 1));
+    }
+    found
+}
+
+/// Cut the sentence at punctuation marks and before connectors
+fn chunks(words: &[String], punctuation: &[bool], phrases: &PhraseMatcher) -> Vec<Chunk> {
+    let connectors = connectors(words, phrases);
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let starts_chunk = chunks.is_empty() || punctuation[i] || connectors[i].is_some();
+        if starts_chunk {
+            let (connector, conjunction) = match &connectors[i] {
+                Some((kind, text, _)) => (*kind, Some(text.clone())),
+                None => (ClauseConnector::None, None),
+            };
+            chunks.push(Chunk {
+                words: i..i,
+                connector,
+                conjunction,
+            });
+        }
+        let len = connectors[i].as_ref().map_or(1, |(_, _, len)| *len);
+        if let Some(chunk) = chunks.last_mut() {
+            chunk.words.end = i + len;
+        }
+        i += len;
+    }
+    chunks
+}
+
+/// Subject of a chunk: a nominative pronoun or noun not governed by a preposition
+///
+/// Nouns of unknown case (rule-based backend) count only before the predicate.
+fn find_subject(
+    words: &[String],
+    analyses: &[WordAnalysis],
+    chunk: &Range<usize>,
+    predicate: Option<usize>,
+) -> Option<usize> {
+    chunk.clone().find(|&i| {
+        let word = &analyses[i];
+        if NOMINATIVE_PRONOUNS.contains(&words[i].as_str()) {
+            return true;
+        }
+        let after_preposition = i > 0 && PREPOSITIONS.contains(words[i - 1].as_str());
+        word.pos == PartOfSpeech::Noun
+            && !after_preposition
+            && match word.case {
+                Some(case) => case == Case::Nominative,
+                None => predicate.is_some_and(|p| i < p),
+            }
+    })
+}
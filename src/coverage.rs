@@ -0,0 +1,207 @@
+//! Lexicon coverage comparison
+//! Counts the tokens every semantic category matches under two analyzers,
+//! e.g. surface-form versus lemma matching of the same lexicon, to show what
+//! a change in matching or in the word lists gained
+
+use crate::analyzer::{TextAnalyzer, TokenAnnotation};
+use crate::backend::PredicateType;
+use crate::batch::{default_threads, parallel_map};
+use crate::lexicon::LexiconCategory;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Number of most frequent gained words kept per category
+const TOP_GAINED: usize = 5;
+
+/// Token counts of one category under the baseline and candidate analyzers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryCoverage {
+    pub category: LexiconCategory,
+    /// Tokens matched by the baseline analyzer
+    pub baseline: usize,
+    /// Tokens matched by the candidate analyzer
+    pub candidate: usize,
+    /// Tokens matched only by the candidate
+    pub gained: usize,
+    /// Tokens matched only by the baseline
+    pub lost: usize,
+    /// Most frequent gained words (lemma if known) with their counts
+    pub top_gained: Vec<(String, usize)>,
+}
+
+/// Per-category coverage of a corpus under two analyzers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub texts: usize,
+    pub tokens: usize,
+    pub categories: Vec<CategoryCoverage>,
+}
+
+/// Whether a token was assigned to a lexicon category
+fn in_category(token: &TokenAnnotation, category: LexiconCategory) -> bool {
+    let a = &token.analysis;
+    match category {
+        LexiconCategory::EmotionWords => a.is_emotion_word,
+        LexiconCategory::InternalPredicates => a.predicate_type == Some(PredicateType::Internal),
+        LexiconCategory::ExternalPredicates => a.predicate_type == Some(PredicateType::External),
+        LexiconCategory::FillerWords => a.is_filler,
+        LexiconCategory::StopWords => a.is_stop_word,
+        LexiconCategory::SocialFamilyWords => a.is_social_interaction,
+    }
+}
+
+/// Counts of one text, merged across the corpus
+#[derive(Default)]
+struct Tally {
+    tokens: usize,
+    baseline: HashMap<LexiconCategory, usize>,
+    candidate: HashMap<LexiconCategory, usize>,
+    lost: HashMap<LexiconCategory, usize>,
+    gained: HashMap<LexiconCategory, HashMap<String, usize>>,
+}
+
+impl Tally {
+    fn merge(&mut self, other: Tally) {
+        self.tokens += other.tokens;
+        for (mine, theirs) in [
+            (&mut self.baseline, other.baseline),
+            (&mut self.candidate, other.candidate),
+            (&mut self.lost, other.lost),
+        ] {
+            for (category, count) in theirs {
+                *mine.entry(category).or_default() += count;
+            }
+        }
+        for (category, words) in other.gained {
+            let mine = self.gained.entry(category).or_default();
+            for (word, count) in words {
+                *mine.entry(word).or_default() += count;
+            }
+        }
+    }
+}
+
+impl CoverageReport {
+    /// Analyze every text with both analyzers and compare category matches token by token
+    #[must_use]
+    pub fn compare<T: AsRef<str> + Sync>(
+        baseline: &TextAnalyzer,
+        candidate: &TextAnalyzer,
+        texts: &[T],
+    ) -> Self {
+        let tallies = parallel_map(texts, default_threads(), |text| {
+            let before = baseline.analyze_detailed(text.as_ref()).tokens;
+            let after = candidate.analyze_detailed(text.as_ref()).tokens;
+            let mut tally = Tally {
+                tokens: after.len(),
+                ..Tally::default()
+            };
+            for (old, new) in before.iter().zip(&after) {
+                for category in LexiconCategory::ALL {
+                    let (was, is) = (in_category(old, category), in_category(new, category));
+                    *tally.baseline.entry(category).or_default() += usize::from(was);
+                    *tally.candidate.entry(category).or_default() += usize::from(is);
+                    if was && !is {
+                        *tally.lost.entry(category).or_default() += 1;
+                    } else if is && !was {
+                        let word = new.analysis.lemma.as_ref().unwrap_or(&new.analysis.word);
+                        *tally
+                            .gained
+                            .entry(category)
+                            .or_default()
+                            .entry(word.clone())
+                            .or_default() += 1;
+                    }
+                }
+            }
+            tally
+        });
+
+        let mut total = Tally::default();
+        for tally in tallies {
+            total.merge(tally);
+        }
+
+        let categories = LexiconCategory::ALL
+            .into_iter()
+            .map(|category| {
+                let mut words: Vec<(String, usize)> = total
+                    .gained
+                    .remove(&category)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect();
+                words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                CategoryCoverage {
+                    category,
+                    baseline: total.baseline.get(&category).copied().unwrap_or(0),
+                    candidate: total.candidate.get(&category).copied().unwrap_or(0),
+                    gained: words.iter().map(|(_, count)| count).sum(),
+                    lost: total.lost.get(&category).copied().unwrap_or(0),
+                    top_gained: words.into_iter().take(TOP_GAINED).collect(),
+                }
+            })
+            .collect();
+
+        CoverageReport {
+            texts: texts.len(),
+            tokens: total.tokens,
+            categories,
+        }
+    }
+
+    /// Plain-text table of the report
+    #[must_use]
+    pub fn to_table(&self) -> String {
+        let mut table = format!("Текстов: {}, слов: {}\n\n", self.texts, self.tokens);
+        let _ = writeln!(
+            table,
+            "{:<22} {:>8} {:>8} {:>8} {:>8}  Примеры прироста",
+            "Категория", "Было", "Стало", "Прирост", "Потеряно"
+        );
+        for row in &self.categories {
+            let examples: Vec<String> = row
+                .top_gained
+                .iter()
+                .map(|(word, count)| format!("{word} ({count})"))
+                .collect();
+            let _ = writeln!(
+                table,
+                "{:<22} {:>8} {:>8} {:>8} {:>8}  {}",
+                row.category.key(),
+                row.baseline,
+                row.candidate,
+                format!("+{}", row.gained),
+                row.lost,
+                examples.join(", ")
+            );
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexicon::{Lexicon, MatchOptions};
+
+    #[test]
+    fn test_lemma_matching_gain() {
+        let baseline = TextAnalyzer::with_lexicon(Lexicon::builtin().with_matching(MatchOptions::FORMS_ONLY));
+        let candidate = TextAnalyzer::new();
+        let texts = ["Мы долго рыдали.", "Они рыдали, а я рыдал вместе с ней."];
+
+        let report = CoverageReport::compare(&baseline, &candidate, &texts);
+        assert_eq!(report.texts, 2);
+        assert_eq!(report.tokens, 11);
+
+        let emotion = &report.categories[0];
+        assert_eq!(emotion.category, LexiconCategory::EmotionWords);
+        assert_eq!(emotion.gained, 2);
+        assert_eq!(emotion.lost, 0);
+        assert_eq!(emotion.candidate, emotion.baseline + emotion.gained);
+        assert_eq!(emotion.top_gained, [("рыдать".to_string(), 2)]);
+        assert!(report.to_table().contains("emotion_words"));
+    }
+}
@@ -443,6 +443,26 @@ pub static EXTERNAL_PREDICATES: LazyLock<HashSet<&'static str>> = LazyLock::new(
     .collect()
 });
 
+/// Speech verbs (глаголы речи) introducing direct and reported speech: the
+/// speech verbs of `EXTERNAL_PREDICATES` with their lemmas and a few more
+pub static SPEECH_VERBS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    [
+        "говорить", "говорю", "говорил", "говорила", "говорили", "говорят", "говорит", "сказать",
+        "сказал", "сказала", "сказали", "скажу", "спрашивать", "спрашиваю", "спрашивал",
+        "спрашивала", "спросить", "спросил", "спросила", "отвечать", "отвечаю", "отвечал",
+        "отвечала", "ответить", "ответил", "ответила", "кричать", "кричу", "кричал", "кричала",
+        "крикнуть", "крикнул", "крикнула", "закричать", "закричал", "закричала", "шептать",
+        "шепчу", "шептал", "шептала", "прошептать", "прошептал", "прошептала", "звать", "зову",
+        "звал", "звала", "позвать", "позвал", "позвала", "просить", "просил", "просила",
+        "попросить", "попросил", "попросила", "рассказывать", "рассказывал", "рассказывала",
+        "рассказать", "рассказал", "рассказала", "объяснять", "объяснил", "объяснила",
+        "объяснить", "повторять", "повторил", "повторила", "повторить", "обещать", "обещал",
+        "обещала", "пообещать", "пообещал", "пообещала", "велеть", "велел", "велела",
+    ]
+    .into_iter()
+    .collect()
+});
+
 // ==================== STOP WORDS (водность) ====================
 
 /// Stop words for calculating "водность" (general phrases, meaningless words)
@@ -463,6 +483,27 @@ pub static STOP_WORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
     .collect()
 });
 
+// ==================== ABBREVIATIONS ====================
+
+/// Abbreviations whose period never ends a sentence ("т.е.", "см.", "ул. Ленина"),
+/// written without the final period; inner periods are kept ("т.е")
+pub static ABBREVIATIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    [
+        "т", "т.е", "т.к", "т.н", "е", "к", "н", "см", "ср", "напр", "рис", "стр", "с", "гл", "ст",
+        "ул", "пл", "пер", "пр-т", "просп", "наб", "ш", "пос", "дер", "обл", "р-н", "кв", "им",
+        "проф", "акад", "доц", "канд", "зав", "зам", "нач", "тов", "гр", "св", "и.о", "ок", "прим",
+        "изд", "тыс", "млн", "млрд", "руб", "коп", "в", "вв", "мл",
+    ]
+    .into_iter()
+    .collect()
+});
+
+/// Abbreviations that may close a sentence ("и т.д."); their period ends it
+/// only when a new sentence visibly starts after it
+pub static SENTENCE_FINAL_ABBREVIATIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    ["т.д", "т.п", "д", "п", "др", "пр", "н.э"].into_iter().collect()
+});
+
 // ==================== VERB ENDINGS ====================
 
 /// Common past tense verb endings
@@ -489,6 +530,14 @@ pub static PARTICIPLE_ENDINGS: &[&str] = &[
     "ущие", "ющие", "ащие", "ящие", "вшие", "шие", "емые", "омые", "имые", "нные", "тые", "енные",
 ];
 
+/// Passive participle endings, a subset of [`PARTICIPLE_ENDINGS`]
+pub static PASSIVE_PARTICIPLE_ENDINGS: &[&str] = &[
+    "емый", "омый", "имый", "нный", "тый", "енный",
+    "емая", "омая", "имая", "нная", "тая", "енная",
+    "емое", "омое", "имое", "нное", "тое", "енное",
+    "емые", "омые", "имые", "нные", "тые", "енные",
+];
+
 /// Gerund endings (деепричастия)
 pub static GERUND_ENDINGS: &[&str] = &["я", "а", "в", "вши", "вшись", "учи", "ючи"];
 
@@ -0,0 +1,184 @@
+//! Context-aware choice between alternative morphological parses
+//! Every parse of every word in a sentence is scored against its neighbours
+//! (agreement, prepositional government, subject–verb and verb–infinitive
+//! patterns) and the most consistent sequence is found with the Viterbi algorithm
+
+use rsmorphy::prelude::*;
+
+/// Lowest prior given to a part of speech, so a strong context can still
+/// select a rare reading (e.g. "стали" as a noun after "из")
+const MIN_POS_PRIOR: f64 = 0.05;
+
+/// Imperatives are rare in narrative texts; "мой", "три" are usually not verbs
+const IMPERATIVE_PENALTY: f64 = 0.5;
+
+const POS_TAGS: [&str; 17] = [
+    "NOUN", "ADJF", "ADJS", "COMP", "VERB", "INFN", "PRTF", "PRTS", "GRND", "NUMR", "ADVB",
+    "NPRO", "PRED", "PREP", "CONJ", "PRCL", "INTJ",
+];
+const CASES: [&str; 10] = [
+    "nomn", "gent", "datv", "accs", "ablt", "loct", "voct", "gen2", "acc2", "loc2",
+];
+const NUMBERS: [&str; 2] = ["sing", "plur"];
+const GENDERS: [&str; 3] = ["masc", "femn", "neut"];
+const PERSONS: [&str; 3] = ["1per", "2per", "3per"];
+
+/// One parse of a word considered during disambiguation
+pub(crate) struct Reading<'m> {
+    pub lemma: String,
+    pub grammemes: &'m GrammemeSet,
+    /// Dictionary probability estimated by rsmorphy
+    pub score: f64,
+}
+
+fn has(grammemes: &GrammemeSet, tag: &str) -> bool {
+    grammemes.set.contains(&Grammeme::new(tag))
+}
+
+fn first_of(grammemes: &GrammemeSet, tags: &[&'static str]) -> Option<&'static str> {
+    tags.iter().copied().find(|tag| has(grammemes, tag))
+}
+
+fn is_nominal(g: &GrammemeSet) -> bool {
+    ["NOUN", "ADJF", "NPRO", "NUMR", "PRTF"].iter().any(|tag| has(g, tag))
+}
+
+/// Equal if both are known, compatible if either is missing
+fn compatible(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+/// Modifier and noun agree in case, number and (in the singular) gender
+fn modifier_agrees(modifier: &GrammemeSet, noun: &GrammemeSet) -> bool {
+    let number = first_of(noun, &NUMBERS);
+    first_of(modifier, &CASES) == first_of(noun, &CASES)
+        && compatible(first_of(modifier, &NUMBERS), number)
+        && (number == Some("plur") || compatible(first_of(modifier, &GENDERS), first_of(noun, &GENDERS)))
+}
+
+/// Nominative subject and finite verb agree in number, person or gender
+fn subject_agrees(subject: &GrammemeSet, verb: &GrammemeSet) -> bool {
+    let number = first_of(verb, &NUMBERS);
+    if !compatible(first_of(subject, &NUMBERS), number) {
+        return false;
+    }
+    if has(verb, "past") {
+        number == Some("plur") || compatible(first_of(subject, &GENDERS), first_of(verb, &GENDERS))
+    } else {
+        let person = first_of(subject, &PERSONS).or(Some("3per"));
+        compatible(person, first_of(verb, &PERSONS))
+    }
+}
+
+/// Context score of `next` directly following `prev`
+fn transition(prev: &GrammemeSet, next: &GrammemeSet) -> f64 {
+    let mut score = 0.0;
+
+    // Prepositions govern an oblique case and are not followed by verbs
+    if has(prev, "PREP") {
+        if is_nominal(next) {
+            score += if has(next, "nomn") { -1.0 } else { 2.0 };
+        } else if has(next, "VERB") || has(next, "INFN") {
+            score -= 2.0;
+        }
+    }
+
+    // Adjectives and participles agree with the following noun
+    if (has(prev, "ADJF") || has(prev, "PRTF")) && has(next, "NOUN") {
+        score += if modifier_agrees(prev, next) { 1.5 } else { -1.0 };
+    }
+
+    // Numerals quantify the following noun
+    if has(prev, "NUMR") && has(next, "NOUN") {
+        score += 1.0;
+    }
+
+    // A nominative subject is followed by an agreeing finite verb
+    if (has(prev, "NPRO") || has(prev, "NOUN")) && has(prev, "nomn") {
+        if has(next, "VERB") && has(next, "indc") && subject_agrees(prev, next) {
+            score += if has(prev, "NPRO") { 1.5 } else { 0.5 };
+        } else if has(prev, "NPRO") && has(next, "impr") {
+            score -= 1.5;
+        }
+    }
+
+    // Verbs taking an infinitive complement ("стали играть", "хотела печь")
+    if (has(prev, "VERB") || has(prev, "INFN")) && has(next, "INFN") {
+        score += 1.0;
+    }
+
+    // Transitive verb followed by its direct object
+    if has(prev, "VERB") && has(prev, "tran") && has(next, "NOUN") && has(next, "accs") {
+        score += 0.5;
+    }
+
+    score
+}
+
+/// Log prior of each reading: total probability of its part of speech
+fn emissions(readings: &[Reading]) -> Vec<f64> {
+    readings
+        .iter()
+        .map(|reading| {
+            let pos = first_of(reading.grammemes, &POS_TAGS);
+            let pos_prior: f64 = readings
+                .iter()
+                .filter(|other| first_of(other.grammemes, &POS_TAGS) == pos)
+                .map(|other| other.score)
+                .sum();
+            let penalty = if has(reading.grammemes, "impr") { IMPERATIVE_PENALTY } else { 0.0 };
+            pos_prior.max(MIN_POS_PRIOR).ln() - penalty
+        })
+        .collect()
+}
+
+/// Index of the best reading for every word of a sentence
+///
+/// Words without readings get `None` and break the context chain.
+pub(crate) fn disambiguate(sentence: &[Vec<Reading>]) -> Vec<Option<usize>> {
+    let n = sentence.len();
+    let mut scores: Vec<Vec<f64>> = Vec::with_capacity(n);
+    let mut back: Vec<Vec<Option<usize>>> = Vec::with_capacity(n);
+
+    for (i, readings) in sentence.iter().enumerate() {
+        let emission = emissions(readings);
+        let mut word_scores = Vec::with_capacity(readings.len());
+        let mut word_back = Vec::with_capacity(readings.len());
+
+        for (k, reading) in readings.iter().enumerate() {
+            let best_prev = (i > 0)
+                .then(|| {
+                    sentence[i - 1].iter().zip(&scores[i - 1]).enumerate().map(|(j, (prev, s))| {
+                        (j, s + transition(prev.grammemes, reading.grammemes))
+                    })
+                })
+                .into_iter()
+                .flatten()
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            word_scores.push(emission[k] + best_prev.map_or(0.0, |(_, s)| s));
+            word_back.push(best_prev.map(|(j, _)| j));
+        }
+        scores.push(word_scores);
+        back.push(word_back);
+    }
+
+    let mut choice = vec![None; n];
+    let mut next: Option<usize> = None;
+    for i in (0..n).rev() {
+        if scores[i].is_empty() {
+            next = None;
+            continue;
+        }
+        let k = next.and_then(|k_next| back[i + 1][k_next]).unwrap_or_else(|| {
+            (0..scores[i].len())
+                .max_by(|&a, &b| scores[i][a].total_cmp(&scores[i][b]))
+                .unwrap_or(0)
+        });
+        choice[i] = Some(k);
+        next = Some(k);
+    }
+    choice
+}
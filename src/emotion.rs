@@ -0,0 +1,178 @@
+//! Affective ratings of emotion words
+//! Emotion lemmas are tagged with a basic emotion category, valence
+//! (−1 unpleasant … 1 pleasant) and arousal (0 calm … 1 excited), so a text
+//! can be profiled for affective flattening or lability, not only counted
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Basic emotion category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmotionCategory {
+    Joy,
+    Sadness,
+    Fear,
+    Anger,
+    Surprise,
+    Disgust,
+}
+
+impl EmotionCategory {
+    pub const ALL: [EmotionCategory; 6] = [
+        EmotionCategory::Joy,
+        EmotionCategory::Sadness,
+        EmotionCategory::Fear,
+        EmotionCategory::Anger,
+        EmotionCategory::Surprise,
+        EmotionCategory::Disgust,
+    ];
+
+    /// Key used for the category in lexicon files
+    #[must_use]
+    pub fn key(self) -> &'static str {
+        match self {
+            EmotionCategory::Joy => "joy",
+            EmotionCategory::Sadness => "sadness",
+            EmotionCategory::Fear => "fear",
+            EmotionCategory::Anger => "anger",
+            EmotionCategory::Surprise => "surprise",
+            EmotionCategory::Disgust => "disgust",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.key() == key)
+    }
+
+    /// Position in [`EmotionCategory::ALL`]
+    #[must_use]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Affective ratings of one emotion word
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EmotionTag {
+    /// Basic emotion; `None` for states outside the six categories ("стыд", "спокойствие")
+    pub category: Option<EmotionCategory>,
+    /// From −1 (unpleasant) to 1 (pleasant)
+    pub valence: f64,
+    /// From 0 (calm) to 1 (excited)
+    pub arousal: f64,
+}
+
+impl EmotionTag {
+    #[must_use]
+    pub fn new(category: Option<EmotionCategory>, valence: f64, arousal: f64) -> Self {
+        Self {
+            category,
+            valence: valence.clamp(-1.0, 1.0),
+            arousal: arousal.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Emotion diversity: Shannon entropy of the category counts, normalized to 0..1
+///
+/// 0 when at most one category occurs, 1 when all six occur equally often.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn diversity(counts: &[usize]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total == 0 || counts.len() < 2 {
+        return 0.0;
+    }
+    let entropy: f64 = counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.ln()
+        })
+        .sum();
+    entropy / (counts.len() as f64).ln()
+}
+
+/// Built-in emotion lemmas with their ratings
+pub static EMOTION_TAGS: LazyLock<HashMap<&'static str, EmotionTag>> = LazyLock::new(|| {
+    use EmotionCategory::{Anger, Disgust, Fear, Joy, Sadness, Surprise};
+
+    let entries: &[(&[&str], Option<EmotionCategory>, f64, f64)] = &[
+        // Joy
+        (&["радость", "радостный", "радостно", "радоваться"], Some(Joy), 0.8, 0.6),
+        (&["обрадоваться", "веселиться"], Some(Joy), 0.8, 0.7),
+        (&["весело", "весёлый", "веселый", "веселье"], Some(Joy), 0.8, 0.7),
+        (&["счастье", "счастливый", "счастливо"], Some(Joy), 0.9, 0.6),
+        (&["восторг", "восторженный", "восторженно", "ликовать"], Some(Joy), 0.9, 0.9),
+        (&["любовь", "обожать", "нежность"], Some(Joy), 0.9, 0.6),
+        (&["любить"], Some(Joy), 0.8, 0.5),
+        (&["нравиться", "удовольствие"], Some(Joy), 0.6, 0.4),
+        (&["смеяться", "смех"], Some(Joy), 0.7, 0.7),
+        (&["улыбаться", "улыбка"], Some(Joy), 0.6, 0.4),
+        // Sadness
+        (&["грусть", "грустный", "грустно", "грустить"], Some(Sadness), -0.7, 0.3),
+        (&["печаль", "печальный", "печально"], Some(Sadness), -0.7, 0.3),
+        (&["тоска", "тоскливый", "тоскливо", "тосковать"], Some(Sadness), -0.8, 0.3),
+        (&["горе", "горестный"], Some(Sadness), -0.9, 0.5),
+        (&["горько", "плакать", "слеза", "слёзы", "слезы"], Some(Sadness), -0.7, 0.6),
+        (&["рыдать"], Some(Sadness), -0.8, 0.8),
+        (&["всхлипывать"], Some(Sadness), -0.7, 0.6),
+        (&["скучать", "жаль", "жалко"], Some(Sadness), -0.5, 0.3),
+        (&["одиночество", "одиноко", "одинокий", "уныние"], Some(Sadness), -0.7, 0.2),
+        (&["страдать", "страдание", "мучиться", "мучение"], Some(Sadness), -0.8, 0.6),
+        // Fear
+        (&["страх", "страшно", "страшный"], Some(Fear), -0.8, 0.8),
+        (&["бояться", "испуг", "испуганный", "испугаться"], Some(Fear), -0.7, 0.8),
+        (&["ужас", "паника"], Some(Fear), -0.9, 0.9),
+        (&["тревога", "тревожно", "тревожный"], Some(Fear), -0.7, 0.7),
+        (&["волноваться", "волнение"], Some(Fear), -0.4, 0.7),
+        (&["беспокоиться", "беспокойство"], Some(Fear), -0.5, 0.6),
+        // Anger
+        (&["злость", "злиться", "сердиться"], Some(Anger), -0.8, 0.8),
+        (&["злой"], Some(Anger), -0.7, 0.7),
+        (&["гнев", "гневный", "ненавидеть", "ненависть"], Some(Anger), -0.9, 0.9),
+        (&["ярость", "яростный"], Some(Anger), -0.9, 1.0),
+        (&["обида", "обиженный", "обидно", "обижаться"], Some(Anger), -0.6, 0.5),
+        (&["раздражение", "раздражать", "бесить"], Some(Anger), -0.7, 0.7),
+        // Surprise
+        (&["удивление", "удивлённый", "удивленный", "удивляться", "удивиться"], Some(Surprise), 0.2, 0.7),
+        (&["удивительно", "изумление"], Some(Surprise), 0.3, 0.8),
+        // Disgust
+        (&["отвращение", "мерзкий", "мерзко"], Some(Disgust), -0.8, 0.6),
+        (&["противно", "противный", "гадко", "гадкий", "брезгливость"], Some(Disgust), -0.7, 0.5),
+        // Other affective states
+        (&["стыд", "стыдно", "стыдный"], None, -0.6, 0.5),
+        (&["смущение", "смущённый", "смущенный"], None, -0.3, 0.5),
+        (&["спокойствие", "спокойно", "спокойный"], None, 0.4, 0.1),
+    ];
+
+    entries
+        .iter()
+        .flat_map(|&(words, category, valence, arousal)| {
+            words
+                .iter()
+                .map(move |&word| (word, EmotionTag::new(category, valence, arousal)))
+        })
+        .collect()
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diversity() {
+        assert!(diversity(&[0; 6]).abs() < 1e-12);
+        assert!(diversity(&[5, 0, 0, 0, 0, 0]).abs() < 1e-12);
+        assert!((diversity(&[2; 6]) - 1.0).abs() < 1e-12);
+        let two = diversity(&[1, 1, 0, 0, 0, 0]);
+        assert!(two > 0.0 && two < 1.0);
+
+        assert_eq!(EMOTION_TAGS["тоска"].category, Some(EmotionCategory::Sadness));
+        assert!(EMOTION_TAGS.values().all(|t| (-1.0..=1.0).contains(&t.valence)));
+    }
+}
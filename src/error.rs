@@ -0,0 +1,121 @@
+//! Crate-level error type
+//! Wraps the errors of the individual modules and adds the input checks of
+//! `TextAnalyzer::try_analyze`, so callers can handle every failure in one place
+
+use crate::batch::BatchError;
+use crate::bundle::BundleError;
+use crate::lexicon::LexiconError;
+use crate::model::ModelError;
+use std::io;
+use std::sync::Arc;
+
+/// Any error of this library
+#[derive(Debug)]
+pub enum Error {
+    /// The rsmorphy dictionary could not be loaded; shared because the
+    /// dictionary is loaded once per process
+    Dictionary(Arc<BundleError>),
+    Lexicon(LexiconError),
+    Model(ModelError),
+    Batch(BatchError),
+    Io(io::Error),
+    /// A result could not be serialized
+    Json(serde_json::Error),
+    /// Text is empty or has no words
+    EmptyText,
+    /// Too few of the letters in the text are Cyrillic
+    NotRussian {
+        /// Share of Cyrillic letters among all letters, 0-1
+        cyrillic_share: f64,
+    },
+    /// Text has fewer words than the analyzer's minimum
+    TooShort { words: usize, min_words: usize },
+}
+
+/// Result with the crate-level [`Error`]
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Whether the error is about the text itself rather than the setup,
+    /// i.e. other texts can still be analyzed
+    #[must_use]
+    pub fn is_input_error(&self) -> bool {
+        matches!(
+            self,
+            Error::EmptyText | Error::NotRussian { .. } | Error::TooShort { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Dictionary(e) => write!(f, "cannot load the rsmorphy dictionary: {e}"),
+            Error::Lexicon(e) => e.fmt(f),
+            Error::Model(e) => e.fmt(f),
+            Error::Batch(e) => e.fmt(f),
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::Json(e) => write!(f, "cannot serialize result: {e}"),
+            Error::EmptyText => write!(f, "text is empty"),
+            Error::NotRussian { cyrillic_share } => write!(
+                f,
+                "text does not look Russian ({:.0}% of letters are Cyrillic)",
+                cyrillic_share * 100.0
+            ),
+            Error::TooShort { words, min_words } => write!(
+                f,
+                "text is too short: {words} words, at least {min_words} needed"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Dictionary(e) => Some(e.as_ref()),
+            Error::Lexicon(e) => Some(e),
+            Error::Model(e) => Some(e),
+            Error::Batch(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<BundleError> for Error {
+    fn from(e: BundleError) -> Self {
+        Error::Dictionary(Arc::new(e))
+    }
+}
+
+impl From<LexiconError> for Error {
+    fn from(e: LexiconError) -> Self {
+        Error::Lexicon(e)
+    }
+}
+
+impl From<ModelError> for Error {
+    fn from(e: ModelError) -> Self {
+        Error::Model(e)
+    }
+}
+
+impl From<BatchError> for Error {
+    fn from(e: BatchError) -> Self {
+        Error::Batch(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
@@ -0,0 +1,617 @@
+//! C ABI for embedding the analyzer in other applications
+//! Opaque analyzer/classifier handles, metrics as a flat struct or JSON and
+//! status codes instead of panics; the header is `include/mdpredict.h`
+
+use crate::analyzer::TextAnalyzer;
+use crate::classifier::Classifier;
+use crate::error::Error;
+use crate::lexicon::Lexicon;
+use crate::metrics::{DiagnosticGroup, TextMetrics};
+use crate::morphology::MorphAnalyzer;
+use crate::server;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+/// Result of every fallible call; details are in [`mdp_last_error`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MdpStatus {
+    Ok = 0,
+    /// A required pointer argument was null
+    NullPointer = 1,
+    /// Text or a string argument is not valid UTF-8
+    InvalidUtf8 = 2,
+    /// Unknown backend name or another invalid argument
+    InvalidArgument = 3,
+    /// Dictionary, lexicon or model could not be loaded
+    Load = 4,
+    /// Text is empty or has no words
+    EmptyText = 5,
+    /// Result could not be serialized to JSON
+    Serialization = 6,
+    /// The library panicked; the call had no effect
+    Panic = 7,
+    /// Fewer than half of the letters of the text are Cyrillic
+    NotRussian = 8,
+    /// Text has fewer words than the analyzer's minimum
+    TooShort = 9,
+}
+
+/// Diagnostic group, in the order of the paper
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MdpGroup {
+    Healthy = 0,
+    Schizophrenia = 1,
+    PersonalityDisorder = 2,
+    BipolarDisorder = 3,
+}
+
+impl From<DiagnosticGroup> for MdpGroup {
+    fn from(group: DiagnosticGroup) -> Self {
+        match group {
+            DiagnosticGroup::Healthy => MdpGroup::Healthy,
+            DiagnosticGroup::Schizophrenia => MdpGroup::Schizophrenia,
+            DiagnosticGroup::PersonalityDisorder => MdpGroup::PersonalityDisorder,
+            DiagnosticGroup::BipolarDisorder => MdpGroup::BipolarDisorder,
+        }
+    }
+}
+
+/// Declares `MdpMetrics` with the fields of `TextMetrics` in declaration order
+///
+/// The conversions list every field without `..Default::default()`, so a new
+/// `TextMetrics` field fails to compile until it is added here and to the header.
+macro_rules! flat_metrics {
+    (counts: $($count:ident),* ; values: $($value:ident),* $(,)?) => {
+        /// `TextMetrics` as a flat C struct: counts as `size_t`, the rest as `double`
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy, Default)]
+        pub struct MdpMetrics {
+            $(pub $count: usize,)*
+            $(pub $value: f64,)*
+        }
+
+        impl From<&TextMetrics> for MdpMetrics {
+            fn from(m: &TextMetrics) -> Self {
+                Self { $($count: m.$count,)* $($value: m.$value,)* }
+            }
+        }
+
+        impl From<&MdpMetrics> for TextMetrics {
+            fn from(m: &MdpMetrics) -> Self {
+                Self { $($count: m.$count,)* $($value: m.$value,)* }
+            }
+        }
+
+        /// Field names of `MdpMetrics`, in order
+        #[cfg(test)]
+        const METRIC_FIELDS: &[&str] = &[$(stringify!($count),)* $(stringify!($value),)*];
+    };
+}
+
+flat_metrics! {
+    counts: total_words, total_sentences, run_on_sentences, compound_sentences,
+        complex_sentences, simple_sentences, mixed_sentences;
+    values: lexical_diversity_index, external_predicates, internal_predicates,
+        active_voice_verbs, passive_voice_verbs, reflexive_verbs, perfective_verbs,
+        imperfective_verbs, perfective_aspect_ratio, past_tense_verbs, present_tense_verbs,
+        future_tense_verbs, infinitives, non_finite_verb_forms, adjectives, nouns, adverbs,
+        first_person_singular_pronouns, first_person_plural_pronouns,
+        second_person_singular_pronouns, second_person_plural_pronouns,
+        third_person_singular_pronouns, third_person_plural_pronouns, filler_words_index,
+        stop_words_index, prepositions, conjunctions, social_interaction_words, emotion_words,
+        positive_emotion_share, negative_emotion_share, mean_valence, mean_arousal,
+        emotion_diversity, negation_density, negated_internal_predicates, negated_emotion_words,
+        direct_speech_share, reported_speech_share, egocentrism_index,
+}
+
+/// Classification as a flat C struct
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct MdpClassification {
+    pub primary_group: MdpGroup,
+    /// Probability of the primary group
+    pub confidence: f64,
+    pub p_healthy: f64,
+    pub p_schizophrenia: f64,
+    pub p_personality_disorder: f64,
+    pub p_bipolar_disorder: f64,
+}
+
+/// Opaque analyzer handle
+pub struct MdpAnalyzer(TextAnalyzer);
+
+/// Opaque classifier handle
+pub struct MdpClassifier(Classifier);
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// A failed call: its status and the message for [`mdp_last_error`]
+struct Failure(MdpStatus, String);
+
+impl Failure {
+    fn null(argument: &str) -> Self {
+        Failure(MdpStatus::NullPointer, format!("{argument} is null"))
+    }
+}
+
+impl From<Error> for Failure {
+    fn from(e: Error) -> Self {
+        let status = match e {
+            Error::EmptyText => MdpStatus::EmptyText,
+            Error::NotRussian { .. } => MdpStatus::NotRussian,
+            Error::TooShort { .. } => MdpStatus::TooShort,
+            Error::Json(_) => MdpStatus::Serialization,
+            _ => MdpStatus::Load,
+        };
+        Failure(status, e.to_string())
+    }
+}
+
+/// Run `f`, turning errors and panics into a status and the last error message
+fn guard(f: impl FnOnce() -> Result<(), Failure>) -> MdpStatus {
+    let Failure(status, message) = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => return MdpStatus::Ok,
+        Ok(Err(failure)) => failure,
+        Err(payload) => {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Failure(MdpStatus::Panic, format!("panic: {reason}"))
+        }
+    };
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+/// Optional NUL-terminated UTF-8 string argument
+unsafe fn optional_str<'a>(s: *const c_char, argument: &str) -> Result<Option<&'a str>, Failure> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: the caller passes a valid NUL-terminated string
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map(Some)
+        .map_err(|_| {
+            Failure(
+                MdpStatus::InvalidUtf8,
+                format!("{argument} is not valid UTF-8"),
+            )
+        })
+}
+
+/// UTF-8 text buffer
+unsafe fn text_arg<'a>(text: *const c_char, len: usize) -> Result<&'a str, Failure> {
+    if text.is_null() {
+        return Err(Failure::null("text"));
+    }
+    // SAFETY: the caller passes `len` readable bytes
+    let bytes = unsafe { std::slice::from_raw_parts(text.cast::<u8>(), len) };
+    std::str::from_utf8(bytes).map_err(|e| {
+        Failure(
+            MdpStatus::InvalidUtf8,
+            format!("text is not valid UTF-8: {e}"),
+        )
+    })
+}
+
+/// Serialize to a JSON string owned by the caller
+fn json_string(value: &impl serde::Serialize) -> Result<*mut c_char, Failure> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| Failure(MdpStatus::Serialization, e.to_string()))?;
+    // serde_json escapes control characters, so the JSON has no NUL bytes
+    CString::new(json)
+        .map(CString::into_raw)
+        .map_err(|e| Failure(MdpStatus::Serialization, e.to_string()))
+}
+
+/// Message of the last failed call on this thread, empty if none
+///
+/// The pointer stays valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn mdp_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ptr())
+}
+
+/// Library version, a static NUL-terminated string
+#[unsafe(no_mangle)]
+pub extern "C" fn mdp_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Create an analyzer
+///
+/// `backend` is "rsmorphy" or "rules" (null means "rsmorphy"); `lexicon_path`
+/// is a .toml/.json/.txt word list file or null for the built-in lists.
+///
+/// # Safety
+///
+/// String arguments must be null or valid NUL-terminated strings; `out` must
+/// be a valid pointer. Free the analyzer with [`mdp_analyzer_free`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdp_analyzer_new(
+    backend: *const c_char,
+    lexicon_path: *const c_char,
+    narrative_only: bool,
+    out: *mut *mut MdpAnalyzer,
+) -> MdpStatus {
+    guard(|| {
+        if out.is_null() {
+            return Err(Failure::null("out"));
+        }
+        // SAFETY: guaranteed by the caller
+        let (backend, lexicon_path) = unsafe {
+            (
+                optional_str(backend, "backend")?,
+                optional_str(lexicon_path, "lexicon_path")?,
+            )
+        };
+        let lexicon = match lexicon_path {
+            Some(path) => Arc::new(
+                Lexicon::load(path)
+                    .map_err(|e| Failure(MdpStatus::Load, format!("{path}: {e}")))?,
+            ),
+            None => Lexicon::shared_builtin(),
+        };
+        let analyzer = match backend.unwrap_or("rsmorphy") {
+            "rsmorphy" => TextAnalyzer::try_with_lexicon(lexicon)
+                .map_err(|e| Failure(MdpStatus::Load, e.to_string()))?,
+            "rules" => TextAnalyzer::with_backend(MorphAnalyzer::with_lexicon(lexicon)),
+            other => {
+                return Err(Failure(
+                    MdpStatus::InvalidArgument,
+                    format!("unknown backend '{other}' (use rsmorphy or rules)"),
+                ));
+            }
+        };
+        let handle = Box::new(MdpAnalyzer(analyzer.with_narrative_only(narrative_only)));
+        // SAFETY: `out` is valid and not null
+        unsafe { *out = Box::into_raw(handle) };
+        Ok(())
+    })
+}
+
+/// Set the minimum number of words a text needs (default 5, 0 for none);
+/// shorter texts give [`MdpStatus::TooShort`]
+///
+/// # Safety
+///
+/// `analyzer` must be a live analyzer not used by other threads during the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdp_analyzer_set_min_words(
+    analyzer: *mut MdpAnalyzer,
+    min_words: usize,
+) -> MdpStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller
+        let analyzer = unsafe { analyzer.as_mut() }.ok_or_else(|| Failure::null("analyzer"))?;
+        analyzer.0 = analyzer.0.clone().with_min_words(min_words);
+        Ok(())
+    })
+}
+
+/// Free an analyzer; null is ignored
+///
+/// # Safety
+///
+/// `analyzer` must come from [`mdp_analyzer_new`] and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdp_analyzer_free(analyzer: *mut MdpAnalyzer) {
+    if !analyzer.is_null() {
+        // SAFETY: created by `Box::into_raw` in `mdp_analyzer_new`
+        drop(unsafe { Box::from_raw(analyzer) });
+    }
+}
+
+/// Create a classifier from a model file (.json or .bin), or with the
+/// paper's coefficients if `model_path` is null
+///
+/// # Safety
+///
+/// `model_path` must be null or a valid NUL-terminated string; `out` must be
+/// a valid pointer. Free the classifier with [`mdp_classifier_free`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdp_classifier_new(
+    model_path: *const c_char,
+    out: *mut *mut MdpClassifier,
+) -> MdpStatus {
+    guard(|| {
+        if out.is_null() {
+            return Err(Failure::null("out"));
+        }
+        // SAFETY: guaranteed by the caller
+        let classifier = match unsafe { optional_str(model_path, "model_path")? } {
+            Some(path) => Classifier::load(path)
+                .map_err(|e| Failure(MdpStatus::Load, format!("{path}: {e}")))?,
+            None => Classifier::new(),
+        };
+        // SAFETY: `out` is valid and not null
+        unsafe { *out = Box::into_raw(Box::new(MdpClassifier(classifier))) };
+        Ok(())
+    })
+}
+
+/// Free a classifier; null is ignored
+///
+/// # Safety
+///
+/// `classifier` must come from [`mdp_classifier_new`] and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdp_classifier_free(classifier: *mut MdpClassifier) {
+    if !classifier.is_null() {
+        // SAFETY: created by `Box::into_raw` in `mdp_classifier_new`
+        drop(unsafe { Box::from_raw(classifier) });
+    }
+}
+
+/// Analyze `len` bytes of UTF-8 text into a flat metrics struct
+///
+/// # Safety
+///
+/// `analyzer` must be a live analyzer, `text` must point to `len` readable
+/// bytes and `out` must be a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdp_analyze(
+    analyzer: *const MdpAnalyzer,
+    text: *const c_char,
+    len: usize,
+    out: *mut MdpMetrics,
+) -> MdpStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller
+        let (analyzer, out) = unsafe { (analyzer.as_ref(), out.as_mut()) };
+        let analyzer = analyzer.ok_or_else(|| Failure::null("analyzer"))?;
+        let out = out.ok_or_else(|| Failure::null("out"))?;
+        // SAFETY: guaranteed by the caller
+        let text = unsafe { text_arg(text, len)? };
+        *out = MdpMetrics::from(&analyzer.0.try_analyze(text)?);
+        Ok(())
+    })
+}
+
+/// Classify flat metrics
+///
+/// # Safety
+///
+/// `classifier` must be a live classifier; `metrics` and `out` must be valid pointers.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdp_classify(
+    classifier: *const MdpClassifier,
+    metrics: *const MdpMetrics,
+    out: *mut MdpClassification,
+) -> MdpStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller
+        let (classifier, metrics, out) =
+            unsafe { (classifier.as_ref(), metrics.as_ref(), out.as_mut()) };
+        let classifier = classifier.ok_or_else(|| Failure::null("classifier"))?;
+        let metrics = metrics.ok_or_else(|| Failure::null("metrics"))?;
+        let out = out.ok_or_else(|| Failure::null("out"))?;
+        let result = classifier.0.classify(&TextMetrics::from(metrics));
+        let scores = &result.group_scores;
+        *out = MdpClassification {
+            primary_group: result.primary_diagnosis.into(),
+            confidence: result.confidence,
+            p_healthy: scores.healthy,
+            p_schizophrenia: scores.schizophrenia,
+            p_personality_disorder: scores.personality_disorder,
+            p_bipolar_disorder: scores.bipolar_disorder,
+        };
+        Ok(())
+    })
+}
+
+/// Analyze `len` bytes of UTF-8 text into JSON
+///
+/// With a classifier the JSON is the report of `mdpredict --json` (metrics,
+/// classification, explanation, profile); with a null classifier it is the
+/// metrics alone. Free the string with [`mdp_string_free`].
+///
+/// # Safety
+///
+/// `analyzer` must be a live analyzer, `classifier` null or a live
+/// classifier, `text` must point to `len` readable bytes and `out_json` must
+/// be a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdp_analyze_json(
+    analyzer: *const MdpAnalyzer,
+    classifier: *const MdpClassifier,
+    text: *const c_char,
+    len: usize,
+    out_json: *mut *mut c_char,
+) -> MdpStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller
+        let (analyzer, classifier) = unsafe { (analyzer.as_ref(), classifier.as_ref()) };
+        let analyzer = analyzer.ok_or_else(|| Failure::null("analyzer"))?;
+        if out_json.is_null() {
+            return Err(Failure::null("out_json"));
+        }
+        // SAFETY: guaranteed by the caller
+        let text = unsafe { text_arg(text, len)? };
+        let metrics = analyzer.0.try_analyze(text)?;
+        let json = match classifier {
+            Some(classifier) => {
+                json_string(&server::metrics_report(&analyzer.0, &classifier.0, &metrics))?
+            }
+            None => json_string(&metrics)?,
+        };
+        // SAFETY: `out_json` is valid and not null
+        unsafe { *out_json = json };
+        Ok(())
+    })
+}
+
+/// Free a string returned by this library; null is ignored
+///
+/// # Safety
+///
+/// `s` must come from this library and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdp_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: created by `CString::into_raw` in `json_string`
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    const HEADER: &str = include_str!("../include/mdpredict.h");
+
+    #[test]
+    fn test_header_matches_metrics() {
+        let start = HEADER.find("typedef struct MdpMetrics {").unwrap();
+        let end = start + HEADER[start..].find("} MdpMetrics;").unwrap();
+        let fields: Vec<&str> = HEADER[start..end]
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.trim().strip_suffix(';'))
+            .filter_map(|line| line.split_whitespace().last())
+            .collect();
+        assert_eq!(fields, METRIC_FIELDS);
+    }
+
+    #[test]
+    fn test_analyze_and_classify() {
+        let text = "Я помню как катался на велосипеде и упал.";
+        unsafe {
+            let mut analyzer = ptr::null_mut();
+            let backend = c"rules";
+            assert_eq!(
+                mdp_analyzer_new(backend.as_ptr(), ptr::null(), false, &raw mut analyzer),
+                MdpStatus::Ok
+            );
+            let mut classifier = ptr::null_mut();
+            assert_eq!(
+                mdp_classifier_new(ptr::null(), &raw mut classifier),
+                MdpStatus::Ok
+            );
+
+            let mut metrics = MdpMetrics::default();
+            let status = mdp_analyze(analyzer, text.as_ptr().cast(), text.len(), &raw mut metrics);
+            assert_eq!(status, MdpStatus::Ok);
+            assert_eq!(metrics.total_words, 8);
+
+            let mut classification = MdpClassification {
+                primary_group: MdpGroup::Healthy,
+                confidence: 0.0,
+                p_healthy: 0.0,
+                p_schizophrenia: 0.0,
+                p_personality_disorder: 0.0,
+                p_bipolar_disorder: 0.0,
+            };
+            assert_eq!(
+                mdp_classify(classifier, &raw const metrics, &raw mut classification),
+                MdpStatus::Ok
+            );
+            let total = classification.p_healthy
+                + classification.p_schizophrenia
+                + classification.p_personality_disorder
+                + classification.p_bipolar_disorder;
+            assert!((total - 1.0).abs() < 1e-9);
+
+            let mut json = ptr::null_mut();
+            let status = mdp_analyze_json(
+                analyzer,
+                classifier,
+                text.as_ptr().cast(),
+                text.len(),
+                &raw mut json,
+            );
+            assert_eq!(status, MdpStatus::Ok);
+            let report: serde_json::Value =
+                serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            assert_eq!(report["metrics"]["total_words"], 8);
+            mdp_string_free(json);
+
+            mdp_classifier_free(classifier);
+            mdp_analyzer_free(analyzer);
+        }
+    }
+
+    #[test]
+    fn test_error_codes() {
+        unsafe {
+            let mut analyzer = ptr::null_mut();
+            let unknown = c"unknown";
+            assert_eq!(
+                mdp_analyzer_new(unknown.as_ptr(), ptr::null(), false, &raw mut analyzer),
+                MdpStatus::InvalidArgument
+            );
+            assert!(analyzer.is_null());
+            let message = CStr::from_ptr(mdp_last_error()).to_str().unwrap();
+            assert!(message.contains("unknown backend"));
+
+            let rules = c"rules";
+            assert_eq!(
+                mdp_analyzer_new(rules.as_ptr(), ptr::null(), false, &raw mut analyzer),
+                MdpStatus::Ok
+            );
+            let mut metrics = MdpMetrics::default();
+            let blank = "  ";
+            assert_eq!(
+                mdp_analyze(
+                    analyzer,
+                    blank.as_ptr().cast(),
+                    blank.len(),
+                    &raw mut metrics
+                ),
+                MdpStatus::EmptyText
+            );
+            let invalid = [0xff_u8, 0xfe];
+            assert_eq!(
+                mdp_analyze(
+                    analyzer,
+                    invalid.as_ptr().cast(),
+                    invalid.len(),
+                    &raw mut metrics
+                ),
+                MdpStatus::InvalidUtf8
+            );
+            let english = "I remember riding my bicycle and falling.";
+            assert_eq!(
+                mdp_analyze(
+                    analyzer,
+                    english.as_ptr().cast(),
+                    english.len(),
+                    &raw mut metrics
+                ),
+                MdpStatus::NotRussian
+            );
+            let short = "Я упал.";
+            assert_eq!(
+                mdp_analyze(analyzer, short.as_ptr().cast(), short.len(), &raw mut metrics),
+                MdpStatus::TooShort
+            );
+            assert_eq!(mdp_analyzer_set_min_words(analyzer, 2), MdpStatus::Ok);
+            assert_eq!(
+                mdp_analyze(analyzer, short.as_ptr().cast(), short.len(), &raw mut metrics),
+                MdpStatus::Ok
+            );
+            assert_eq!(metrics.total_words, 2);
+            assert_eq!(
+                mdp_analyze(
+                    ptr::null(),
+                    blank.as_ptr().cast(),
+                    blank.len(),
+                    &raw mut metrics
+                ),
+                MdpStatus::NullPointer
+            );
+            mdp_analyzer_free(analyzer);
+        }
+    }
+}
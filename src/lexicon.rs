@@ -0,0 +1,877 @@
+//! Loadable word lists for the semantic categories
+//! A `Lexicon` holds the emotion, predicate, filler, stop and social word lists.
+//! It starts from the built-in dictionaries and can be extended or replaced
+//! from TOML, JSON or plain-text files without recompiling
+
+use crate::backend::PredicateType;
+use crate::dictionaries::{
+    EMOTION_WORDS, EXTERNAL_PREDICATES, FILLER_WORDS, INTERNAL_PREDICATES, SOCIAL_FAMILY_WORDS,
+    STOP_WORDS,
+};
+use crate::emotion::{EmotionCategory, EmotionTag, EMOTION_TAGS};
+use crate::phrases::PhraseMatcher;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+/// Name of the lexicon compiled into the library
+pub const BUILTIN_LEXICON_NAME: &str = "builtin";
+
+/// Word list category that can be overridden by a lexicon file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LexiconCategory {
+    EmotionWords,
+    InternalPredicates,
+    ExternalPredicates,
+    FillerWords,
+    StopWords,
+    SocialFamilyWords,
+}
+
+impl LexiconCategory {
+    pub const ALL: [LexiconCategory; 6] = [
+        LexiconCategory::EmotionWords,
+        LexiconCategory::InternalPredicates,
+        LexiconCategory::ExternalPredicates,
+        LexiconCategory::FillerWords,
+        LexiconCategory::StopWords,
+        LexiconCategory::SocialFamilyWords,
+    ];
+
+    /// Key used for the category in lexicon files
+    #[must_use]
+    pub fn key(self) -> &'static str {
+        match self {
+            LexiconCategory::EmotionWords => "emotion_words",
+            LexiconCategory::InternalPredicates => "internal_predicates",
+            LexiconCategory::ExternalPredicates => "external_predicates",
+            LexiconCategory::FillerWords => "filler_words",
+            LexiconCategory::StopWords => "stop_words",
+            LexiconCategory::SocialFamilyWords => "social_family_words",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.key() == key)
+    }
+
+    /// Internal or external predicates, matched on verbs only
+    #[must_use]
+    pub fn is_predicate(self) -> bool {
+        matches!(
+            self,
+            LexiconCategory::InternalPredicates | LexiconCategory::ExternalPredicates
+        )
+    }
+
+    /// Whether an inflected entry stands for its whole paradigm
+    ///
+    /// Fillers and stop words are form-specific ("типа" is a filler, "тип" is
+    /// not), so for them only entries given in dictionary form cover all forms.
+    #[must_use]
+    pub fn lemmatizes_inflected_entries(self) -> bool {
+        !matches!(self, LexiconCategory::FillerWords | LexiconCategory::StopWords)
+    }
+}
+
+/// How lexicon entries are matched against tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchOptions {
+    /// Match every form of a listed word by lemma, not only the listed forms
+    pub lemmas: bool,
+    /// Also match the aspect partner of a listed verb ("решать" ↔ "решить")
+    pub aspect_pairs: bool,
+    /// Also match prefixed derivatives of a listed verb ("заплакать" → "плакать")
+    pub prefixes: bool,
+}
+
+impl MatchOptions {
+    /// Surface forms only, as before lemma matching was introduced
+    pub const FORMS_ONLY: MatchOptions = MatchOptions {
+        lemmas: false,
+        aspect_pairs: false,
+        prefixes: false,
+    };
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self {
+            lemmas: true,
+            aspect_pairs: false,
+            prefixes: false,
+        }
+    }
+}
+
+/// How a lexicon file combines with the lexicon it is applied to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LexiconMode {
+    /// Add the listed words to the existing lists
+    #[default]
+    Merge,
+    /// Listed categories replace the existing lists; unlisted ones become empty
+    Replace,
+}
+
+/// On-disk lexicon format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexiconFormat {
+    Toml,
+    Json,
+    /// `[category]` sections with one word or phrase per line
+    Text,
+}
+
+impl LexiconFormat {
+    /// Guess the format from a file extension (`.toml`, `.json`, `.txt`/`.lex`)
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "toml" => Some(LexiconFormat::Toml),
+            "json" => Some(LexiconFormat::Json),
+            "txt" | "lex" => Some(LexiconFormat::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Error when reading or parsing a lexicon file
+#[derive(Debug)]
+pub enum LexiconError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    /// Malformed line in a plain-text lexicon
+    Syntax { line: usize, message: String },
+    /// File extension does not identify a known format
+    UnknownFormat(String),
+    UnknownCategory(String),
+    /// Emotion rating with a valence or arousal that is not a finite number
+    InvalidRating(String),
+}
+
+impl fmt::Display for LexiconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexiconError::Io(e) => write!(f, "lexicon I/O error: {e}"),
+            LexiconError::Toml(e) => write!(f, "invalid TOML lexicon: {e}"),
+            LexiconError::Json(e) => write!(f, "invalid JSON lexicon: {e}"),
+            LexiconError::Syntax { line, message } => {
+                write!(f, "invalid lexicon at line {line}: {message}")
+            }
+            LexiconError::UnknownFormat(path) => write!(
+                f,
+                "cannot determine lexicon format of '{path}' (use .toml, .json or .txt)"
+            ),
+            LexiconError::UnknownCategory(key) => write!(f, "unknown lexicon category '{key}'"),
+            LexiconError::InvalidRating(word) => {
+                write!(f, "emotion rating of '{word}' is not a finite number")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LexiconError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LexiconError::Io(e) => Some(e),
+            LexiconError::Toml(e) => Some(e),
+            LexiconError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for LexiconError {
+    fn from(e: std::io::Error) -> Self {
+        LexiconError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for LexiconError {
+    fn from(e: toml::de::Error) -> Self {
+        LexiconError::Toml(e)
+    }
+}
+
+impl From<serde_json::Error> for LexiconError {
+    fn from(e: serde_json::Error) -> Self {
+        LexiconError::Json(e)
+    }
+}
+
+/// Contents of a lexicon file, shared by all formats
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LexiconFile {
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    mode: LexiconMode,
+    lemmas: Option<bool>,
+    aspect_pairs: Option<bool>,
+    prefixes: Option<bool>,
+    emotion_words: Option<Vec<String>>,
+    internal_predicates: Option<Vec<String>>,
+    external_predicates: Option<Vec<String>>,
+    filler_words: Option<Vec<String>>,
+    stop_words: Option<Vec<String>>,
+    social_family_words: Option<Vec<String>>,
+    emotions: Option<HashMap<String, EmotionTag>>,
+}
+
+/// Section of a plain-text lexicon
+#[derive(Clone, Copy)]
+enum Section {
+    Words(LexiconCategory),
+    Emotions,
+}
+
+/// Key of the emotion ratings section
+const EMOTIONS_KEY: &str = "emotions";
+
+impl LexiconFile {
+    fn words_mut(&mut self, category: LexiconCategory) -> &mut Option<Vec<String>> {
+        match category {
+            LexiconCategory::EmotionWords => &mut self.emotion_words,
+            LexiconCategory::InternalPredicates => &mut self.internal_predicates,
+            LexiconCategory::ExternalPredicates => &mut self.external_predicates,
+            LexiconCategory::FillerWords => &mut self.filler_words,
+            LexiconCategory::StopWords => &mut self.stop_words,
+            LexiconCategory::SocialFamilyWords => &mut self.social_family_words,
+        }
+    }
+
+    /// Parse the plain-text format:
+    ///
+    /// ```text
+    /// # comment
+    /// name = clinic
+    /// version = 2
+    /// mode = merge
+    /// prefixes = true
+    ///
+    /// [emotion_words]
+    /// тоска
+    /// не по себе
+    ///
+    /// [emotions]
+    /// # word category valence arousal ("-" for no category)
+    /// хандра sadness -0.6 0.3
+    /// ```
+    fn parse_text(source: &str) -> Result<Self, LexiconError> {
+        let mut file = LexiconFile::default();
+        let mut section: Option<Section> = None;
+
+        for (index, raw) in source.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let syntax = |message: String| LexiconError::Syntax {
+                line: index + 1,
+                message,
+            };
+
+            if let Some(key) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let key = key.trim();
+                if key == EMOTIONS_KEY {
+                    file.emotions.get_or_insert_with(HashMap::new);
+                    section = Some(Section::Emotions);
+                } else {
+                    let category = LexiconCategory::from_key(key)
+                        .ok_or_else(|| LexiconError::UnknownCategory(key.to_string()))?;
+                    file.words_mut(category).get_or_insert_with(Vec::new);
+                    section = Some(Section::Words(category));
+                }
+            } else if let Some(Section::Words(category)) = section {
+                file.words_mut(category).get_or_insert_with(Vec::new).push(line.to_string());
+            } else if let Some(Section::Emotions) = section {
+                let (word, tag) = parse_emotion(line).map_err(syntax)?;
+                file.emotions.get_or_insert_with(HashMap::new).insert(word, tag);
+            } else {
+                let (key, value) = line
+                    .split_once('=')
+                    .ok_or_else(|| syntax(format!("expected 'key = value' or '[category]', found '{line}'")))?;
+                let value = value.trim().to_string();
+                let flag = |value: &str| match value {
+                    "true" => Ok(true),
+                    "false" => Ok(false),
+                    other => Err(syntax(format!("expected true or false, found '{other}'"))),
+                };
+                match key.trim() {
+                    "lemmas" => file.lemmas = Some(flag(&value)?),
+                    "aspect_pairs" => file.aspect_pairs = Some(flag(&value)?),
+                    "prefixes" => file.prefixes = Some(flag(&value)?),
+                    "name" => file.name = Some(value),
+                    "version" => file.version = Some(value),
+                    "mode" => {
+                        file.mode = match value.as_str() {
+                            "merge" => LexiconMode::Merge,
+                            "replace" => LexiconMode::Replace,
+                            other => return Err(syntax(format!("unknown mode '{other}' (use merge or replace)"))),
+                        }
+                    }
+                    other => return Err(syntax(format!("unknown key '{other}'"))),
+                }
+            }
+        }
+        Ok(file)
+    }
+}
+
+/// Parse an emotion rating line: `word category valence arousal`
+fn parse_emotion(line: &str) -> Result<(String, EmotionTag), String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [word, category, valence, arousal] = fields[..] else {
+        return Err(format!("expected 'word category valence arousal', found '{line}'"));
+    };
+    let category = match category {
+        "-" => None,
+        key => Some(
+            EmotionCategory::from_key(key).ok_or_else(|| format!("unknown emotion category '{key}'"))?,
+        ),
+    };
+    let number = |value: &str| {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .ok_or_else(|| format!("expected a number, found '{value}'"))
+    };
+    Ok((word.to_string(), EmotionTag::new(category, number(valence)?, number(arousal)?)))
+}
+
+/// Lowercase an entry and collapse inner whitespace; `None` if empty
+fn normalize(entry: &str) -> Option<String> {
+    let words: Vec<String> = entry.split_whitespace().map(str::to_lowercase).collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// 64-bit FNV-1a, stable across platforms and compiler versions
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |h, &b| (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Identifies the exact lexicon that produced a set of results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LexiconInfo {
+    pub name: String,
+    pub version: String,
+    /// Hash of all word lists, so unversioned edits are still detectable
+    pub fingerprint: String,
+}
+
+impl fmt::Display for LexiconInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ({})", self.name, self.version, self.fingerprint)
+    }
+}
+
+/// Semantic word lists used by the morphology backends
+#[derive(Debug, Clone)]
+pub struct Lexicon {
+    name: String,
+    version: String,
+    emotion_words: HashSet<String>,
+    internal_predicates: HashSet<String>,
+    external_predicates: HashSet<String>,
+    filler_words: HashSet<String>,
+    stop_words: HashSet<String>,
+    social_family_words: HashSet<String>,
+    /// Affective ratings keyed by lowercase word or lemma
+    emotions: HashMap<String, EmotionTag>,
+    matching: MatchOptions,
+    /// Multi-word fillers of this lexicon plus the built-in compound conjunctions
+    phrases: PhraseMatcher,
+}
+
+/// The built-in lexicon, built once per process
+static BUILTIN: LazyLock<Arc<Lexicon>> = LazyLock::new(|| Arc::new(Lexicon::builtin()));
+
+impl Lexicon {
+    /// Lexicon with the compiled-in word lists of [`crate::dictionaries`]
+    #[must_use]
+    pub fn builtin() -> Self {
+        let owned = |set: &HashSet<&str>| set.iter().map(ToString::to_string).collect();
+        let mut lexicon = Self {
+            name: BUILTIN_LEXICON_NAME.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            emotion_words: owned(&EMOTION_WORDS),
+            internal_predicates: owned(&INTERNAL_PREDICATES),
+            external_predicates: owned(&EXTERNAL_PREDICATES),
+            filler_words: owned(&FILLER_WORDS),
+            stop_words: owned(&STOP_WORDS),
+            social_family_words: owned(&SOCIAL_FAMILY_WORDS),
+            emotions: EMOTION_TAGS
+                .iter()
+                .map(|(word, tag)| ((*word).to_string(), *tag))
+                .collect(),
+            matching: MatchOptions::default(),
+            phrases: PhraseMatcher::default(),
+        };
+        lexicon.rebuild_phrases();
+        lexicon
+    }
+
+    /// Shared handle to the built-in lexicon
+    #[must_use]
+    pub fn shared_builtin() -> Arc<Lexicon> {
+        Arc::clone(&BUILTIN)
+    }
+
+    pub(crate) fn builtin_ref() -> &'static Lexicon {
+        &BUILTIN
+    }
+
+    /// Load a lexicon file and apply it to the built-in lexicon
+    ///
+    /// The format is chosen from the file extension.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LexiconError`] if the file cannot be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LexiconError> {
+        let path = path.as_ref();
+        let format = LexiconFormat::from_path(path)
+            .ok_or_else(|| LexiconError::UnknownFormat(path.display().to_string()))?;
+        let source = std::fs::read_to_string(path)?;
+        Self::builtin().extended(&source, format)
+    }
+
+    /// Apply a lexicon in the given format on top of this one
+    ///
+    /// In merge mode the listed words are added; in replace mode the result
+    /// contains only the words of `source`. Name and version are taken from
+    /// `source`, falling back to this lexicon's.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LexiconError`] if `source` cannot be parsed.
+    pub fn extended(&self, source: &str, format: LexiconFormat) -> Result<Self, LexiconError> {
+        let mut file = match format {
+            LexiconFormat::Toml => toml::from_str(source)?,
+            LexiconFormat::Json => serde_json::from_str(source)?,
+            LexiconFormat::Text => LexiconFile::parse_text(source)?,
+        };
+
+        let mut lexicon = self.clone();
+        if let Some(name) = file.name.take() {
+            lexicon.name = name;
+        }
+        if let Some(version) = file.version.take() {
+            lexicon.version = version;
+        }
+        let matching = &mut lexicon.matching;
+        matching.lemmas = file.lemmas.unwrap_or(matching.lemmas);
+        matching.aspect_pairs = file.aspect_pairs.unwrap_or(matching.aspect_pairs);
+        matching.prefixes = file.prefixes.unwrap_or(matching.prefixes);
+        for category in LexiconCategory::ALL {
+            let words = lexicon.words_mut(category);
+            if file.mode == LexiconMode::Replace {
+                words.clear();
+            }
+            if let Some(entries) = file.words_mut(category).take() {
+                words.extend(entries.iter().filter_map(|entry| normalize(entry)));
+            }
+        }
+        if file.mode == LexiconMode::Replace {
+            lexicon.emotions.clear();
+        }
+        for (word, tag) in file.emotions.take().unwrap_or_default() {
+            if !tag.valence.is_finite() || !tag.arousal.is_finite() {
+                return Err(LexiconError::InvalidRating(word));
+            }
+            if let Some(word) = normalize(&word) {
+                lexicon
+                    .emotions
+                    .insert(word, EmotionTag::new(tag.category, tag.valence, tag.arousal));
+            }
+        }
+        lexicon.rebuild_phrases();
+        Ok(lexicon)
+    }
+
+    fn rebuild_phrases(&mut self) {
+        self.phrases = PhraseMatcher::with_fillers(self.filler_words.iter().map(String::as_str));
+    }
+
+    fn words_mut(&mut self, category: LexiconCategory) -> &mut HashSet<String> {
+        match category {
+            LexiconCategory::EmotionWords => &mut self.emotion_words,
+            LexiconCategory::InternalPredicates => &mut self.internal_predicates,
+            LexiconCategory::ExternalPredicates => &mut self.external_predicates,
+            LexiconCategory::FillerWords => &mut self.filler_words,
+            LexiconCategory::StopWords => &mut self.stop_words,
+            LexiconCategory::SocialFamilyWords => &mut self.social_family_words,
+        }
+    }
+
+    /// All entries of a category (lowercase; phrases use single spaces)
+    #[must_use]
+    pub fn words(&self, category: LexiconCategory) -> &HashSet<String> {
+        match category {
+            LexiconCategory::EmotionWords => &self.emotion_words,
+            LexiconCategory::InternalPredicates => &self.internal_predicates,
+            LexiconCategory::ExternalPredicates => &self.external_predicates,
+            LexiconCategory::FillerWords => &self.filler_words,
+            LexiconCategory::StopWords => &self.stop_words,
+            LexiconCategory::SocialFamilyWords => &self.social_family_words,
+        }
+    }
+
+    /// Whether a lowercase word belongs to a category
+    #[must_use]
+    pub fn contains(&self, category: LexiconCategory, word: &str) -> bool {
+        self.words(category).contains(word)
+    }
+
+    /// Predicate type of a word form or its lemma; internal takes precedence
+    #[must_use]
+    pub fn predicate_type(&self, word: &str, lemma: &str) -> PredicateType {
+        let listed = |category| self.contains(category, word) || self.contains(category, lemma);
+        if listed(LexiconCategory::InternalPredicates) {
+            PredicateType::Internal
+        } else if listed(LexiconCategory::ExternalPredicates) {
+            PredicateType::External
+        } else {
+            PredicateType::Neither
+        }
+    }
+
+    /// Affective ratings of a lowercase word form or lemma
+    #[must_use]
+    pub fn emotion(&self, word: &str) -> Option<EmotionTag> {
+        self.emotions.get(word).copied()
+    }
+
+    /// All rated emotion words
+    #[must_use]
+    pub fn emotions(&self) -> &HashMap<String, EmotionTag> {
+        &self.emotions
+    }
+
+    /// How entries are matched against tokens
+    #[must_use]
+    pub fn matching(&self) -> MatchOptions {
+        self.matching
+    }
+
+    /// The same word lists with different matching options
+    #[must_use]
+    pub fn with_matching(mut self, matching: MatchOptions) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    /// Matcher for this lexicon's multi-word fillers and compound conjunctions
+    #[must_use]
+    pub fn phrases(&self) -> &PhraseMatcher {
+        &self.phrases
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Hex hash of all word lists, emotion ratings and matching options,
+    /// independent of insertion order
+    #[must_use]
+    pub fn fingerprint(&self) -> String {
+        let mut hash = 0xcbf2_9ce4_8422_2325;
+        for category in LexiconCategory::ALL {
+            let mut words: Vec<&str> = self.words(category).iter().map(String::as_str).collect();
+            words.sort_unstable();
+            hash = fnv1a(hash, category.key().as_bytes());
+            for word in words {
+                hash = fnv1a(hash, word.as_bytes());
+                hash = fnv1a(hash, b"\n");
+            }
+        }
+        let mut emotions: Vec<(&String, &EmotionTag)> = self.emotions.iter().collect();
+        emotions.sort_unstable_by_key(|(word, _)| *word);
+        hash = fnv1a(hash, EMOTIONS_KEY.as_bytes());
+        for (word, tag) in emotions {
+            let category = tag.category.map_or("-", EmotionCategory::key);
+            let line = format!("{word}\t{category}\t{}\t{}\n", tag.valence, tag.arousal);
+            hash = fnv1a(hash, line.as_bytes());
+        }
+        let MatchOptions { lemmas, aspect_pairs, prefixes } = self.matching;
+        hash = fnv1a(hash, &[u8::from(lemmas), u8::from(aspect_pairs), u8::from(prefixes)]);
+        format!("{hash:016x}")
+    }
+
+    /// Name, version and fingerprint for recording alongside results
+    #[must_use]
+    pub fn info(&self) -> LexiconInfo {
+        LexiconInfo {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            fingerprint: self.fingerprint(),
+        }
+    }
+}
+
+/// Verbal prefixes stripped for prefix-derivation matching
+const VERB_PREFIXES: [&str; 44] = [
+    "недо", "пере", "пред", "разо", "разъ", "подо", "подъ", "надо", "обез", "обес", "при",
+    "про", "раз", "рас", "под", "над", "воз", "вос", "изо", "изъ", "ото", "отъ", "обо", "объ",
+    "взъ", "вы", "за", "на", "по", "из", "ис", "от", "об", "во", "вз", "вс", "до", "со", "съ",
+    "въ", "с", "у", "о", "в",
+];
+
+/// Lemmas of the single-word lexicon entries, per category
+///
+/// Built by a backend that can lemmatize; the lexicon itself stays
+/// independent of any morphology dictionary.
+#[derive(Debug, Default)]
+pub(crate) struct LemmaIndex {
+    lemmas: HashMap<LexiconCategory, HashSet<String>>,
+}
+
+impl LemmaIndex {
+    /// Index every entry under one lemma
+    ///
+    /// `candidates` returns the lemmas of an entry's parses, most probable
+    /// first, each with whether it is a verb lemma. Among several candidates
+    /// the one shared by most entries of the category wins, so "плачу" listed
+    /// next to "плакал" is indexed as "плакать", not "платить".
+    pub(crate) fn build(lexicon: &Lexicon, candidates: impl Fn(&str) -> Vec<(String, bool)>) -> Self {
+        let mut index = Self::default();
+        for category in LexiconCategory::ALL {
+            let entries: Vec<(&str, Vec<String>)> = lexicon
+                .words(category)
+                .iter()
+                .filter(|entry| !entry.contains(' '))
+                .map(|entry| {
+                    let lemmas = candidates(entry)
+                        .into_iter()
+                        .filter(|(_, verb)| *verb || !category.is_predicate())
+                        .map(|(lemma, _)| lemma)
+                        .collect();
+                    (entry.as_str(), lemmas)
+                })
+                .collect();
+
+            let mut support: HashMap<&str, usize> = HashMap::new();
+            for lemma in entries.iter().flat_map(|(_, lemmas)| lemmas) {
+                *support.entry(lemma).or_default() += 1;
+            }
+
+            let lemmas = index.lemmas.entry(category).or_default();
+            for (entry, candidates) in &entries {
+                let best = candidates
+                    .iter()
+                    .rev()
+                    .max_by_key(|lemma| support[lemma.as_str()]);
+                if let Some(best) = best
+                    && (category.lemmatizes_inflected_entries() || best == entry)
+                {
+                    lemmas.insert(best.clone());
+                }
+            }
+        }
+        index
+    }
+
+    /// Whether a token lemma matches the category under the given options
+    ///
+    /// Predicates match verbs only; aspect and prefix expansion apply to verbs.
+    pub(crate) fn contains(
+        &self,
+        category: LexiconCategory,
+        lemma: &str,
+        verb: bool,
+        matching: MatchOptions,
+    ) -> bool {
+        if !matching.lemmas || (category.is_predicate() && !verb) {
+            return false;
+        }
+        let Some(lemmas) = self.lemmas.get(&category) else {
+            return false;
+        };
+        if lemmas.contains(lemma) {
+            return true;
+        }
+        verb && (matching.aspect_pairs || matching.prefixes)
+            && verb_variants(lemma, matching).iter().any(|v| lemmas.contains(v))
+    }
+}
+
+/// Related verb lemmas: without "-ся", without a prefix, and aspect partners
+fn verb_variants(lemma: &str, matching: MatchOptions) -> Vec<String> {
+    let mut variants = vec![lemma.to_string()];
+    if let Some(base) = lemma.strip_suffix("ся").or_else(|| lemma.strip_suffix("сь")) {
+        variants.push(base.to_string());
+    }
+    if matching.prefixes {
+        let unprefixed: Vec<String> = variants.iter().flat_map(|v| strip_prefix(v)).collect();
+        variants.extend(unprefixed);
+    }
+    if matching.aspect_pairs {
+        let partners: Vec<String> = variants.iter().flat_map(|v| aspect_partners(v)).collect();
+        variants.extend(partners);
+    }
+    variants
+}
+
+/// The verb without each prefix it may start with
+/// ("заплакать" → "плакать", "сыграть" → "играть")
+fn strip_prefix(verb: &str) -> Vec<String> {
+    VERB_PREFIXES.iter().filter_map(|prefix| {
+        let rest = verb.strip_prefix(prefix)?;
+        if rest.chars().count() < 4 {
+            return None;
+        }
+        // Root-initial "и" becomes "ы" after a consonant prefix
+        Some(match rest.strip_prefix('ы') {
+            Some(tail) => format!("и{tail}"),
+            None => rest.to_string(),
+        })
+    })
+    .collect()
+}
+
+/// Likely aspect partners formed by suffix alternation
+fn aspect_partners(verb: &str) -> Vec<String> {
+    let mut partners = Vec::new();
+    for (from, to) in [
+        ("ывать", "ать"),
+        ("ивать", "ать"),
+        ("ивать", "ить"),
+        ("авать", "ать"),
+        ("ять", "ить"),
+        ("ать", "ить"),
+        ("ить", "ать"),
+        ("ить", "ять"),
+        ("ать", "нуть"),
+        ("нуть", "ать"),
+    ] {
+        if let Some(stem) = verb.strip_suffix(from)
+            && stem.chars().count() >= 2
+        {
+            partners.push(format!("{stem}{to}"));
+        }
+    }
+    partners
+}
+
+impl Default for Lexicon {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats_merge_and_replace() {
+        let toml = r#"
+            name = "clinic"
+            version = "2.1"
+            emotion_words = ["Хандра", "не по себе"]
+        "#;
+        let json = r#"{"name": "clinic", "version": "2.1", "emotion_words": ["хандра", "не  по себе"]}"#;
+        let text = "# clinic list\nname = clinic\nversion = 2.1\n\n[emotion_words]\nхандра\nне по себе\n";
+
+        let builtin = Lexicon::builtin();
+        let merged = builtin.extended(toml, LexiconFormat::Toml).unwrap();
+        assert_eq!(merged.name(), "clinic");
+        assert_eq!(merged.version(), "2.1");
+        assert!(merged.contains(LexiconCategory::EmotionWords, "хандра"));
+        assert!(merged.contains(LexiconCategory::EmotionWords, "радость"));
+        assert_eq!(merged.words(LexiconCategory::StopWords), builtin.words(LexiconCategory::StopWords));
+        assert_ne!(merged.fingerprint(), builtin.fingerprint());
+
+        for (source, format) in [(json, LexiconFormat::Json), (text, LexiconFormat::Text)] {
+            assert_eq!(builtin.extended(source, format).unwrap().info(), merged.info());
+        }
+
+        let replaced = builtin
+            .extended("mode = replace\n[filler_words]\nкороче говоря\n", LexiconFormat::Text)
+            .unwrap();
+        assert_eq!(replaced.name(), BUILTIN_LEXICON_NAME);
+        assert!(replaced.words(LexiconCategory::EmotionWords).is_empty());
+        assert_eq!(replaced.words(LexiconCategory::FillerWords).len(), 1);
+        assert_eq!(replaced.phrases().find(&["короче", "говоря"]).len(), 1);
+        assert_eq!(replaced.phrases().find(&["как", "бы"]).len(), 0);
+    }
+
+    #[test]
+    fn test_emotion_ratings() {
+        let toml = r#"
+            [emotions]
+            "хандра" = { category = "sadness", valence = -0.6, arousal = 0.3 }
+            "умиротворение" = { valence = 0.7, arousal = 0.1 }
+        "#;
+        let text = "[emotions]\nхандра sadness -0.6 0.3\nумиротворение - 0.7 0.1\n";
+
+        let builtin = Lexicon::builtin();
+        let merged = builtin.extended(toml, LexiconFormat::Toml).unwrap();
+        let tag = merged.emotion("хандра").unwrap();
+        assert_eq!(tag.category, Some(EmotionCategory::Sadness));
+        assert!((tag.valence + 0.6).abs() < 1e-12);
+        assert_eq!(merged.emotion("умиротворение").unwrap().category, None);
+        assert!(merged.emotion("радость").is_some());
+        assert_ne!(merged.fingerprint(), builtin.fingerprint());
+        assert_eq!(builtin.extended(text, LexiconFormat::Text).unwrap().info(), merged.info());
+
+        let replaced = builtin
+            .extended(&format!("mode = replace\n{text}"), LexiconFormat::Text)
+            .unwrap();
+        assert_eq!(replaced.emotions().len(), 2);
+        assert!(matches!(
+            builtin.extended("[emotions]\nхандра melancholy -0.6 0.3", LexiconFormat::Text),
+            Err(LexiconError::Syntax { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_lexicons() {
+        let builtin = Lexicon::builtin();
+        assert!(matches!(
+            builtin.extended("[feelings]\nхандра", LexiconFormat::Text),
+            Err(LexiconError::UnknownCategory(_))
+        ));
+        assert!(matches!(
+            builtin.extended("хандра", LexiconFormat::Text),
+            Err(LexiconError::Syntax { line: 1, .. })
+        ));
+        assert!(matches!(
+            builtin.extended("feelings = [\"тоска\"]", LexiconFormat::Toml),
+            Err(LexiconError::Toml(_))
+        ));
+        assert!(matches!(
+            builtin.extended("[emotions]\nхандра sadness nan 0.3", LexiconFormat::Text),
+            Err(LexiconError::Syntax { line: 2, .. })
+        ));
+        assert!(matches!(
+            builtin.extended("[emotions]\n\"хандра\" = { valence = nan, arousal = 0.3 }", LexiconFormat::Toml),
+            Err(LexiconError::InvalidRating(_))
+        ));
+        assert!(matches!(
+            builtin.extended("[emotions]\n\"хандра\" = { valence = -0.6, arousal = inf }", LexiconFormat::Toml),
+            Err(LexiconError::InvalidRating(_))
+        ));
+        assert!(matches!(
+            Lexicon::load("words.yaml"),
+            Err(LexiconError::UnknownFormat(_))
+        ));
+    }
+}
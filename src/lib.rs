@@ -1,4 +1,5 @@
 #![warn(clippy::pedantic)]
+#![cfg_attr(test, allow(clippy::uninlined_format_args))]
 //! # mdpredict-russian
 //!
 //! Mental Disorder Prediction based on structural characteristics of Russian written speech.
@@ -12,9 +13,31 @@
 //! # Features
 //!
 //! - Morphological analysis of Russian text
-//! - Sentence structure classification
+//! - Sentence segmentation aware of abbreviations, initials, decimals, quotes and asides
+//! - Sentence structure classification from clause segmentation, mixed sentences included
 //! - Computation of 19+ structural speech characteristics
 //! - Classification into diagnostic groups (Schizophrenia, Bipolar, Personality Disorder, Healthy)
+//! - Fitting the LDA classifier on your own annotated corpus (`Classifier::fit`)
+//! - Saving and loading versioned classifier models (JSON or bincode)
+//! - Z-score profile against the paper's reference groups (`ProfileComparison`)
+//! - Token-level annotation with byte spans (`TextAnalyzer::analyze_detailed`)
+//! - Batch processing of corpora with CSV/TSV export (`batch`)
+//! - Thread-safe analyzer sharing one dictionary load, parallel batch analysis
+//! - Pluggable morphology backends (`MorphologyBackend`): rsmorphy or rule-based
+//! - Context-aware disambiguation of homonymous parses within a sentence
+//! - Multi-word fillers and compound conjunctions (`PhraseMatcher`)
+//! - Word lists loadable from TOML/JSON/text files (`Lexicon`)
+//! - Lexicon matching by lemma with aspect/prefix expansion, coverage reports (`CoverageReport`)
+//! - Voice from grammemes, analytic passives ("был построен") and reflexive verbs
+//! - Verb aspect and the compound future ("буду делать") counted as one future verb
+//! - Emotion profile from valence/arousal/category ratings of emotion words (`EmotionTag`)
+//! - Direct and reported speech detection, metrics on the narrative only (`SpeechKind`)
+//! - Negation scope ("не помню", "не страшно"), negated emotion words reported separately
+//! - HTTP JSON API sharing one analyzer and classifier across requests (`Server`)
+//! - Python bindings with metrics as dicts and DataFrame-ready batch rows (feature `python`)
+//! - C ABI with a flat metrics struct or JSON and status codes (`ffi`, `include/mdpredict.h`)
+//! - Crate-level `Error` with fallible constructors (`TextAnalyzer::try_new`) and input checks (`try_analyze`)
+//! - WebAssembly build for offline analysis in the browser, dictionary passed as bytes (feature `wasm`, `bundle`)
 //!
 //! # Example
 //!
@@ -38,43 +61,137 @@
 //! substitute for professional medical diagnosis. Always consult a
 //! qualified healthcare professional for mental health assessments.
 
+pub mod analytic;
 pub mod analyzer;
+pub mod backend;
+pub mod batch;
+pub mod bundle;
 pub mod classifier;
+pub mod clause;
+pub mod coverage;
 pub mod dictionaries;
+pub mod emotion;
+pub mod error;
+pub mod ffi;
+pub mod lexicon;
+mod disambiguation;
+mod linalg;
 pub mod metrics;
+pub mod model;
 pub mod morphology;
+pub mod negation;
+pub mod phrases;
+pub mod profile;
+#[cfg(feature = "python")]
+pub mod python;
 pub mod rsmorph;
+pub mod segmenter;
 pub mod sentence;
+pub mod server;
+pub mod speech;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 // Re-export main types
-pub use analyzer::TextAnalyzer;
-pub use classifier::Classifier;
-pub use metrics::{ClassificationResult, DiagnosticGroup, GroupScores, TextMetrics};
-pub use rsmorph::{RsMorphAnalyzer, PartOfSpeech, PredicateType, VerbForm, VerbTense};
-pub use sentence::{SentenceAnalyzer, SentenceType};
+pub use analyzer::{DetailedAnalysis, TextAnalyzer, TokenAnnotation};
+pub use backend::{
+    Aspect, Case, MorphologyBackend, PartOfSpeech, PredicateType, VerbForm, VerbTense, Voice,
+    WordAnalysis,
+};
+pub use batch::{BatchError, BatchInput, BatchWriter, ExportFormat};
+pub use classifier::{CanonicalFunction, Classifier, FitError};
+pub use clause::{Clause, ClauseConnector};
+pub use coverage::{CategoryCoverage, CoverageReport};
+pub use emotion::{EmotionCategory, EmotionTag};
+pub use error::{Error, Result};
+pub use lexicon::{
+    Lexicon, LexiconCategory, LexiconError, LexiconFormat, LexiconInfo, MatchOptions,
+};
+pub use metrics::{
+    ClassificationExplanation, ClassificationResult, DiagnosticGroup, GroupScores, TextMetrics,
+};
+pub use model::{ClassifierModel, ModelError, ModelFormat};
+pub use morphology::MorphAnalyzer;
+pub use phrases::{PhraseKind, PhraseMatcher};
+pub use profile::ProfileComparison;
+pub use rsmorph::RsMorphAnalyzer;
+pub use sentence::{SentenceAnalysis, SentenceAnalyzer, SentenceType};
+pub use server::{ApiError, Server};
+pub use speech::SpeechKind;
+
+use std::sync::{Arc, LazyLock};
+
+/// Analyzer shared by the convenience functions; a dictionary that cannot be
+/// loaded is kept as the error, so every call can report it
+static SHARED_ANALYZER: LazyLock<Result<TextAnalyzer>> = LazyLock::new(TextAnalyzer::try_new);
+
+/// Built-in classifier shared by the convenience functions
+static SHARED_CLASSIFIER: LazyLock<Classifier> = LazyLock::new(Classifier::new);
+
+fn shared_analyzer() -> Result<&'static TextAnalyzer> {
+    match &*SHARED_ANALYZER {
+        Ok(analyzer) => Ok(analyzer),
+        Err(Error::Dictionary(e)) => Err(Error::Dictionary(Arc::clone(e))),
+        Err(e) => unreachable!("TextAnalyzer::try_new failed with {e}"),
+    }
+}
 
 /// Convenience function to analyze text and get classification
+///
+/// The text is not checked: an empty or non-Russian text gets the metrics
+/// and classification of whatever words it has. Use
+/// [`try_analyze_and_classify`] to reject such texts.
+///
+/// # Panics
+///
+/// Panics if the rsmorphy dictionary cannot be loaded.
 #[must_use] 
 pub fn analyze_and_classify(text: &str) -> (TextMetrics, ClassificationResult) {
-    let analyzer = TextAnalyzer::new();
-    let classifier = Classifier::new();
-
-    let metrics = analyzer.analyze(text);
-    let result = classifier.classify(&metrics);
+    let metrics = shared_analyzer().unwrap_or_else(|e| panic!("{e}")).analyze(text);
+    let result = SHARED_CLASSIFIER.classify(&metrics);
 
     (metrics, result)
 }
 
+/// Analyze text and get classification, rejecting empty, non-Russian and too
+/// short texts
+///
+/// # Errors
+///
+/// Returns [`Error::Dictionary`] if the rsmorphy dictionary cannot be loaded
+/// and the input errors of [`TextAnalyzer::try_analyze`].
+pub fn try_analyze_and_classify(text: &str) -> Result<(TextMetrics, ClassificationResult)> {
+    let metrics = shared_analyzer()?.try_analyze(text)?;
+    let result = SHARED_CLASSIFIER.classify(&metrics);
+
+    Ok((metrics, result))
+}
+
 /// Get a full analysis report for text
+///
+/// The text is not checked, as in [`analyze_and_classify`]; use
+/// [`try_get_full_report`] to reject empty, non-Russian and too short texts.
+///
+/// # Panics
+///
+/// Panics if the rsmorphy dictionary cannot be loaded.
 #[must_use] 
 pub fn get_full_report(text: &str) -> String {
-    let analyzer = TextAnalyzer::new();
-    let classifier = Classifier::new();
+    let (metrics, result) = analyze_and_classify(text);
+
+    SHARED_CLASSIFIER.get_detailed_report(&metrics, &result)
+}
 
-    let metrics = analyzer.analyze(text);
-    let result = classifier.classify(&metrics);
+/// Get a full analysis report for text, rejecting empty, non-Russian and too
+/// short texts
+///
+/// # Errors
+///
+/// Same as [`try_analyze_and_classify`].
+pub fn try_get_full_report(text: &str) -> Result<String> {
+    let (metrics, result) = try_analyze_and_classify(text)?;
 
-    classifier.get_detailed_report(&metrics, &result)
+    Ok(SHARED_CLASSIFIER.get_detailed_report(&metrics, &result))
 }
 
 #[cfg(test)]
@@ -97,6 +214,21 @@ mod tests {
 
         assert!(report.contains("АНАЛИЗ"));
         assert!(report.contains("РЕЗУЛЬТАТ"));
+        assert!(report.contains("Сложные с разными видами связи: "));
+    }
+
+    #[test]
+    fn test_try_variants_reject_input() {
+        let text = "Я помню как катался на велосипеде и упал.";
+        assert!(try_analyze_and_classify(text).is_ok());
+        assert!(try_get_full_report(text).unwrap().contains("РЕЗУЛЬТАТ"));
+
+        assert!(matches!(try_analyze_and_classify("   "), Err(Error::EmptyText)));
+        assert!(matches!(
+            try_get_full_report("I remember my first bicycle."),
+            Err(Error::NotRussian { .. })
+        ));
+        assert!(matches!(try_analyze_and_classify("Упал."), Err(Error::TooShort { .. })));
     }
 
     #[test]
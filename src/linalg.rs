@@ -0,0 +1,187 @@
+//! Small dense linear algebra helpers used by the LDA classifier
+//! Matrices are square, row-major `Vec<Vec<f64>>` - the feature space is tiny,
+//! so clarity wins over performance here
+
+// Index loops mirror the textbook formulations of these algorithms
+#![allow(clippy::needless_range_loop, clippy::many_single_char_names)]
+
+/// Square matrix stored as rows
+pub(crate) type Matrix = Vec<Vec<f64>>;
+
+/// Create an `n x n` zero matrix
+pub(crate) fn zeros(n: usize) -> Matrix {
+    vec![vec![0.0; n]; n]
+}
+
+/// Add the outer product `scale * a * b^T` to `m`
+pub(crate) fn add_outer(m: &mut Matrix, a: &[f64], b: &[f64], scale: f64) {
+    for (row, &ai) in m.iter_mut().zip(a) {
+        for (cell, &bj) in row.iter_mut().zip(b) {
+            *cell += scale * ai * bj;
+        }
+    }
+}
+
+/// Multiply a matrix by a vector
+pub(crate) fn mat_vec(m: &Matrix, v: &[f64]) -> Vec<f64> {
+    m.iter().map(|row| dot(row, v)).collect()
+}
+
+/// Dot product of two vectors
+pub(crate) fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Cholesky decomposition `m = L * L^T` of a symmetric positive definite matrix.
+/// Returns `None` if the matrix is not positive definite.
+pub(crate) fn cholesky(m: &Matrix) -> Option<Matrix> {
+    let n = m.len();
+    let mut l = zeros(n);
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let d = m[i][i] - sum;
+                if d <= 0.0 || !d.is_finite() {
+                    return None;
+                }
+                l[i][j] = d.sqrt();
+            } else {
+                l[i][j] = (m[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Invert a lower triangular matrix
+pub(crate) fn invert_lower(l: &Matrix) -> Matrix {
+    let n = l.len();
+    let mut inv = zeros(n);
+    for i in 0..n {
+        inv[i][i] = 1.0 / l[i][i];
+        for j in 0..i {
+            let sum: f64 = (j..i).map(|k| l[i][k] * inv[k][j]).sum();
+            inv[i][j] = -sum / l[i][i];
+        }
+    }
+    inv
+}
+
+/// Transpose a matrix
+pub(crate) fn transpose(m: &Matrix) -> Matrix {
+    let n = m.len();
+    let mut t = zeros(n);
+    for (i, row) in m.iter().enumerate() {
+        for (j, &v) in row.iter().enumerate() {
+            t[j][i] = v;
+        }
+    }
+    t
+}
+
+/// Multiply two square matrices
+pub(crate) fn mat_mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let n = a.len();
+    let mut c = zeros(n);
+    for i in 0..n {
+        for k in 0..n {
+            let aik = a[i][k];
+            if aik == 0.0 {
+                continue;
+            }
+            for j in 0..n {
+                c[i][j] += aik * b[k][j];
+            }
+        }
+    }
+    c
+}
+
+/// Eigen decomposition of a symmetric matrix using the cyclic Jacobi method.
+/// Returns `(eigenvalues, eigenvectors)` sorted by descending eigenvalue;
+/// `eigenvectors[k]` is the unit eigenvector for `eigenvalues[k]`.
+pub(crate) fn symmetric_eigen(m: &Matrix) -> (Vec<f64>, Vec<Vec<f64>>) {
+    const MAX_SWEEPS: usize = 100;
+    let n = m.len();
+    let mut a = m.clone();
+    let mut v = zeros(n);
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+
+    for _ in 0..MAX_SWEEPS {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off < 1e-22 {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let akp = a[k][p];
+                    let akq = a[k][q];
+                    a[k][p] = c * akp - s * akq;
+                    a[k][q] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let apk = a[p][k];
+                    let aqk = a[q][k];
+                    a[p][k] = c * apk - s * aqk;
+                    a[q][k] = s * apk + c * aqk;
+                }
+                for row in &mut v {
+                    let vkp = row[p];
+                    let vkq = row[q];
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| a[j][j].total_cmp(&a[i][i]));
+    let values = order.iter().map(|&i| a[i][i]).collect();
+    let vectors = order
+        .iter()
+        .map(|&i| v.iter().map(|row| row[i]).collect())
+        .collect();
+    (values, vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cholesky_inverse() {
+        let m = vec![vec![4.0, 2.0], vec![2.0, 3.0]];
+        let l = cholesky(&m).unwrap();
+        let l_inv = invert_lower(&l);
+        let inv = mat_mul(&transpose(&l_inv), &l_inv);
+        let identity = mat_mul(&m, &inv);
+        assert!((identity[0][0] - 1.0).abs() < 1e-12);
+        assert!(identity[0][1].abs() < 1e-12);
+        assert!((identity[1][1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let m = vec![vec![2.0, 1.0], vec![1.0, 2.0]];
+        let (values, vectors) = symmetric_eigen(&m);
+        assert!((values[0] - 3.0).abs() < 1e-10);
+        assert!((values[1] - 1.0).abs() < 1e-10);
+        let mv = mat_vec(&m, &vectors[0]);
+        assert!((mv[0] - 3.0 * vectors[0][0]).abs() < 1e-10);
+    }
+}
@@ -3,40 +3,264 @@
 //! Command-line interface for analyzing structural characteristics
 //! of written speech for mental health research.
 
-use mdpredict_russian::{analyze_and_classify, get_full_report, Classifier, TextAnalyzer};
+use mdpredict_russian::batch::{self, BatchWriter, ExportFormat};
+use mdpredict_russian::bundle;
+use mdpredict_russian::server::{self, Server};
+use mdpredict_russian::{
+    Aspect, Classifier, CoverageReport, DetailedAnalysis, Error, Lexicon, MatchOptions,
+    MorphAnalyzer, SpeechKind, TextAnalyzer, Voice,
+};
 use std::env;
 use std::fs;
-use std::io::{self, BufRead};
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    match args.len() {
-        1 => run_interactive_mode(),
-        2 => {
-            match args[1].as_str() {
-                "--help" | "-h" => print_help(),
-                "--version" | "-v" => print_version(),
-                "--demo" => run_demo(),
-                "--json" => run_json_mode(),
-                _ => {
-                    // Treat as file path
-                    analyze_file(&args[1], false);
-                }
-            }
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+// Exit codes by error class, so pipelines can tell failures apart
+/// Bad command line
+const EXIT_USAGE: u8 = 2;
+/// Dictionary, lexicon or model cannot be loaded
+const EXIT_LOAD: u8 = 3;
+/// Input or output cannot be read or written, or batch files were unreadable
+const EXIT_IO: u8 = 4;
+/// Text is empty, not Russian or too short, or batch texts were rejected
+const EXIT_INPUT: u8 = 5;
+/// Result cannot be serialized
+const EXIT_INTERNAL: u8 = 6;
+
+/// Failed command: message for stderr and the exit code of its class
+struct Failure {
+    code: u8,
+    message: String,
+}
+
+impl Failure {
+    fn new(code: u8, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
         }
-        3 => {
-            if args[1] == "--json" || args[2] == "--json" {
-                let file_path = if args[1] == "--json" { &args[2] } else { &args[1] };
-                analyze_file(file_path, true);
-            } else {
-                eprintln!("Unknown arguments. Use --help for usage information.");
-            }
+    }
+
+    fn usage(message: &str) -> Self {
+        Self::new(EXIT_USAGE, format!("{message}. Use --help for usage information."))
+    }
+
+    /// Failure for a library error, with `context` before its message
+    fn error(context: &str, error: impl Into<Error>) -> Self {
+        let error = error.into();
+        Self::new(exit_code(&error), format!("{context}: {error}"))
+    }
+}
+
+/// Exit code of the class a library error belongs to
+fn exit_code(error: &Error) -> u8 {
+    match error {
+        Error::Dictionary(_) | Error::Lexicon(_) | Error::Model(_) => EXIT_LOAD,
+        Error::Batch(_) | Error::Io(_) => EXIT_IO,
+        Error::EmptyText | Error::NotRussian { .. } | Error::TooShort { .. } => EXIT_INPUT,
+        Error::Json(_) => EXIT_INTERNAL,
+    }
+}
+
+/// Parsed command-line options
+#[derive(Default)]
+struct Options {
+    json: bool,
+    demo: bool,
+    tokens: bool,
+    batch: bool,
+    coverage: bool,
+    serve: bool,
+    dictionary: bool,
+    expand: bool,
+    narrative: bool,
+    tsv: bool,
+    threads: Option<usize>,
+    min_words: Option<usize>,
+    model: Option<String>,
+    backend: Option<String>,
+    lexicon: Option<String>,
+    output: Option<String>,
+    addr: Option<String>,
+    max_body: Option<usize>,
+    file: Option<String>,
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("{}", failure.message);
+            ExitCode::from(failure.code)
         }
-        _ => {
-            eprintln!("Too many arguments. Use --help for usage information.");
+    }
+}
+
+fn run() -> Result<(), Failure> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut options = Options::default();
+    let mut iter = args.iter().peekable();
+    match iter
+        .next_if(|arg| matches!(arg.as_str(), "batch" | "coverage" | "serve" | "dictionary"))
+        .map(String::as_str)
+    {
+        Some("batch") => options.batch = true,
+        Some("coverage") => options.coverage = true,
+        Some("serve") => options.serve = true,
+        Some(_) => options.dictionary = true,
+        None => {}
+    }
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                print_help();
+                return Ok(());
+            }
+            "--version" | "-v" => {
+                print_version();
+                return Ok(());
+            }
+            "--demo" => options.demo = true,
+            "--json" => options.json = true,
+            "--tokens" => options.tokens = true,
+            "--expand" => options.expand = true,
+            "--narrative" => options.narrative = true,
+            "--model" => {
+                let Some(path) = iter.next() else {
+                    return Err(Failure::usage("Option --model requires a path"));
+                };
+                options.model = Some(path.clone());
+            }
+            "--backend" => {
+                let Some(name) = iter.next() else {
+                    return Err(Failure::usage("Option --backend requires a name"));
+                };
+                options.backend = Some(name.clone());
+            }
+            "--lexicon" => {
+                let Some(path) = iter.next() else {
+                    return Err(Failure::usage("Option --lexicon requires a path"));
+                };
+                options.lexicon = Some(path.clone());
+            }
+            "--output" | "-o" => {
+                let Some(path) = iter.next() else {
+                    return Err(Failure::usage("Option --output requires a path"));
+                };
+                options.output = Some(path.clone());
+            }
+            "--addr" => {
+                let Some(addr) = iter.next() else {
+                    return Err(Failure::usage("Option --addr requires HOST:PORT"));
+                };
+                options.addr = Some(addr.clone());
+            }
+            "--max-body" => {
+                let Some(bytes) = iter.next().and_then(|n| n.parse().ok()).filter(|&n| n > 0) else {
+                    return Err(Failure::usage("Option --max-body requires a positive number of bytes"));
+                };
+                options.max_body = Some(bytes);
+            }
+            "--min-words" => {
+                let Some(words) = iter.next().and_then(|n| n.parse().ok()) else {
+                    return Err(Failure::usage("Option --min-words requires a number"));
+                };
+                options.min_words = Some(words);
+            }
+            "--tsv" => options.tsv = true,
+            "--threads" | "-j" => {
+                let Some(threads) = iter.next().and_then(|n| n.parse().ok()).filter(|&n| n > 0) else {
+                    return Err(Failure::usage("Option --threads requires a positive number"));
+                };
+                options.threads = Some(threads);
+            }
+            other if other.starts_with("--") => {
+                return Err(Failure::usage("Unknown arguments"));
+            }
+            other => {
+                if options.file.is_some() {
+                    return Err(Failure::usage("Too many arguments"));
+                }
+                options.file = Some(other.to_string());
+            }
         }
     }
+
+    if options.dictionary {
+        return write_dictionary_bundle(options.output.as_deref());
+    }
+
+    let classifier = match &options.model {
+        Some(path) => Classifier::load(path)
+            .map_err(|e| Failure::error(&format!("Ошибка загрузки модели '{path}'"), e))?,
+        None => Classifier::new(),
+    };
+
+    let mut lexicon = match &options.lexicon {
+        Some(path) => Arc::new(
+            Lexicon::load(path).map_err(|e| Failure::error(&format!("Ошибка загрузки словаря '{path}'"), e))?,
+        ),
+        None => Lexicon::shared_builtin(),
+    };
+    if options.expand {
+        let matching = MatchOptions {
+            lemmas: true,
+            aspect_pairs: true,
+            prefixes: true,
+        };
+        lexicon = Arc::new(Lexicon::clone(&lexicon).with_matching(matching));
+    }
+
+    let mut analyzer = build_analyzer(options.backend.as_deref(), Arc::clone(&lexicon))?
+        .with_narrative_only(options.narrative);
+    if let Some(words) = options.min_words {
+        analyzer = analyzer.with_min_words(words);
+    }
+
+    if options.demo {
+        run_demo(&analyzer, &classifier);
+        return Ok(());
+    }
+
+    if options.batch {
+        let Some(source) = &options.file else {
+            return Err(Failure::usage("Command batch requires a directory, pattern or manifest"));
+        };
+        return run_batch(source, &options, &analyzer, &classifier);
+    }
+
+    if options.serve {
+        return run_server(&options, analyzer, classifier);
+    }
+
+    if options.coverage {
+        let Some(source) = &options.file else {
+            return Err(Failure::usage("Command coverage requires a directory, pattern or manifest"));
+        };
+        let forms_only = Lexicon::clone(&lexicon).with_matching(MatchOptions::FORMS_ONLY);
+        let baseline = build_analyzer(options.backend.as_deref(), Arc::new(forms_only))?;
+        return run_coverage(source, &options, &baseline, &analyzer);
+    }
+
+    match (&options.file, options.json) {
+        (Some(path), _) => analyze_file(path, &options, &analyzer, &classifier),
+        (None, true) => run_json_mode(&options, &analyzer, &classifier),
+        (None, false) => run_interactive_mode(&options, &analyzer, &classifier),
+    }
+}
+
+/// Create the analyzer for the selected backend, reporting unknown names
+/// and a dictionary that cannot be loaded
+fn build_analyzer(backend: Option<&str>, lexicon: Arc<Lexicon>) -> Result<TextAnalyzer, Failure> {
+    match backend {
+        None | Some("rsmorphy") => TextAnalyzer::try_with_lexicon(lexicon)
+            .map_err(|e| Failure::error("Ошибка загрузки словаря OpenCorpora", e)),
+        Some("rules") => Ok(TextAnalyzer::with_backend(MorphAnalyzer::with_lexicon(lexicon))),
+        Some(other) => Err(Failure::usage(&format!("Unknown backend '{other}' (use rsmorphy or rules)"))),
+    }
 }
 
 fn print_help() {
@@ -45,6 +269,10 @@ fn print_help() {
 
 ИСПОЛЬЗОВАНИЕ:
     mdpredict [OPTIONS] [FILE]
+    mdpredict batch <DIR|PATTERN|MANIFEST.csv> [--output FILE] [--tsv] [--threads N] [--model PATH] [--lexicon PATH] [--narrative] [--min-words N]
+    mdpredict coverage <DIR|PATTERN|MANIFEST.csv> [--lexicon PATH] [--expand] [--json]
+    mdpredict serve [--addr HOST:PORT] [--threads N] [--max-body BYTES] [--model PATH] [--lexicon PATH] [--narrative] [--min-words N]
+    mdpredict dictionary --output FILE.tar
 
 ОПИСАНИЕ:
     Предсказание психических расстройств на основе структурных
@@ -59,12 +287,78 @@ fn print_help() {
     -v, --version   Показать версию
     --demo          Запустить демонстрацию с примерами из статьи
     --json          Вывести результат в формате JSON
+    --model PATH    Использовать обученную модель (.json или .bin)
+    --tokens        Вывести разметку слов и предложений (для проверки подсчётов)
+    --backend NAME  Морфологический анализатор: rsmorphy (по умолчанию)
+                    или rules (правила, без словаря)
+    --lexicon PATH  Дополнить или заменить встроенные списки слов
+                    (эмоции, предикаты, паразиты, стоп-слова, семья)
+                    файлом .toml, .json или .txt
+    --expand        Сопоставлять со словарём также видовые пары
+                    (решать — решить) и приставочные глаголы
+                    (плакать — заплакать)
+    --narrative     Считать метрики только по авторскому повествованию,
+                    без прямой речи (цитат и реплик диалога)
+    --min-words N   Наименьшее число слов в тексте (по умолчанию 5,
+                    0 — без ограничения); более короткий текст,
+                    пустой или не на русском языке — ошибка
+
+ПАКЕТНАЯ ОБРАБОТКА (batch):
+    -o, --output FILE  Записать таблицу в файл (по умолчанию stdout)
+    --tsv              Разделитель — табуляция (также для --output *.tsv)
+    -j, --threads N    Число потоков (по умолчанию — все ядра)
+
+    Источник: каталог (все *.txt), шаблон имени ("corpus/*.txt") или
+    манифест .csv/.tsv со столбцами id и path. Одна строка на текст:
+    id, все метрики, вероятности групп, предсказанная группа и
+    версия словаря (lexicon). Пустые, нерусские и короче --min-words
+    тексты пропускаются с сообщением в stderr.
+
+ОХВАТ СЛОВАРЯ (coverage):
+    Сколько слов каждой категории словаря найдено по словоформам и
+    сколько добавило сопоставление по леммам (и --expand), с примерами.
+
+HTTP-СЕРВЕР (serve):
+    --addr HOST:PORT   Адрес (по умолчанию 127.0.0.1:8080)
+    --max-body BYTES   Наибольший размер тела запроса (по умолчанию 1 МиБ)
+    -j, --threads N    Число одновременно обслуживаемых запросов
+
+    POST /analyze  {{"text": "..."}}             — отчёт как у --json
+    POST /classify {{"text": "..."}} или {{"metrics": {{...}}}}
+                                               — классификация, объяснение, профиль
+    POST /batch    {{"texts": ["...", {{"id": "...", "text": "..."}}]}}
+                                               — {{"results": [отчёт для каждого текста]}}
+    GET  /health                               — состояние и версия словаря
+    Ошибки: {{"error": {{"code": "...", "message": "..."}}}} с кодом 4xx/5xx;
+    пустой, нерусский или короче --min-words текст — 422.
+
+СЛОВАРЬ ДЛЯ WEBASSEMBLY (dictionary):
+    Упаковать словарь OpenCorpora в один файл .tar для сборки
+    WebAssembly: страница загружает его и передаёт в new TextAnalyzer(...).
 
 ПРИМЕРЫ:
     mdpredict                   Интерактивный режим
     mdpredict text.txt          Анализ файла
     mdpredict --json text.txt   Анализ с JSON-выводом
+    mdpredict --tokens text.txt Разметка слов с леммами, частями речи и флагами
+    mdpredict --model site.json text.txt
+                                Анализ с собственной моделью
+    mdpredict --lexicon clinic.toml text.txt
+                                Анализ с собственным словарём
     mdpredict --demo            Демонстрация
+    mdpredict batch corpus/ -o results.csv
+                                Пакетный анализ каталога в CSV
+    mdpredict serve --addr 0.0.0.0:8080
+                                HTTP JSON API
+
+КОДЫ ВОЗВРАТА:
+    0  Успешно
+    2  Неверные аргументы командной строки
+    3  Не загружен словарь OpenCorpora, списки слов (--lexicon) или модель
+    4  Ошибка чтения или записи файла; в batch — непрочитанные файлы
+    5  Текст пустой, не на русском языке или короче --min-words;
+       в batch — пропущенные по этой причине тексты
+    6  Результат не удалось вывести (ошибка сериализации)
 
 ВАЖНОЕ ПРИМЕЧАНИЕ:
     Данный инструмент предназначен ТОЛЬКО для исследовательских целей.
@@ -79,7 +373,7 @@ fn print_version() {
     println!("Основан на исследовании Смерчинской, Трегубенко, Исаевой (2026)");
 }
 
-fn run_interactive_mode() {
+fn run_interactive_mode(options: &Options, analyzer: &TextAnalyzer, classifier: &Classifier) -> Result<(), Failure> {
     println!("=== Анализатор структурных характеристик письменной речи ===");
     println!();
     println!("Введите текст для анализа (для завершения введите пустую строку):");
@@ -89,82 +383,321 @@ fn run_interactive_mode() {
     let mut text = String::new();
 
     for line in stdin.lock().lines() {
-        match line {
-            Ok(line) => {
-                if line.is_empty() {
-                    break;
-                }
-                text.push_str(&line);
-                text.push('\n');
-            }
-            Err(e) => {
-                eprintln!("Ошибка чтения: {}", e);
-                return;
-            }
+        let line = line.map_err(|e| Failure::error("Ошибка чтения", e))?;
+        if line.is_empty() {
+            break;
         }
+        text.push_str(&line);
+        text.push('\n');
     }
 
     if text.trim().is_empty() {
-        println!("Текст не введён.");
-        return;
+        return Err(Failure::new(EXIT_INPUT, "Текст не введён."));
+    }
+
+    if options.tokens {
+        return print_tokens(&text, false, analyzer).map_err(|e| Failure::error("Ошибка анализа", e));
     }
 
-    let report = get_full_report(&text);
+    let metrics = analyzer.try_analyze(&text).map_err(|e| Failure::error("Ошибка анализа", e))?;
+    let result = classifier.classify(&metrics);
+    let report = classifier.get_detailed_report(&metrics, &result);
     println!("\n{}", report);
+    Ok(())
 }
 
-fn analyze_file(path: &str, json_output: bool) {
-    match fs::read_to_string(path) {
-        Ok(text) => {
-            if json_output {
-                let (metrics, result) = analyze_and_classify(&text);
-                let output = serde_json::json!({
-                    "metrics": metrics,
-                    "classification": result
-                });
-                println!("{}", serde_json::to_string_pretty(&output).unwrap());
-            } else {
-                let report = get_full_report(&text);
-                println!("{}", report);
-            }
-        }
-        Err(e) => {
-            eprintln!("Ошибка чтения файла '{}': {}", path, e);
-        }
+fn analyze_file(path: &str, options: &Options, analyzer: &TextAnalyzer, classifier: &Classifier) -> Result<(), Failure> {
+    let text = fs::read_to_string(path).map_err(|e| Failure::error(&format!("Ошибка чтения файла '{path}'"), e))?;
+    if options.tokens {
+        print_tokens(&text, options.json, analyzer)
+            .map_err(|e| Failure::error(&format!("Ошибка анализа '{path}'"), e))
+    } else if options.json {
+        print_json(&text, analyzer, classifier)
+            .map_err(|e| Failure::error(&format!("Ошибка анализа '{path}'"), e))
+    } else {
+        let metrics = analyzer
+            .try_analyze(&text)
+            .map_err(|e| Failure::error(&format!("Ошибка анализа '{path}'"), e))?;
+        let result = classifier.classify(&metrics);
+        let report = classifier.get_detailed_report(&metrics, &result);
+        println!("{}", report);
+        Ok(())
     }
 }
 
-fn run_json_mode() {
+fn run_json_mode(options: &Options, analyzer: &TextAnalyzer, classifier: &Classifier) -> Result<(), Failure> {
     println!("Введите текст для анализа (завершите вводом EOF или Ctrl+D):");
 
     let stdin = io::stdin();
     let mut text = String::new();
 
     for line in stdin.lock().lines() {
-        match line {
-            Ok(line) => {
-                text.push_str(&line);
-                text.push('\n');
+        let line = line.map_err(|e| Failure::error("Ошибка чтения", e))?;
+        text.push_str(&line);
+        text.push('\n');
+    }
+
+    if options.tokens {
+        print_tokens(&text, true, analyzer).map_err(|e| Failure::error("Ошибка анализа", e))
+    } else {
+        print_json(&text, analyzer, classifier).map_err(|e| Failure::error("Ошибка анализа", e))
+    }
+}
+
+/// Print the report of `--json`, rejecting texts `try_analyze` does not accept
+fn print_json(text: &str, analyzer: &TextAnalyzer, classifier: &Classifier) -> Result<(), Error> {
+    let metrics = analyzer.try_analyze(text)?;
+    let output = server::metrics_report(analyzer, classifier, &metrics);
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+/// Print the annotation of `--tokens`, rejecting the same texts as the report
+fn print_tokens(text: &str, json_output: bool, analyzer: &TextAnalyzer) -> Result<(), Error> {
+    let detailed = analyzer.try_analyze_detailed(text)?;
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&detailed)?);
+    } else {
+        print_annotations(&detailed);
+    }
+    Ok(())
+}
+
+fn print_annotations(detailed: &DetailedAnalysis) {
+    println!("=== ПРЕДЛОЖЕНИЯ ===\n");
+    for (i, sentence) in detailed.sentences.iter().enumerate() {
+        println!(
+            "[{}] {}..{} {:?} (клауз: {}): {}",
+            i + 1,
+            sentence.start,
+            sentence.end,
+            sentence.sentence_type,
+            sentence.clause_count,
+            sentence.text
+        );
+        if sentence.clauses.len() > 1 {
+            for (j, clause) in sentence.clauses.iter().enumerate() {
+                println!(
+                    "    клауза {}: {:?} {} (слов: {}, сказуемых: {}, подлежащее: {})",
+                    j + 1,
+                    clause.connector,
+                    clause.conjunction.as_deref().unwrap_or("-"),
+                    clause.words.len(),
+                    clause.predicates.len(),
+                    if clause.subject.is_some() { "есть" } else { "нет" }
+                );
             }
-            Err(_) => break,
         }
     }
 
-    if !text.trim().is_empty() {
-        let (metrics, result) = analyze_and_classify(&text);
+    println!("\n=== СЛОВА ===\n");
+    println!(
+        "{:<4} {:<10} {:<18} {:<18} {:<12} {:<11} {:<11} {:<9} Флаги",
+        "Пр.", "Позиция", "Слово", "Лемма", "Часть речи", "Время", "Форма", "Предикат"
+    );
+    for token in &detailed.tokens {
+        let a = &token.analysis;
+        let mut flags = Vec::new();
+        if a.is_filler {
+            flags.push("паразит");
+        }
+        if a.is_stop_word {
+            flags.push("стоп");
+        }
+        if a.is_emotion_word {
+            flags.push("эмоция");
+        }
+        if a.is_social_interaction {
+            flags.push("соц");
+        }
+        if a.is_egocentrism_marker {
+            flags.push("эго");
+        }
+        if a.is_negation {
+            flags.push("отриц.");
+        } else if a.is_negated {
+            flags.push("под отриц.");
+        }
+        if a.is_auxiliary {
+            flags.push("связка");
+        } else if token.active_voice {
+            flags.push("акт.залог");
+        } else if a.voice == Some(Voice::Passive) {
+            flags.push("пасс.залог");
+        }
+        if a.is_reflexive {
+            flags.push("возвр.");
+        }
+        match a.aspect {
+            Some(Aspect::Perfective) => flags.push("сов.вид"),
+            Some(Aspect::Imperfective) => flags.push("несов.вид"),
+            None => {}
+        }
+        match token.speech {
+            Some(SpeechKind::Direct) => flags.push("пр.речь"),
+            Some(SpeechKind::Reported) => flags.push("косв.речь"),
+            None => {}
+        }
+        if let Some(phrase) = token.phrase.as_ref().filter(|p| p.head) {
+            flags.push(&phrase.phrase);
+        }
+        println!(
+            "{:<4} {:<10} {:<18} {:<18} {:<12} {:<11} {:<11} {:<9} {}",
+            token.sentence.map_or("-".to_string(), |i| (i + 1).to_string()),
+            format!("{}..{}", token.start, token.end),
+            token.text,
+            a.lemma.as_deref().unwrap_or("-"),
+            format!("{:?}", a.pos),
+            a.verb_tense.map_or("-".to_string(), |t| format!("{t:?}")),
+            a.verb_form.map_or("-".to_string(), |f| format!("{f:?}")),
+            a.predicate_type.map_or("-".to_string(), |p| format!("{p:?}")),
+            flags.join(",")
+        );
+    }
+}
+
+/// Write the rsmorphy dictionary as one bundle for the WebAssembly build
+fn write_dictionary_bundle(output: Option<&str>) -> Result<(), Failure> {
+    let Some(path) = output else {
+        return Err(Failure::usage("Command dictionary requires --output FILE"));
+    };
+    fs::File::create(path)
+        .and_then(|file| {
+            let mut writer = io::BufWriter::new(file);
+            bundle::write_bundle(rsmorphy_dict_ru::DICT_PATH, &mut writer)?;
+            writer.flush()
+        })
+        .map_err(|e| Failure::error(&format!("Ошибка записи словаря '{path}'"), e))?;
+    eprintln!("Словарь записан в {path}");
+    Ok(())
+}
+
+fn run_server(options: &Options, analyzer: TextAnalyzer, classifier: Classifier) -> Result<(), Failure> {
+    let addr = options.addr.as_deref().unwrap_or("127.0.0.1:8080");
+    let mut server = Server::new(analyzer, classifier);
+    if let Some(threads) = options.threads {
+        server = server.with_threads(threads);
+    }
+    if let Some(bytes) = options.max_body {
+        server = server.with_max_body(bytes);
+    }
+    eprintln!("Сервер слушает http://{addr} (POST /analyze, /classify, /batch; GET /health)");
+    server
+        .serve(addr)
+        .map_err(|e| Failure::error(&format!("Ошибка запуска сервера на '{addr}'"), e))
+}
+
+fn run_batch(source: &str, options: &Options, analyzer: &TextAnalyzer, classifier: &Classifier) -> Result<(), Failure> {
+    let inputs = batch::collect_inputs(source).map_err(|e| Failure::error("Ошибка пакетной обработки", e))?;
+
+    let format = match &options.output {
+        _ if options.tsv => ExportFormat::Tsv,
+        Some(path) => ExportFormat::from_path(Path::new(path)),
+        None => ExportFormat::Csv,
+    };
+    let sink: Box<dyn Write> = match &options.output {
+        Some(path) => {
+            let file = fs::File::create(path)
+                .map_err(|e| Failure::error(&format!("Ошибка создания файла '{path}'"), e))?;
+            Box::new(io::BufWriter::new(file))
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let threads = options.threads.unwrap_or_else(batch::default_threads);
+    let (skipped, unreadable) = write_batch(&inputs, sink, format, analyzer, classifier, threads)
+        .map_err(|e| Failure::error("Ошибка записи результатов", e))?;
+    let summary = format!("Готово: {} текстов обработано, {} пропущено", inputs.len() - skipped, skipped);
+    if unreadable > 0 {
+        return Err(Failure::new(EXIT_IO, summary));
+    }
+    if skipped > 0 {
+        return Err(Failure::new(EXIT_INPUT, summary));
+    }
+    eprintln!("{summary}");
+    Ok(())
+}
+
+fn run_coverage(source: &str, options: &Options, baseline: &TextAnalyzer, analyzer: &TextAnalyzer) -> Result<(), Failure> {
+    let inputs = batch::collect_inputs(source).map_err(|e| Failure::error("Ошибка чтения корпуса", e))?;
+    let texts: Vec<String> = inputs
+        .iter()
+        .filter_map(|input| match fs::read_to_string(&input.path) {
+            Ok(text) => Some(text),
+            Err(e) => {
+                eprintln!("Пропущен '{}': {e}", input.path.display());
+                None
+            }
+        })
+        .collect();
+
+    let report = CoverageReport::compare(baseline, analyzer, &texts);
+    if options.json {
         let output = serde_json::json!({
-            "metrics": metrics,
-            "classification": result
+            "lexicon": analyzer.lexicon().info(),
+            "matching": analyzer.lexicon().matching(),
+            "coverage": report,
         });
-        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        let json = serde_json::to_string_pretty(&output).map_err(|e| Failure::error("Ошибка вывода JSON", e))?;
+        println!("{json}");
+    } else {
+        let matching = analyzer.lexicon().matching();
+        let mut mode = vec![if matching.lemmas { "леммы" } else { "словоформы" }];
+        if matching.aspect_pairs {
+            mode.push("видовые пары");
+        }
+        if matching.prefixes {
+            mode.push("приставки");
+        }
+        println!("Словарь: {}", analyzer.lexicon().info());
+        println!("Было: словоформы. Стало: {}.", mode.join(", "));
+        println!("{}", report.to_table());
     }
+    Ok(())
 }
 
-fn run_demo() {
-    println!("=== ДЕМОНСТРАЦИЯ АНАЛИЗАТОРА ===\n");
+/// Analyse all inputs and write one row per text; returns the number of
+/// skipped texts and how many of them could not be read
+fn write_batch(
+    inputs: &[batch::BatchInput],
+    sink: Box<dyn Write>,
+    format: ExportFormat,
+    analyzer: &TextAnalyzer,
+    classifier: &Classifier,
+    threads: usize,
+) -> io::Result<(usize, usize)> {
+    /// Inputs analysed between progress messages
+    const CHUNK_SIZE: usize = 500;
+
+    let mut writer = BatchWriter::with_lexicon(sink, format, &analyzer.lexicon().info())?;
+    let mut done = 0;
+    let mut skipped = 0;
+    let mut unreadable = 0;
+    for chunk in inputs.chunks(CHUNK_SIZE) {
+        let outcomes = batch::process_inputs(chunk, analyzer, classifier, threads);
+        for (input, outcome) in chunk.iter().zip(outcomes) {
+            match outcome {
+                Ok((metrics, result)) => writer.write_row(&input.id, &metrics, &result)?,
+                Err(e) => {
+                    eprintln!("Пропущен '{}': {e}", input.path.display());
+                    skipped += 1;
+                    if !e.is_input_error() {
+                        unreadable += 1;
+                    }
+                }
+            }
+        }
+        done += chunk.len();
+        if inputs.len() > CHUNK_SIZE {
+            eprintln!("Обработано {}/{}", done, inputs.len());
+        }
+    }
+    writer.finish()?;
+    Ok((skipped, unreadable))
+}
 
-    let analyzer = TextAnalyzer::new();
-    let classifier = Classifier::new();
+fn run_demo(analyzer: &TextAnalyzer, classifier: &Classifier) {
+    println!("=== ДЕМОНСТРАЦИЯ АНАЛИЗАТОРА ===\n");
 
     // Example from the paper - schizophrenia patient
     println!("--- Пример 1: Текст пациента с шизофренией (из статьи) ---\n");
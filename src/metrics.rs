@@ -5,7 +5,11 @@
 use serde::{Deserialize, Serialize};
 
 /// All structural characteristics of written speech analyzed in the study
+///
+/// Missing fields deserialize as zero, so a classifier can be given only the
+/// features it uses.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TextMetrics {
     // Basic metrics
     /// Total word count (absolute value)
@@ -22,6 +26,8 @@ pub struct TextMetrics {
     pub complex_sentences: usize,
     /// Simple sentences (простые предложения)
     pub simple_sentences: usize,
+    /// Mixed sentences (сложные с разными видами связи), not counted as compound or complex
+    pub mixed_sentences: usize,
 
     // Lexical metrics (as percentage of total words)
     /// Lexical diversity index (unique words / total words * 100)
@@ -34,8 +40,18 @@ pub struct TextMetrics {
     pub internal_predicates: f64,
 
     // Verb metrics (as percentage)
-    /// Verbs in active voice
+    /// Verbs in active voice, reflexive ones included ("катался")
     pub active_voice_verbs: f64,
+    /// Passive participles ("отделана"); an analytic passive ("был построен") counts once
+    pub passive_voice_verbs: f64,
+    /// Reflexive verb forms in "-ся"/"-сь"
+    pub reflexive_verbs: f64,
+    /// Perfective verb forms ("сделал", "пойду")
+    pub perfective_verbs: f64,
+    /// Imperfective verb forms, the compound future "буду делать" included
+    pub imperfective_verbs: f64,
+    /// Perfective verbs among verbs of known aspect (percentage)
+    pub perfective_aspect_ratio: f64,
     /// Past tense verbs
     pub past_tense_verbs: f64,
     /// Present tense verbs
@@ -84,9 +100,36 @@ pub struct TextMetrics {
     // Semantic categories (as percentage)
     /// Words meaning "social interaction" (constructions with "мы" and 1st person plural verbs)
     pub social_interaction_words: f64,
-    /// Words meaning "emotions" ("весело", "грустно", "печальный", etc.)
+    /// Words meaning "emotions" ("весело", "грустно", "печальный", etc.), negated ones excluded
     pub emotion_words: f64,
 
+    // Emotion profile of words with affective ratings (see `emotion`), negated ones excluded
+    /// Rated emotion words with positive valence (percentage of rated words)
+    pub positive_emotion_share: f64,
+    /// Rated emotion words with negative valence (percentage of rated words)
+    pub negative_emotion_share: f64,
+    /// Mean valence of rated emotion words (−1 … 1, 0 if there are none)
+    pub mean_valence: f64,
+    /// Mean arousal of rated emotion words (0 … 1, 0 if there are none)
+    pub mean_arousal: f64,
+    /// Normalized entropy of emotion categories (0 = at most one category, 1 = all six equally)
+    pub emotion_diversity: f64,
+
+    // Negation (see `negation`)
+    /// Negators: "не", "ни", "нет", "никогда", "ничего", ... (percentage of words)
+    pub negation_density: f64,
+    /// Internal predicates in the scope of "не"/"ни" ("не помню"), as percentage of internal predicates
+    pub negated_internal_predicates: f64,
+    /// Emotion words in the scope of "не"/"ни" ("не страшно"), as percentage of all emotion words,
+    /// negated or not; these words are not counted in `emotion_words`
+    pub negated_emotion_words: f64,
+
+    // Other people's speech (percentage of all words, also in narrative-only mode)
+    /// Words in direct speech: quotations and dialogue lines ("— Не плачь")
+    pub direct_speech_share: f64,
+    /// Words in reported speech: clauses after a speech verb ("сказала, что ...")
+    pub reported_speech_share: f64,
+
     // Egocentrism index
     /// Egocentrism index - pronouns "Я" and derivatives ("меня", "мой"), including reflexive ("себя")
     pub egocentrism_index: f64,
@@ -123,6 +166,16 @@ pub enum DiagnosticGroup {
     BipolarDisorder,
 }
 
+impl DiagnosticGroup {
+    /// All diagnostic groups in the order used throughout the paper
+    pub const ALL: [DiagnosticGroup; 4] = [
+        DiagnosticGroup::Healthy,
+        DiagnosticGroup::Schizophrenia,
+        DiagnosticGroup::PersonalityDisorder,
+        DiagnosticGroup::BipolarDisorder,
+    ];
+}
+
 impl std::fmt::Display for DiagnosticGroup {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -154,6 +207,57 @@ pub struct GroupScores {
     pub bipolar_disorder: f64,
 }
 
+/// Contribution of a single feature to a discriminant score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureContribution {
+    /// Feature name (see `classifier::FEATURE_NAMES`)
+    pub feature: String,
+    /// Feature value computed from the text
+    pub value: f64,
+    /// Discriminant coefficient
+    pub coefficient: f64,
+    /// coefficient × value
+    pub contribution: f64,
+}
+
+/// Breakdown of one group's discriminant score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupExplanation {
+    pub group: DiagnosticGroup,
+    /// Raw discriminant score (before softmax)
+    pub score: f64,
+    /// Constant term of the discriminant function
+    pub constant: f64,
+    /// Feature contributions, sorted by absolute magnitude
+    pub contributions: Vec<FeatureContribution>,
+}
+
+/// Difference in a feature's contribution between the winning group and the runner-up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureDifference {
+    pub feature: String,
+    pub value: f64,
+    /// Winner contribution minus runner-up contribution (positive favours the winner)
+    pub difference: f64,
+}
+
+/// Explanation of why a text was assigned to its group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassificationExplanation {
+    /// Group with the highest score
+    pub winner: DiagnosticGroup,
+    /// Group with the second highest score
+    pub runner_up: Option<DiagnosticGroup>,
+    /// Per-group score breakdowns, in descending score order
+    pub groups: Vec<GroupExplanation>,
+    /// Features that pushed the decision toward the winner, strongest first
+    pub toward_winner: Vec<FeatureDifference>,
+    /// Features that pushed the decision toward the runner-up, strongest first
+    pub toward_runner_up: Vec<FeatureDifference>,
+    /// Difference of the constant terms (winner minus runner-up)
+    pub constant_difference: f64,
+}
+
 /// Reference values from the research paper (Table 2)
 #[derive(Debug, Clone)]
 pub struct ReferenceValues {
@@ -163,6 +267,17 @@ pub struct ReferenceValues {
 }
 
 impl ReferenceValues {
+    /// Reference values for all groups, in `DiagnosticGroup::ALL` order
+    #[must_use]
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::healthy(),
+            Self::schizophrenia(),
+            Self::personality_disorder(),
+            Self::bipolar_disorder(),
+        ]
+    }
+
     /// Create reference values for schizophrenia group based on the paper (Table 2)
     #[must_use]
     pub fn schizophrenia() -> Self {
@@ -0,0 +1,225 @@
+//! Serializable classifier model files
+//! A model pins the exact discriminant functions, priors and training metadata
+//! so results can be reproduced across sites and over time
+
+use crate::classifier::CanonicalFunction;
+use crate::metrics::DiagnosticGroup;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Current model file schema version
+pub const MODEL_SCHEMA_VERSION: u32 = 1;
+
+/// On-disk model format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelFormat {
+    /// Human-readable JSON
+    Json,
+    /// Compact binary encoding (bincode)
+    Bincode,
+}
+
+impl ModelFormat {
+    /// Guess the format from a file extension (`.json` or `.bin`/`.bincode`)
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "json" => Some(ModelFormat::Json),
+            "bin" | "bincode" => Some(ModelFormat::Bincode),
+            _ => None,
+        }
+    }
+}
+
+/// Discriminant function of a single group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupModel {
+    pub group: DiagnosticGroup,
+    /// Coefficients in the order of `ClassifierModel::features`
+    pub coefficients: Vec<f64>,
+    pub constant: f64,
+    pub prior: f64,
+}
+
+/// Information about the data a model was trained on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrainingMetadata {
+    /// Total number of training texts
+    pub samples: usize,
+    /// Number of training texts per group
+    pub group_counts: Vec<(DiagnosticGroup, usize)>,
+    /// Training time as seconds since the Unix epoch
+    pub trained_at: Option<u64>,
+    /// Free-form description (site, corpus, annotator, ...)
+    pub description: Option<String>,
+}
+
+/// Complete classifier model as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassifierModel {
+    pub schema_version: u32,
+    /// Version of this library that produced the model
+    pub library_version: String,
+    /// Feature names, defining the order of all coefficient vectors
+    pub features: Vec<String>,
+    pub groups: Vec<GroupModel>,
+    pub canonical_functions: Vec<CanonicalFunction>,
+    /// `None` for the built-in hand-tuned model
+    pub training: Option<TrainingMetadata>,
+}
+
+/// Error when reading, writing or validating a model file
+#[derive(Debug)]
+pub enum ModelError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Bincode(bincode::Error),
+    /// File extension does not identify a known format
+    UnknownFormat(String),
+    /// Model was written by a newer, incompatible schema
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
+    /// Model features do not match the features this library computes
+    FeatureMismatch { expected: Vec<String>, found: Vec<String> },
+    /// Model contains no usable discriminant functions
+    Invalid(String),
+}
+
+impl std::fmt::Display for ModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelError::Io(e) => write!(f, "model I/O error: {e}"),
+            ModelError::Json(e) => write!(f, "invalid JSON model: {e}"),
+            ModelError::Bincode(e) => write!(f, "invalid binary model: {e}"),
+            ModelError::UnknownFormat(path) => {
+                write!(f, "cannot determine model format of '{path}' (use .json or .bin)")
+            }
+            ModelError::UnsupportedSchemaVersion { found, supported } => write!(
+                f,
+                "model schema version {found} is not supported (max {supported})"
+            ),
+            ModelError::FeatureMismatch { expected, found } => write!(
+                f,
+                "model features {found:?} do not match expected {expected:?}"
+            ),
+            ModelError::Invalid(reason) => write!(f, "invalid model: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ModelError::Io(e) => Some(e),
+            ModelError::Json(e) => Some(e),
+            ModelError::Bincode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ModelError {
+    fn from(e: std::io::Error) -> Self {
+        ModelError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ModelError {
+    fn from(e: serde_json::Error) -> Self {
+        ModelError::Json(e)
+    }
+}
+
+impl From<bincode::Error> for ModelError {
+    fn from(e: bincode::Error) -> Self {
+        ModelError::Bincode(e)
+    }
+}
+
+impl ClassifierModel {
+    /// Write the model to a file in the given format
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError`] if the file cannot be written.
+    pub fn save(&self, path: &Path, format: ModelFormat) -> Result<(), ModelError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        match format {
+            ModelFormat::Json => serde_json::to_writer_pretty(&mut writer, self)?,
+            ModelFormat::Bincode => bincode::serialize_into(&mut writer, self)?,
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a model from a file in the given format
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError`] if the file cannot be read or decoded.
+    pub fn load(path: &Path, format: ModelFormat) -> Result<Self, ModelError> {
+        let reader = BufReader::new(File::open(path)?);
+        let model = match format {
+            ModelFormat::Json => serde_json::from_reader(reader)?,
+            ModelFormat::Bincode => bincode::deserialize_from(reader)?,
+        };
+        Ok(model)
+    }
+
+    /// Check the schema version and feature list against this library
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModelError`] describing the first incompatibility found.
+    pub fn validate(&self, expected_features: &[&str]) -> Result<(), ModelError> {
+        if self.schema_version > MODEL_SCHEMA_VERSION {
+            return Err(ModelError::UnsupportedSchemaVersion {
+                found: self.schema_version,
+                supported: MODEL_SCHEMA_VERSION,
+            });
+        }
+        if self.features.iter().map(String::as_str).ne(expected_features.iter().copied()) {
+            return Err(ModelError::FeatureMismatch {
+                expected: expected_features.iter().map(ToString::to_string).collect(),
+                found: self.features.clone(),
+            });
+        }
+        if self.groups.is_empty() {
+            return Err(ModelError::Invalid("no group functions".to_string()));
+        }
+        for (i, group) in self.groups.iter().enumerate() {
+            if self.groups[..i].iter().any(|g| g.group == group.group) {
+                return Err(ModelError::Invalid(format!(
+                    "{:?} has more than one function",
+                    group.group
+                )));
+            }
+            if group.coefficients.len() != expected_features.len() {
+                return Err(ModelError::Invalid(format!(
+                    "{:?} has {} coefficients, expected {}",
+                    group.group,
+                    group.coefficients.len(),
+                    expected_features.len()
+                )));
+            }
+            let finite = group.coefficients.iter().all(|c| c.is_finite())
+                && group.constant.is_finite()
+                && group.prior.is_finite();
+            if !finite {
+                return Err(ModelError::Invalid(format!(
+                    "{:?} has a non-finite coefficient, constant or prior",
+                    group.group
+                )));
+            }
+        }
+        for function in &self.canonical_functions {
+            if function.coefficients.len() != expected_features.len() {
+                return Err(ModelError::Invalid(
+                    "canonical function has wrong number of coefficients".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
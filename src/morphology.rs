@@ -1,115 +1,36 @@
 //! Morphological analysis for Russian text
 //! Rule-based approach using dictionaries and suffix patterns
 
-use crate::dictionaries::{PREPOSITIONS, ALL_CONJUNCTIONS, COORDINATING_CONJUNCTIONS, SUBORDINATING_CONJUNCTIONS, FIRST_PERSON_SINGULAR, FIRST_PERSON_PLURAL, SECOND_PERSON_SINGULAR, SECOND_PERSON_PLURAL, THIRD_PERSON_SINGULAR, THIRD_PERSON_PLURAL, POSSESSIVE_FIRST_PERSON, INTERNAL_PREDICATES, EXTERNAL_PREDICATES, ends_with_any, INFINITIVE_ENDINGS, PARTICIPLE_ENDINGS, PAST_TENSE_ENDINGS, KNOWN_ADVERBS, ADJECTIVE_ENDINGS, FILLER_WORDS, STOP_WORDS, EMOTION_WORDS};
-
-/// Part of speech categories
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PartOfSpeech {
-    Noun,
-    Verb,
-    Adjective,
-    Adverb,
-    Pronoun,
-    Preposition,
-    Conjunction,
-    Numeral,
-    Particle,
-    Interjection,
-    Unknown,
+// Same word-level types as every other backend (formerly duplicated here)
+pub use crate::backend::{
+    PartOfSpeech, PredicateType, PronounNumber, PronounPerson, VerbForm, VerbTense, WordAnalysis,
+};
+use crate::analytic;
+use crate::backend::{is_reflexive_form, MorphologyBackend, Voice};
+use crate::dictionaries::{PREPOSITIONS, ALL_CONJUNCTIONS, COORDINATING_CONJUNCTIONS, SUBORDINATING_CONJUNCTIONS, FIRST_PERSON_SINGULAR, FIRST_PERSON_PLURAL, SECOND_PERSON_SINGULAR, SECOND_PERSON_PLURAL, THIRD_PERSON_SINGULAR, THIRD_PERSON_PLURAL, POSSESSIVE_FIRST_PERSON, ends_with_any, INFINITIVE_ENDINGS, PARTICIPLE_ENDINGS, PASSIVE_PARTICIPLE_ENDINGS, PAST_TENSE_ENDINGS, KNOWN_ADVERBS, ADJECTIVE_ENDINGS};
+use crate::lexicon::{Lexicon, LexiconCategory};
+use std::sync::Arc;
+
+/// Rule-based morphological analyzer
+///
+/// A lightweight fallback that works without dictionary files.
+#[derive(Clone)]
+pub struct MorphAnalyzer {
+    lexicon: Arc<Lexicon>,
 }
 
-/// Verb tense
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum VerbTense {
-    Past,
-    Present,
-    Future,
-    Infinitive,
-    Unknown,
-}
-
-/// Verb form
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum VerbForm {
-    Finite,       // личная форма
-    Infinitive,   // инфинитив
-    Participle,   // причастие
-    Gerund,       // деепричастие
-    Unknown,
-}
-
-/// Predicate type
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PredicateType {
-    External,  // внешние действия
-    Internal,  // внутренние переживания
-    Neither,
-}
-
-/// Pronoun person
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PronounPerson {
-    First,
-    Second,
-    Third,
-    Reflexive,
-    Unknown,
-}
-
-/// Pronoun number
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PronounNumber {
-    Singular,
-    Plural,
-    Unknown,
-}
-
-/// Word analysis result
-#[derive(Debug, Clone)]
-#[allow(clippy::struct_excessive_bools)]
-pub struct WordAnalysis {
-    pub word: String,
-    pub pos: PartOfSpeech,
-    pub verb_tense: Option<VerbTense>,
-    pub verb_form: Option<VerbForm>,
-    pub predicate_type: Option<PredicateType>,
-    pub pronoun_person: Option<PronounPerson>,
-    pub pronoun_number: Option<PronounNumber>,
-    pub is_filler: bool,
-    pub is_stop_word: bool,
-    pub is_emotion_word: bool,
-    pub is_social_interaction: bool,
-    pub is_egocentrism_marker: bool,
-}
-
-impl WordAnalysis {
-    #[must_use] 
-    pub fn new(word: &str) -> Self {
-        Self {
-            word: word.to_string(),
-            pos: PartOfSpeech::Unknown,
-            verb_tense: None,
-            verb_form: None,
-            predicate_type: None,
-            pronoun_person: None,
-            pronoun_number: None,
-            is_filler: false,
-            is_stop_word: false,
-            is_emotion_word: false,
-            is_social_interaction: false,
-            is_egocentrism_marker: false,
-        }
-    }
-}
-
-/// Morphological analyzer
-pub struct MorphAnalyzer;
-
 impl MorphAnalyzer {
     #[must_use]
     pub fn new() -> Self {
-        Self
+        Self::with_lexicon(Lexicon::shared_builtin())
+    }
+
+    /// Create an analyzer using the given word lists for the semantic categories
+    #[must_use]
+    pub fn with_lexicon(lexicon: impl Into<Arc<Lexicon>>) -> Self {
+        Self {
+            lexicon: lexicon.into(),
+        }
     }
 
     /// Analyze a single word
@@ -119,9 +40,10 @@ impl MorphAnalyzer {
         let mut analysis = WordAnalysis::new(&word_lower);
 
         // Check special categories first
-        analysis.is_filler = Self::is_filler_word(&word_lower);
-        analysis.is_stop_word = Self::is_stop_word(&word_lower);
-        analysis.is_emotion_word = Self::is_emotion_word(&word_lower);
+        analysis.is_filler = self.lexicon.contains(LexiconCategory::FillerWords, &word_lower);
+        analysis.is_stop_word = self.lexicon.contains(LexiconCategory::StopWords, &word_lower);
+        analysis.is_emotion_word = self.lexicon.contains(LexiconCategory::EmotionWords, &word_lower);
+        analysis.emotion = self.lexicon.emotion(&word_lower);
         analysis.is_egocentrism_marker = Self::is_egocentrism_marker(&word_lower);
 
         // Determine part of speech
@@ -133,11 +55,13 @@ impl MorphAnalyzer {
             analysis.pos = PartOfSpeech::Pronoun;
             analysis.pronoun_person = Some(person);
             analysis.pronoun_number = Some(number);
-        } else if let Some((tense, form, pred_type)) = Self::analyze_verb(&word_lower) {
+        } else if let Some((tense, form, pred_type)) = self.analyze_verb(&word_lower) {
             analysis.pos = PartOfSpeech::Verb;
             analysis.verb_tense = Some(tense);
             analysis.verb_form = Some(form);
             analysis.predicate_type = Some(pred_type);
+            analysis.voice = Some(Self::voice(&word_lower, form));
+            analysis.is_reflexive = is_reflexive_form(&word_lower);
         } else if Self::is_adverb(&word_lower) {
             analysis.pos = PartOfSpeech::Adverb;
         } else if Self::is_adjective(&word_lower) {
@@ -147,7 +71,8 @@ impl MorphAnalyzer {
         }
 
         // Check for social interaction
-        analysis.is_social_interaction = Self::is_social_interaction_word(&word_lower);
+        analysis.is_social_interaction = Self::is_social_interaction_word(&word_lower)
+            || self.lexicon.contains(LexiconCategory::SocialFamilyWords, &word_lower);
 
         analysis
     }
@@ -199,21 +124,12 @@ impl MorphAnalyzer {
         None
     }
 
-    fn analyze_verb(word: &str) -> Option<(VerbTense, VerbForm, PredicateType)> {
-        // Check if it's in our predicate dictionaries first
-        let is_internal = INTERNAL_PREDICATES.contains(word);
-        let is_external = EXTERNAL_PREDICATES.contains(word);
-
-        let pred_type = if is_internal {
-            PredicateType::Internal
-        } else if is_external {
-            PredicateType::External
-        } else {
-            PredicateType::Neither
-        };
+    fn analyze_verb(&self, word: &str) -> Option<(VerbTense, VerbForm, PredicateType)> {
+        // Check if it's in the lexicon's predicate lists first
+        let pred_type = self.lexicon.predicate_type(word, word);
 
         // If it's in our verb dictionaries, it's definitely a verb
-        if is_internal || is_external {
+        if pred_type != PredicateType::Neither {
             let (tense, form) = Self::determine_verb_tense_form(word);
             return Some((tense, form, pred_type));
         }
@@ -321,18 +237,6 @@ impl MorphAnalyzer {
         word.len() > 2
     }
 
-    fn is_filler_word(word: &str) -> bool {
-        FILLER_WORDS.contains(word)
-    }
-
-    fn is_stop_word(word: &str) -> bool {
-        STOP_WORDS.contains(word)
-    }
-
-    fn is_emotion_word(word: &str) -> bool {
-        EMOTION_WORDS.contains(word)
-    }
-
     fn is_egocentrism_marker(word: &str) -> bool {
         FIRST_PERSON_SINGULAR.contains(word) || POSSESSIVE_FIRST_PERSON.contains(word)
     }
@@ -349,17 +253,54 @@ impl MorphAnalyzer {
         false
     }
 
-    /// Check if a word is a verb in active voice (approximation)
-    #[must_use]
-    pub fn is_active_voice(word: &str) -> bool {
-        // In Russian, passive voice is typically formed with:
-        // 1. Short passive participles (-н, -т endings)
-        // 2. Reflexive verbs with -ся/-сь (sometimes)
-        // 3. Analytical constructions with быть + participle
-
-        // For simplicity, we consider non-reflexive verbs as active voice
-        // and verbs without passive participle endings
-        !word.ends_with("ся") && !word.ends_with("сь")
+    /// Voice of a verb form: passive only for participles with passive suffixes
+    fn voice(word: &str, form: VerbForm) -> Voice {
+        if form == VerbForm::Participle && ends_with_any(word, PASSIVE_PARTICIPLE_ENDINGS) {
+            Voice::Passive
+        } else {
+            Voice::Active
+        }
+    }
+
+    /// Reanalyze a word after an auxiliary "быть" as a short passive participle
+    fn mark_short_participle(analysis: &mut WordAnalysis) {
+        analysis.pos = PartOfSpeech::Verb;
+        analysis.verb_tense = Some(VerbTense::Unknown);
+        analysis.verb_form = Some(VerbForm::Participle);
+        analysis.voice = Some(Voice::Passive);
+        analysis.predicate_type.get_or_insert(PredicateType::Neither);
+    }
+}
+
+impl MorphologyBackend for MorphAnalyzer {
+    fn name(&self) -> &'static str {
+        "rules"
+    }
+
+    fn analyze(&self, word: &str) -> WordAnalysis {
+        MorphAnalyzer::analyze(self, word)
+    }
+
+    fn lexicon(&self) -> &Lexicon {
+        &self.lexicon
+    }
+
+    /// Analyze every word on its own, then recognize short passive participles
+    /// after a form of "быть" ("был построен"), where the ending is reliable
+    fn analyze_sentence(&self, words: &[&str]) -> Vec<WordAnalysis> {
+        let mut analyses: Vec<WordAnalysis> = words.iter().map(|word| self.analyze(word)).collect();
+        for i in 0..analyses.len() {
+            if !analytic::is_auxiliary_form(&analyses[i]) {
+                continue;
+            }
+            if let Some(j) = analytic::main_verb_slot(&analyses, i)
+                && analyses[j].pos != PartOfSpeech::Verb
+                && analytic::looks_like_short_participle(&analyses[j].word)
+            {
+                Self::mark_short_participle(&mut analyses[j]);
+            }
+        }
+        analyses
     }
 }
 
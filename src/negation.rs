@@ -0,0 +1,124 @@
+//! Negation and its scope
+//! Negators ("не", "ни", "нет", "никогда", "ничего", ...) are found within a
+//! sentence and "не"/"ни" are attached to the predicate or adjective they
+//! negate, so "не помню" and "не страшно" are counted apart from "помню" and "страшно"
+
+use crate::analytic;
+use crate::backend::{PartOfSpeech, WordAnalysis};
+
+/// Negative particles, pronouns and adverbs
+const NEGATORS: [&str; 15] = [
+    "не", "ни", "нет", "никогда", "ничего", "ничто", "ничем", "никто", "никого", "никому", "никем",
+    "нигде", "никуда", "ниоткуда", "никак",
+];
+
+/// Negators that take scope over the following predicate or adjective
+const SCOPED_NEGATORS: [&str; 2] = ["не", "ни"];
+
+/// Adverbs allowed between a negated adverb and its head ("не очень страшно")
+const MAX_GAP: usize = 2;
+
+/// Whether the word is a negator
+#[must_use]
+pub fn is_negator(word: &str) -> bool {
+    NEGATORS.contains(&word)
+}
+
+/// Whether the word can be negated: a verb, adjective or adverb, or an
+/// emotion word left without a part of speech (predicatives like "страшно")
+fn is_negatable(analysis: &WordAnalysis) -> bool {
+    match analysis.pos {
+        PartOfSpeech::Verb | PartOfSpeech::Adjective | PartOfSpeech::Adverb => true,
+        PartOfSpeech::Unknown => analysis.is_emotion_word,
+        _ => false,
+    }
+}
+
+/// Index of the word negated by a scoped negator at `i`
+///
+/// Particles after the negator are skipped; the first other word must be a
+/// verb, adjective or adverb. An adverb passes the scope on to the adjective
+/// or adverb it modifies ("не очень страшно", "не слишком весёлый"), and an
+/// auxiliary to its main verb ("не был построен", "не буду делать").
+#[must_use]
+pub fn negation_scope(analyses: &[WordAnalysis], i: usize) -> Option<usize> {
+    let mut j = i + 1 + analyses.get(i + 1..)?.iter().position(|a| a.pos != PartOfSpeech::Particle)?;
+    if !is_negatable(&analyses[j]) {
+        return None;
+    }
+    for _ in 0..MAX_GAP {
+        match analyses.get(j + 1) {
+            Some(next)
+                if analyses[j].pos == PartOfSpeech::Adverb
+                    && (matches!(next.pos, PartOfSpeech::Adjective | PartOfSpeech::Adverb)
+                        || (next.pos == PartOfSpeech::Unknown && next.is_emotion_word)) =>
+            {
+                j += 1;
+            }
+            _ => break,
+        }
+    }
+    if analyses[j].is_auxiliary {
+        j = analytic::main_verb_slot(analyses, j)?;
+    }
+    Some(j)
+}
+
+/// Mark negators and the words they negate in one sentence
+///
+/// Expects analytic forms to be marked already, so that an auxiliary passes
+/// the negation on to its main verb.
+pub fn mark_negation(analyses: &mut [WordAnalysis]) {
+    for i in 0..analyses.len() {
+        if !is_negator(&analyses[i].word) {
+            continue;
+        }
+        analyses[i].is_negation = true;
+        if SCOPED_NEGATORS.contains(&analyses[i].word.as_str())
+            && let Some(j) = negation_scope(analyses, i)
+        {
+            analyses[j].is_negated = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, pos: PartOfSpeech) -> WordAnalysis {
+        let mut analysis = WordAnalysis::new(text);
+        analysis.pos = pos;
+        analysis
+    }
+
+    fn negated(analyses: &mut [WordAnalysis]) -> Vec<&str> {
+        mark_negation(analyses);
+        analyses.iter().filter(|a| a.is_negated).map(|a| a.word.as_str()).collect()
+    }
+
+    #[test]
+    fn test_negation_scope() {
+        use PartOfSpeech::{Adverb, Noun, Particle, Pronoun, Verb};
+
+        let mut sentence = [word("я", Pronoun), word("не", Particle), word("помню", Verb)];
+        assert_eq!(negated(&mut sentence), vec!["помню"]);
+        assert!(sentence[1].is_negation);
+
+        let mut sentence = [word("не", Particle), word("очень", Adverb), word("страшно", Adverb)];
+        assert_eq!(negated(&mut sentence), vec!["страшно"]);
+
+        let mut sentence = [word("не", Particle), word("маме", Noun), word("плохо", Adverb)];
+        assert!(negated(&mut sentence).is_empty());
+
+        let mut auxiliary = word("буду", Verb);
+        auxiliary.is_auxiliary = true;
+        let mut sentence = [word("не", Particle), auxiliary, word("плакать", Verb)];
+        assert_eq!(negated(&mut sentence), vec!["плакать"]);
+
+        // "никогда" is a negator without scope of its own
+        let mut sentence = [word("никогда", Adverb), word("не", Particle), word("плакал", Verb)];
+        assert_eq!(negated(&mut sentence), vec!["плакал"]);
+        assert!(sentence[0].is_negation && !sentence[0].is_negated);
+    }
+}
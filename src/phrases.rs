@@ -0,0 +1,170 @@
+//! Multi-word expression matching
+//! Recognises multi-word fillers ("как бы", "в общем") and compound
+//! conjunctions ("потому что", "несмотря на то что") over token sequences
+
+use crate::dictionaries::{COORDINATING_CONJUNCTIONS, FILLER_WORDS, SUBORDINATING_CONJUNCTIONS};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Category of a multi-word expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PhraseKind {
+    /// Filler phrase (слово-паразит)
+    Filler,
+    /// Compound coordinating conjunction ("не только", "но и")
+    CoordinatingConjunction,
+    /// Compound subordinating conjunction ("потому что", "так как")
+    SubordinatingConjunction,
+}
+
+impl PhraseKind {
+    #[must_use]
+    pub fn is_conjunction(self) -> bool {
+        matches!(
+            self,
+            PhraseKind::CoordinatingConjunction | PhraseKind::SubordinatingConjunction
+        )
+    }
+}
+
+/// A phrase found in a token sequence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhraseMatch {
+    /// Index of the first token
+    pub start: usize,
+    /// Number of tokens
+    pub len: usize,
+    pub kind: PhraseKind,
+    /// Normalized phrase (lowercase, single spaces)
+    pub phrase: String,
+}
+
+/// Membership of a token in a multi-word expression
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhraseToken {
+    pub phrase: String,
+    pub kind: PhraseKind,
+    /// First token of the phrase; the phrase is counted once, here
+    pub head: bool,
+}
+
+/// Longest-match recognizer of multi-word expressions
+#[derive(Debug, Clone, Default)]
+pub struct PhraseMatcher {
+    phrases: HashMap<String, PhraseKind>,
+    max_len: usize,
+}
+
+impl PhraseMatcher {
+    /// Build a matcher from `(phrase, kind)` entries; single-word entries are ignored
+    ///
+    /// If a phrase is listed twice, the first kind wins.
+    pub fn new<'a>(entries: impl IntoIterator<Item = (&'a str, PhraseKind)>) -> Self {
+        let mut matcher = Self::default();
+        for (phrase, kind) in entries {
+            let words: Vec<String> = phrase.split_whitespace().map(str::to_lowercase).collect();
+            if words.len() < 2 {
+                continue;
+            }
+            matcher.max_len = matcher.max_len.max(words.len());
+            matcher.phrases.entry(words.join(" ")).or_insert(kind);
+        }
+        matcher
+    }
+
+    /// Build a matcher from the given fillers and the built-in compound conjunctions
+    pub fn with_fillers<'a>(fillers: impl IntoIterator<Item = &'a str>) -> Self {
+        let fillers = fillers.into_iter().map(|p| (p, PhraseKind::Filler));
+        let coordinating = COORDINATING_CONJUNCTIONS
+            .iter()
+            .map(|p| (*p, PhraseKind::CoordinatingConjunction));
+        let subordinating = SUBORDINATING_CONJUNCTIONS
+            .iter()
+            .map(|p| (*p, PhraseKind::SubordinatingConjunction));
+        Self::new(fillers.chain(coordinating).chain(subordinating))
+    }
+
+    /// Number of multi-word phrases known to the matcher
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.phrases.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.phrases.is_empty()
+    }
+
+    /// Find non-overlapping phrases, scanning left to right and preferring
+    /// the longest phrase at each position
+    #[must_use]
+    pub fn find<S: AsRef<str>>(&self, words: &[S]) -> Vec<PhraseMatch> {
+        let lowered: Vec<String> = words.iter().map(|w| w.as_ref().to_lowercase()).collect();
+        let mut matches = Vec::new();
+        let mut i = 0;
+
+        while i < lowered.len() {
+            let longest = (2..=self.max_len.min(lowered.len() - i)).rev().find_map(|len| {
+                let candidate = lowered[i..i + len].join(" ");
+                self.phrases.get(&candidate).map(|&kind| PhraseMatch {
+                    start: i,
+                    len,
+                    kind,
+                    phrase: candidate,
+                })
+            });
+            match longest {
+                Some(found) => {
+                    i += found.len;
+                    matches.push(found);
+                }
+                None => i += 1,
+            }
+        }
+        matches
+    }
+
+    /// Phrase membership of every token, aligned with `words`
+    #[must_use]
+    pub fn annotate<S: AsRef<str>>(&self, words: &[S]) -> Vec<Option<PhraseToken>> {
+        let mut tokens = vec![None; words.len()];
+        for found in self.find(words) {
+            for (offset, token) in tokens[found.start..found.start + found.len].iter_mut().enumerate() {
+                *token = Some(PhraseToken {
+                    phrase: found.phrase.clone(),
+                    kind: found.kind,
+                    head: offset == 0,
+                });
+            }
+        }
+        tokens
+    }
+}
+
+/// Multi-word fillers and conjunctions from the built-in dictionaries
+pub static PHRASES: LazyLock<PhraseMatcher> =
+    LazyLock::new(|| PhraseMatcher::with_fillers(FILLER_WORDS.iter().copied()));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_match() {
+        let words = ["Я", "пришёл", "несмотря", "на", "то", "что", "было", "как", "бы", "поздно"];
+        let matches = PHRASES.find(&words);
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].phrase, "несмотря на то что");
+        assert_eq!(matches[0].kind, PhraseKind::SubordinatingConjunction);
+        assert_eq!((matches[0].start, matches[0].len), (2, 4));
+        assert_eq!(matches[1].phrase, "как бы");
+        assert_eq!(matches[1].kind, PhraseKind::Filler);
+
+        let annotated = PHRASES.annotate(&words);
+        assert!(annotated[2].as_ref().unwrap().head);
+        assert!(!annotated[5].as_ref().unwrap().head);
+        assert!(annotated[6].is_none());
+    }
+}
@@ -0,0 +1,260 @@
+//! Z-score profile of a text against the paper's reference groups (Table 2)
+//! Gives an interpretable view next to the LDA probabilities: how far each
+//! metric lies from the mean of every diagnostic group, in standard deviations
+
+use crate::metrics::{DiagnosticGroup, ReferenceValues, TextMetrics};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Metrics outside this many standard deviations are flagged
+pub const OUTLIER_Z_THRESHOLD: f64 = 2.0;
+
+type MetricAccessor = fn(&TextMetrics) -> f64;
+
+/// Metrics for which the paper reports standard deviations: (key, label, accessor)
+#[allow(clippy::cast_precision_loss)]
+const PROFILE_METRICS: &[(&str, &str, MetricAccessor)] = &[
+    ("total_words", "Объём текста", |m| {
+        m.total_words as f64
+    }),
+    (
+        "lexical_diversity_index",
+        "Лексическое разнообразие",
+        |m| m.lexical_diversity_index,
+    ),
+    (
+        "external_predicates",
+        "Внешние предикаты",
+        |m| m.external_predicates,
+    ),
+    (
+        "internal_predicates",
+        "Внутренние предикаты",
+        |m| m.internal_predicates,
+    ),
+    (
+        "past_tense_verbs",
+        "Глаголы прош. времени",
+        |m| m.past_tense_verbs,
+    ),
+    (
+        "present_tense_verbs",
+        "Глаголы наст. времени",
+        |m| m.present_tense_verbs,
+    ),
+    (
+        "first_person_singular_pronouns",
+        "Местоимения 1л. ед.ч.",
+        |m| m.first_person_singular_pronouns,
+    ),
+    ("emotion_words", "Слова эмоций", |m| {
+        m.emotion_words
+    }),
+    (
+        "social_interaction_words",
+        "Социальное взаимодействие",
+        |m| m.social_interaction_words,
+    ),
+    (
+        "non_finite_verb_forms",
+        "Отглагольные формы",
+        |m| m.non_finite_verb_forms,
+    ),
+];
+
+/// Deviation of a single metric from a group's reference mean
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDeviation {
+    /// Metric field name in `TextMetrics`
+    pub metric: String,
+    /// Observed value
+    pub value: f64,
+    /// Reference group mean
+    pub reference_mean: f64,
+    /// Reference group standard deviation
+    pub reference_sd: f64,
+    /// (value - mean) / sd
+    pub z_score: f64,
+    /// Whether |z| exceeds `OUTLIER_Z_THRESHOLD`
+    pub outside_range: bool,
+}
+
+/// Profile of a text against one reference group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupProfile {
+    pub group: DiagnosticGroup,
+    pub deviations: Vec<MetricDeviation>,
+    /// Number of metrics outside ±2 SD
+    pub flagged_count: usize,
+    /// Mean absolute z-score across metrics
+    pub mean_abs_z: f64,
+}
+
+/// Z-score profile of a text against every reference group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileComparison {
+    pub groups: Vec<GroupProfile>,
+}
+
+impl ProfileComparison {
+    /// Compare metrics against the paper's reference values (Table 2)
+    #[must_use]
+    pub fn compare(metrics: &TextMetrics) -> Self {
+        Self::with_references(metrics, &ReferenceValues::all())
+    }
+
+    /// Compare metrics against custom reference values
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn with_references(metrics: &TextMetrics, references: &[ReferenceValues]) -> Self {
+        let groups = references
+            .iter()
+            .map(|reference| {
+                let deviations: Vec<MetricDeviation> = PROFILE_METRICS
+                    .iter()
+                    .filter_map(|(key, _, get)| {
+                        let sd = get(&reference.std_dev);
+                        if sd <= 0.0 {
+                            return None;
+                        }
+                        let value = get(metrics);
+                        let mean = get(&reference.metrics);
+                        let z_score = (value - mean) / sd;
+                        Some(MetricDeviation {
+                            metric: (*key).to_string(),
+                            value,
+                            reference_mean: mean,
+                            reference_sd: sd,
+                            z_score,
+                            outside_range: z_score.abs() > OUTLIER_Z_THRESHOLD,
+                        })
+                    })
+                    .collect();
+
+                let flagged_count = deviations.iter().filter(|d| d.outside_range).count();
+                let mean_abs_z = if deviations.is_empty() {
+                    0.0
+                } else {
+                    deviations.iter().map(|d| d.z_score.abs()).sum::<f64>()
+                        / deviations.len() as f64
+                };
+
+                GroupProfile {
+                    group: reference.group,
+                    deviations,
+                    flagged_count,
+                    mean_abs_z,
+                }
+            })
+            .collect();
+
+        Self { groups }
+    }
+
+    /// Group whose reference profile is closest (smallest mean |z|)
+    #[must_use]
+    pub fn closest_group(&self) -> Option<DiagnosticGroup> {
+        self.groups
+            .iter()
+            .min_by(|a, b| a.mean_abs_z.total_cmp(&b.mean_abs_z))
+            .map(|p| p.group)
+    }
+
+    /// Render the profile as a text table (metrics × groups)
+    #[must_use]
+    pub fn render_table(&self) -> String {
+        let mut table = String::new();
+
+        let _ = write!(table, "{:<28}", "Показатель");
+        for profile in &self.groups {
+            let _ = write!(table, "{:>8} ", short_label(profile.group));
+        }
+        table.push('\n');
+
+        for (key, label, _) in PROFILE_METRICS {
+            if !self
+                .groups
+                .iter()
+                .any(|p| p.deviations.iter().any(|d| d.metric == *key))
+            {
+                continue;
+            }
+            let _ = write!(table, "{label:<28}");
+            for profile in &self.groups {
+                match profile.deviations.iter().find(|d| d.metric == *key) {
+                    Some(d) => {
+                        let mark = if d.outside_range { "*" } else { " " };
+                        let _ = write!(table, "{:>+8.2}{mark}", d.z_score);
+                    }
+                    None => {
+                        let _ = write!(table, "{:>8} ", "—");
+                    }
+                }
+            }
+            table.push('\n');
+        }
+
+        let _ = write!(table, "{:<28}", "Среднее |z|");
+        for profile in &self.groups {
+            let _ = write!(table, "{:>8.2} ", profile.mean_abs_z);
+        }
+        table.push('\n');
+        let _ = writeln!(
+            table,
+            "* — отклонение более ±{OUTLIER_Z_THRESHOLD:.0} SD от среднего группы"
+        );
+
+        table
+    }
+}
+
+/// Short column label for a group
+fn short_label(group: DiagnosticGroup) -> &'static str {
+    match group {
+        DiagnosticGroup::Healthy => "Здор.",
+        DiagnosticGroup::Schizophrenia => "Шиз.",
+        DiagnosticGroup::PersonalityDisorder => "РЛ",
+        DiagnosticGroup::BipolarDisorder => "БАР",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_mean_has_zero_z() {
+        let reference = ReferenceValues::schizophrenia();
+        let comparison = ProfileComparison::compare(&reference.metrics);
+
+        let own = comparison
+            .groups
+            .iter()
+            .find(|p| p.group == DiagnosticGroup::Schizophrenia)
+            .unwrap();
+        assert_eq!(own.deviations.len(), PROFILE_METRICS.len());
+        assert!(own.deviations.iter().all(|d| d.z_score.abs() < 1e-9));
+        assert_eq!(own.flagged_count, 0);
+        assert_eq!(
+            comparison.closest_group(),
+            Some(DiagnosticGroup::Schizophrenia)
+        );
+    }
+
+    #[test]
+    fn test_outlier_flagged() {
+        let mut metrics = ReferenceValues::healthy().metrics;
+        metrics.emotion_words = 20.0;
+        let comparison = ProfileComparison::compare(&metrics);
+
+        for profile in &comparison.groups {
+            let emotion = profile
+                .deviations
+                .iter()
+                .find(|d| d.metric == "emotion_words")
+                .unwrap();
+            assert!(emotion.outside_range);
+        }
+        assert!(comparison.render_table().contains('*'));
+    }
+}
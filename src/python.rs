@@ -0,0 +1,404 @@
+//! Python bindings (feature `python`)
+//! Exposes `TextAnalyzer`, `Classifier` and the batch path to Python, with
+//! metrics and results as dicts and batch results as DataFrame-ready rows
+
+use crate::analyzer::{DEFAULT_MIN_WORDS, TextAnalyzer};
+use crate::batch::{self, default_threads, group_code, metric_columns};
+use crate::classifier::Classifier;
+use crate::error::Error;
+use crate::lexicon::Lexicon;
+use crate::metrics::{ClassificationResult, DiagnosticGroup, TextMetrics};
+use crate::morphology::MorphAnalyzer;
+use pyo3::exceptions::{PyOSError, PyRuntimeWarning, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyString};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+use std::sync::Arc;
+
+/// Convert a JSON value to the matching Python object
+fn to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => i.into_pyobject(py)?.into_any(),
+            (None, Some(f)) => PyFloat::new(py, f).into_any(),
+            (None, None) => py.None().into_bound(py),
+        },
+        Value::String(s) => PyString::new(py, s).into_any(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(to_py(py, item)?)?;
+            }
+            list.into_any()
+        }
+        Value::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (key, item) in fields {
+                dict.set_item(key, to_py(py, item)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+/// Convert a Python dict, list or scalar to JSON
+fn from_py(object: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if object.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(b) = object.downcast::<PyBool>() {
+        Ok(Value::Bool(b.is_true()))
+    } else if let Ok(i) = object.extract::<i64>() {
+        Ok(Value::from(i))
+    } else if let Ok(f) = object.extract::<f64>() {
+        Ok(Number::from_f64(f).map_or(Value::Null, Value::Number))
+    } else if let Ok(s) = object.extract::<String>() {
+        Ok(Value::String(s))
+    } else if let Ok(dict) = object.downcast::<PyDict>() {
+        let mut fields = Map::new();
+        for (key, item) in dict {
+            fields.insert(key.extract()?, from_py(&item)?);
+        }
+        Ok(Value::Object(fields))
+    } else if let Ok(list) = object.downcast::<PyList>() {
+        list.iter().map(|item| from_py(&item)).collect::<PyResult<_>>().map(Value::Array)
+    } else {
+        Err(PyValueError::new_err(format!(
+            "cannot convert {} to JSON",
+            object.get_type().name()?
+        )))
+    }
+}
+
+fn to_dict<'py, T: Serialize>(py: Python<'py>, value: &T) -> PyResult<Bound<'py, PyAny>> {
+    let value = serde_json::to_value(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, &value)
+}
+
+fn from_dict<T: DeserializeOwned>(object: &Bound<'_, PyAny>) -> PyResult<T> {
+    serde_json::from_value(from_py(object)?).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Text rejected by `TextAnalyzer::try_analyze` as `ValueError`
+#[allow(clippy::needless_pass_by_value)] // used with `map_err`
+fn rejected(e: Error) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn warn_skipped(py: Python<'_>, what: &str, e: &Error) -> PyResult<()> {
+    let message = std::ffi::CString::new(format!("skipped '{what}': {e}"))?;
+    PyErr::warn(py, &py.get_type::<PyRuntimeWarning>(), &message, 1)
+}
+
+/// One batch row: the columns of the CSV export, with numbers kept as numbers
+fn batch_row<'py>(
+    py: Python<'py>,
+    id: &str,
+    metrics: &TextMetrics,
+    result: &ClassificationResult,
+) -> PyResult<Bound<'py, PyDict>> {
+    let row = PyDict::new(py);
+    row.set_item("id", id)?;
+    let values = serde_json::to_value(metrics).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    for (name, _) in metric_columns(metrics) {
+        row.set_item(name, to_py(py, &values[name])?)?;
+    }
+    let scores = &result.group_scores;
+    let probabilities = [
+        scores.healthy,
+        scores.schizophrenia,
+        scores.personality_disorder,
+        scores.bipolar_disorder,
+    ];
+    for (group, p) in DiagnosticGroup::ALL.iter().zip(probabilities) {
+        row.set_item(format!("p_{}", group_code(*group)), p)?;
+    }
+    row.set_item("predicted_group", group_code(result.primary_diagnosis))?;
+    row.set_item("confidence", result.confidence)?;
+    Ok(row)
+}
+
+/// Text analyzer computing the metrics of the paper
+///
+/// `TextAnalyzer(backend="rsmorphy", lexicon=None, narrative_only=False, min_words=5)`;
+/// empty, non-Russian and too short texts raise `ValueError`
+#[pyclass(name = "TextAnalyzer", module = "mdpredict_russian", frozen)]
+pub struct PyTextAnalyzer {
+    inner: TextAnalyzer,
+}
+
+#[pymethods]
+impl PyTextAnalyzer {
+    #[new]
+    #[pyo3(signature = (backend = "rsmorphy", lexicon = None, narrative_only = false, min_words = DEFAULT_MIN_WORDS))]
+    fn new(backend: &str, lexicon: Option<&str>, narrative_only: bool, min_words: usize) -> PyResult<Self> {
+        let lexicon = match lexicon {
+            Some(path) => Arc::new(
+                Lexicon::load(path).map_err(|e| PyOSError::new_err(format!("{path}: {e}")))?,
+            ),
+            None => Lexicon::shared_builtin(),
+        };
+        let inner = match backend {
+            "rsmorphy" => TextAnalyzer::try_with_lexicon(lexicon).map_err(|e| PyOSError::new_err(e.to_string()))?,
+            "rules" => TextAnalyzer::with_backend(MorphAnalyzer::with_lexicon(lexicon)),
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown backend '{other}' (use rsmorphy or rules)"
+                )));
+            }
+        };
+        Ok(Self {
+            inner: inner.with_narrative_only(narrative_only).with_min_words(min_words),
+        })
+    }
+
+    /// Metrics of one text as a dict
+    fn analyze<'py>(&self, py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyAny>> {
+        let metrics = py.allow_threads(|| self.inner.try_analyze(text)).map_err(rejected)?;
+        to_dict(py, &metrics)
+    }
+
+    /// Token and sentence annotation of one text, as `mdpredict --tokens --json`
+    fn analyze_detailed<'py>(&self, py: Python<'py>, text: &str) -> PyResult<Bound<'py, PyAny>> {
+        let detailed = py.allow_threads(|| self.inner.try_analyze_detailed(text)).map_err(rejected)?;
+        to_dict(py, &detailed)
+    }
+
+    /// Metrics of several texts in parallel, in input order; raises
+    /// `ValueError` naming the first rejected text
+    #[pyo3(signature = (texts, threads = None))]
+    #[allow(clippy::needless_pass_by_value)] // pyo3 extracts sequences by value
+    fn analyze_batch<'py>(
+        &self,
+        py: Python<'py>,
+        texts: Vec<String>,
+        threads: Option<usize>,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        let threads = threads.unwrap_or_else(default_threads);
+        let outcomes = py.allow_threads(|| {
+            batch::parallel_map(&texts, threads, |text| self.inner.try_analyze(text))
+        });
+        outcomes
+            .into_iter()
+            .enumerate()
+            .map(|(i, outcome)| {
+                let metrics = outcome.map_err(|e| PyValueError::new_err(format!("text {i}: {e}")))?;
+                to_dict(py, &metrics)
+            })
+            .collect()
+    }
+
+    /// Name and version of the word lists in use
+    fn lexicon<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_dict(py, &self.inner.lexicon().info())
+    }
+}
+
+/// LDA classifier into the four diagnostic groups
+///
+/// `Classifier(model=None)` uses the paper's coefficients; `model` is a path
+/// to a model saved by `mdpredict` (.json or .bin).
+#[pyclass(name = "Classifier", module = "mdpredict_russian", frozen)]
+pub struct PyClassifier {
+    inner: Classifier,
+}
+
+#[pymethods]
+impl PyClassifier {
+    #[new]
+    #[pyo3(signature = (model = None))]
+    fn new(model: Option<&str>) -> PyResult<Self> {
+        let inner = match model {
+            Some(path) => {
+                Classifier::load(path).map_err(|e| PyOSError::new_err(format!("{path}: {e}")))?
+            }
+            None => Classifier::new(),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Classification of a metrics dict (as returned by `TextAnalyzer.analyze`)
+    fn classify<'py>(
+        &self,
+        py: Python<'py>,
+        metrics: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let metrics: TextMetrics = from_dict(metrics)?;
+        to_dict(py, &self.inner.classify(&metrics))
+    }
+
+    /// Per-feature explanation of the classification of a metrics dict
+    fn explain<'py>(
+        &self,
+        py: Python<'py>,
+        metrics: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let metrics: TextMetrics = from_dict(metrics)?;
+        to_dict(py, &self.inner.explain(&metrics))
+    }
+}
+
+/// Analyze and classify texts, one row per text with the columns of the CSV
+/// export; `pandas.DataFrame(rows)` gives the same table as `mdpredict batch`
+///
+/// Rejected texts are skipped with a `RuntimeWarning`.
+#[pyfunction]
+#[pyo3(signature = (texts, ids = None, analyzer = None, classifier = None, threads = None))]
+#[allow(clippy::needless_pass_by_value)] // pyo3 extracts sequences by value
+fn batch_rows<'py>(
+    py: Python<'py>,
+    texts: Vec<String>,
+    ids: Option<Vec<String>>,
+    analyzer: Option<&PyTextAnalyzer>,
+    classifier: Option<&PyClassifier>,
+    threads: Option<usize>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let ids = ids.unwrap_or_else(|| (1..=texts.len()).map(|i| i.to_string()).collect());
+    if ids.len() != texts.len() {
+        return Err(PyValueError::new_err("ids and texts differ in length"));
+    }
+    let analyzer = match analyzer {
+        Some(analyzer) => analyzer.inner.clone(),
+        None => TextAnalyzer::try_new().map_err(|e| PyOSError::new_err(e.to_string()))?,
+    };
+    let classifier = classifier.map_or_else(Classifier::new, |c| c.inner.clone());
+
+    let threads = threads.unwrap_or_else(default_threads);
+    let results = py.allow_threads(|| {
+        batch::parallel_map(&texts, threads, |text| {
+            let metrics = analyzer.try_analyze(text)?;
+            let result = classifier.classify(&metrics);
+            Ok::<_, Error>((metrics, result))
+        })
+    });
+    let mut rows = Vec::with_capacity(texts.len());
+    for (id, outcome) in ids.iter().zip(results) {
+        match outcome {
+            Ok((metrics, result)) => rows.push(batch_row(py, id, &metrics, &result)?),
+            Err(e) => warn_skipped(py, id, &e)?,
+        }
+    }
+    Ok(rows)
+}
+
+/// Rows for a directory, file-name pattern or manifest, as `mdpredict batch`
+///
+/// Unreadable files and rejected texts are skipped with a `RuntimeWarning`.
+#[pyfunction]
+#[pyo3(signature = (source, analyzer = None, classifier = None, threads = None))]
+fn batch_source<'py>(
+    py: Python<'py>,
+    source: &str,
+    analyzer: Option<&PyTextAnalyzer>,
+    classifier: Option<&PyClassifier>,
+    threads: Option<usize>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let inputs = batch::collect_inputs(source).map_err(|e| PyOSError::new_err(e.to_string()))?;
+    let analyzer = match analyzer {
+        Some(analyzer) => analyzer.inner.clone(),
+        None => TextAnalyzer::try_new().map_err(|e| PyOSError::new_err(e.to_string()))?,
+    };
+    let classifier = classifier.map_or_else(Classifier::new, |c| c.inner.clone());
+
+    let threads = threads.unwrap_or_else(default_threads);
+    let outcomes = py.allow_threads(|| batch::process_inputs(&inputs, &analyzer, &classifier, threads));
+    let mut rows = Vec::with_capacity(inputs.len());
+    for (input, outcome) in inputs.iter().zip(outcomes) {
+        match outcome {
+            Ok((metrics, result)) => rows.push(batch_row(py, &input.id, &metrics, &result)?),
+            Err(e) => warn_skipped(py, &input.path.display().to_string(), &e)?,
+        }
+    }
+    Ok(rows)
+}
+
+#[pymodule]
+fn mdpredict_russian(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    module.add_class::<PyTextAnalyzer>()?;
+    module.add_class::<PyClassifier>()?;
+    module.add_function(wrap_pyfunction!(batch_rows, module)?)?;
+    module.add_function(wrap_pyfunction!(batch_source, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyInt;
+
+    fn rules_analyzer() -> PyTextAnalyzer {
+        PyTextAnalyzer {
+            inner: TextAnalyzer::with_backend(MorphAnalyzer::new()).with_min_words(2),
+        }
+    }
+
+    #[test]
+    fn test_json_conversion() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            // `True` is also an int in Python, so it must be checked first
+            let flag = from_py(PyBool::new(py, true).as_any()).unwrap();
+            assert_eq!(flag, Value::Bool(true));
+            assert!(to_py(py, &flag).unwrap().is_instance_of::<PyBool>());
+            let count = to_py(py, &Value::from(3)).unwrap();
+            assert!(count.is_exact_instance_of::<PyInt>());
+            assert_eq!(from_py(&count).unwrap(), Value::from(3));
+
+            // NaN has no JSON form and becomes null
+            assert_eq!(from_py(PyFloat::new(py, f64::NAN).as_any()).unwrap(), Value::Null);
+            assert!(to_py(py, &Value::Null).unwrap().is_none());
+
+            let value = serde_json::json!({"words": [1, 2.5, "x", null], "ok": false});
+            assert_eq!(from_py(&to_py(py, &value).unwrap()).unwrap(), value);
+            assert!(from_py(&py.get_type::<PyBool>().into_any()).is_err());
+        });
+    }
+
+    #[test]
+    fn test_batch_rows_checks_ids() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let analyzer = rules_analyzer();
+            let texts = vec!["Я упал.".to_string(), "Мы пошли гулять.".to_string()];
+            let error = batch_rows(py, texts, Some(vec!["s1".to_string()]), Some(&analyzer), None, Some(1))
+                .unwrap_err();
+            assert!(error.is_instance_of::<PyValueError>(py));
+        });
+    }
+
+    #[test]
+    fn test_batch_rows_skips_rejected_texts() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let analyzer = rules_analyzer();
+            let texts = vec!["Я упал.".to_string(), "Упал.".to_string(), "Мы пошли гулять.".to_string()];
+
+            let warnings = py.import("warnings").unwrap();
+            let record = PyDict::new(py);
+            record.set_item("record", true).unwrap();
+            let catcher = warnings.call_method("catch_warnings", (), Some(&record)).unwrap();
+            let caught = catcher.call_method0("__enter__").unwrap();
+            warnings.call_method1("simplefilter", ("always",)).unwrap();
+            let rows = batch_rows(py, texts, None, Some(&analyzer), None, Some(1));
+            catcher.call_method1("__exit__", (py.None(), py.None(), py.None())).unwrap();
+
+            let ids: Vec<String> = rows
+                .unwrap()
+                .iter()
+                .map(|row| row.get_item("id").unwrap().unwrap().extract().unwrap())
+                .collect();
+            assert_eq!(ids, vec!["1", "3"]);
+            let caught = caught.downcast::<PyList>().unwrap();
+            assert_eq!(caught.len(), 1);
+            let warning = caught.get_item(0).unwrap();
+            let category = warning.getattr("category").unwrap();
+            assert!(category.is(py.get_type::<PyRuntimeWarning>()));
+            let message = warning.getattr("message").unwrap().str().unwrap().to_string();
+            assert!(message.starts_with("skipped '2'"), "{message}");
+        });
+    }
+}
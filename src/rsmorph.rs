@@ -2,147 +2,225 @@
 //! Uses the `OpenCorpora` dictionary for accurate POS tagging
 
 use rsmorphy::prelude::*;
+use std::sync::{Arc, LazyLock};
 
-use crate::dictionaries::{
-    EMOTION_WORDS, EXTERNAL_PREDICATES, FILLER_WORDS, FIRST_PERSON_PLURAL, FIRST_PERSON_SINGULAR,
-    INTERNAL_PREDICATES, POSSESSIVE_FIRST_PERSON, SECOND_PERSON_PLURAL, SECOND_PERSON_SINGULAR,
-    SOCIAL_FAMILY_WORDS, STOP_WORDS, THIRD_PERSON_PLURAL, THIRD_PERSON_SINGULAR,
+// Word-level types are shared by all backends; re-exported here for compatibility
+pub use crate::backend::{
+    PartOfSpeech, PredicateType, PronounNumber, PronounPerson, VerbForm, VerbTense, WordAnalysis,
 };
+use crate::backend::{is_reflexive_form, Aspect, Case, MorphologyBackend, Voice};
+use crate::bundle::{self, BundleError};
+use crate::disambiguation::{disambiguate, Reading};
+use crate::error::{Error, Result};
 
-/// Part of speech categories (matching our existing enum)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PartOfSpeech {
-    Noun,
-    Verb,
-    Adjective,
-    Adverb,
-    Pronoun,
-    Preposition,
-    Conjunction,
-    Numeral,
-    Particle,
-    Interjection,
-    Unknown,
-}
-
-/// Verb tense
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum VerbTense {
-    Past,
-    Present,
-    Future,
-    Infinitive,
-    Unknown,
-}
+use crate::dictionaries::{
+    FIRST_PERSON_PLURAL, FIRST_PERSON_SINGULAR, POSSESSIVE_FIRST_PERSON, SECOND_PERSON_PLURAL,
+    SECOND_PERSON_SINGULAR, THIRD_PERSON_PLURAL, THIRD_PERSON_SINGULAR,
+};
+use crate::lexicon::{LemmaIndex, Lexicon, LexiconCategory};
+
+/// `OpenCorpora` dictionary from the rsmorphy-dict-ru crate, loaded once per
+/// process; a failed load is kept and reported to every constructor
+static DICTIONARY: LazyLock<Result<Arc<MorphAnalyzer>, Arc<BundleError>>> = LazyLock::new(|| {
+    bundle::load_dictionary_dir(rsmorphy_dict_ru::DICT_PATH)
+        .map(Arc::new)
+        .map_err(Arc::new)
+});
+
+/// Lemmas of the built-in lexicon, indexed once per process
+static BUILTIN_LEMMAS: LazyLock<Result<Arc<LemmaIndex>, Arc<BundleError>>> = LazyLock::new(|| {
+    let dictionary = DICTIONARY.as_ref().map_err(Arc::clone)?;
+    Ok(Arc::new(RsMorphAnalyzer::index_lemmas(dictionary, &Lexicon::shared_builtin())))
+});
 
-/// Verb form
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum VerbForm {
-    Finite,
-    Infinitive,
-    Participle,
-    Gerund,
-    Unknown,
+/// RsMorphy-based morphological analyzer
+///
+/// All instances share one dictionary, so cloning is cheap and the
+/// analyzer can be used from several threads at once.
+#[derive(Clone)]
+pub struct RsMorphAnalyzer {
+    analyzer: Arc<MorphAnalyzer>,
+    lexicon: Arc<Lexicon>,
+    lemmas: Arc<LemmaIndex>,
 }
 
-/// Predicate type
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PredicateType {
-    External,
-    Internal,
-    Neither,
-}
+impl RsMorphAnalyzer {
+    /// Create an analyzer with the built-in lexicon; the dictionary is loaded on first use only
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dictionary cannot be loaded; see [`Self::try_new`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|e| panic!("{e}"))
+    }
 
-/// Pronoun person
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PronounPerson {
-    First,
-    Second,
-    Third,
-    Reflexive,
-    Unknown,
-}
+    /// Create an analyzer with the built-in lexicon, reporting a dictionary
+    /// that cannot be loaded
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dictionary`] if the dictionary is missing or damaged.
+    pub fn try_new() -> Result<Self> {
+        Self::try_with_lexicon(Lexicon::shared_builtin())
+    }
 
-/// Pronoun number
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PronounNumber {
-    Singular,
-    Plural,
-    Unknown,
-}
+    /// Create an analyzer using the given word lists for the semantic categories
+    ///
+    /// Lexicon entries are lemmatized here, so every inflected form of a
+    /// listed word is matched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dictionary cannot be loaded; see [`Self::try_with_lexicon`].
+    #[must_use]
+    pub fn with_lexicon(lexicon: impl Into<Arc<Lexicon>>) -> Self {
+        Self::try_with_lexicon(lexicon).unwrap_or_else(|e| panic!("{e}"))
+    }
 
-/// Word analysis result
-#[derive(Debug, Clone)]
-#[allow(clippy::struct_excessive_bools)]
-pub struct WordAnalysis {
-    pub word: String,
-    pub lemma: Option<String>,
-    pub pos: PartOfSpeech,
-    pub verb_tense: Option<VerbTense>,
-    pub verb_form: Option<VerbForm>,
-    pub predicate_type: Option<PredicateType>,
-    pub pronoun_person: Option<PronounPerson>,
-    pub pronoun_number: Option<PronounNumber>,
-    pub is_filler: bool,
-    pub is_stop_word: bool,
-    pub is_emotion_word: bool,
-    pub is_social_interaction: bool,
-    pub is_egocentrism_marker: bool,
-}
+    /// Create an analyzer using the given word lists, reporting a dictionary
+    /// that cannot be loaded
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dictionary`] if the dictionary is missing or damaged.
+    pub fn try_with_lexicon(lexicon: impl Into<Arc<Lexicon>>) -> Result<Self> {
+        let dictionary = DICTIONARY.as_ref().map_err(|e| Error::Dictionary(Arc::clone(e)))?;
+        let lexicon = lexicon.into();
+        let lemmas = if Arc::ptr_eq(&lexicon, &Lexicon::shared_builtin()) {
+            BUILTIN_LEMMAS.as_ref().map_err(|e| Error::Dictionary(Arc::clone(e)))?.clone()
+        } else {
+            Arc::new(Self::index_lemmas(dictionary, &lexicon))
+        };
+        Ok(Self {
+            analyzer: Arc::clone(dictionary),
+            lexicon,
+            lemmas,
+        })
+    }
 
-impl WordAnalysis {
+    /// Create an analyzer on a dictionary loaded elsewhere, e.g. from a
+    /// bundle with [`crate::bundle::load_dictionary`] where
+    /// `rsmorphy_dict_ru::DICT_PATH` is not available
     #[must_use]
-    pub fn new(word: &str) -> Self {
+    pub fn with_dictionary(dictionary: Arc<MorphAnalyzer>, lexicon: impl Into<Arc<Lexicon>>) -> Self {
+        let lexicon = lexicon.into();
+        let lemmas = Arc::new(Self::index_lemmas(&dictionary, &lexicon));
         Self {
-            word: word.to_string(),
-            lemma: None,
-            pos: PartOfSpeech::Unknown,
-            verb_tense: None,
-            verb_form: None,
-            predicate_type: None,
-            pronoun_person: None,
-            pronoun_number: None,
-            is_filler: false,
-            is_stop_word: false,
-            is_emotion_word: false,
-            is_social_interaction: false,
-            is_egocentrism_marker: false,
+            analyzer: dictionary,
+            lexicon,
+            lemmas,
         }
     }
-}
 
-/// RsMorphy-based morphological analyzer
-pub struct RsMorphAnalyzer {
-    analyzer: MorphAnalyzer,
-}
+    /// Index the lexicon entries by the lemmas of all their parses
+    fn index_lemmas(analyzer: &MorphAnalyzer, lexicon: &Lexicon) -> LemmaIndex {
+        LemmaIndex::build(lexicon, |entry| {
+            let mut lemmas: Vec<(String, bool)> = Vec::new();
+            for parse in analyzer.parse(entry) {
+                let lemma = parse.lex.get_normal_form(analyzer).into_owned();
+                let verb = Self::extract_pos(&parse.lex.get_tag(analyzer).grammemes) == PartOfSpeech::Verb;
+                if !lemmas.contains(&(lemma.clone(), verb)) {
+                    lemmas.push((lemma, verb));
+                }
+            }
+            lemmas
+        })
+    }
 
-impl RsMorphAnalyzer {
-    #[must_use]
-    pub fn new() -> Self {
-        // Load dictionary from the rsmorphy-dict-ru crate
-        let analyzer = MorphAnalyzer::from_file(rsmorphy_dict_ru::DICT_PATH);
-        Self { analyzer }
+    /// Whether the word belongs to a lexicon category by form or, if
+    /// enabled, by the lemma of its selected reading
+    fn in_category(&self, category: LexiconCategory, word: &str, reading: Option<&Reading>) -> bool {
+        self.lexicon.contains(category, word)
+            || reading.is_some_and(|r| {
+                let verb = Self::extract_pos(r.grammemes) == PartOfSpeech::Verb;
+                self.lemmas.contains(category, &r.lemma, verb, self.lexicon.matching())
+            })
     }
 
-    /// Analyze a single word using rsmorphy
+    /// Predicate type by form, then by lemma; form matches take precedence
+    fn predicate_type(&self, word: &str, reading: Option<&Reading>) -> PredicateType {
+        match self.lexicon.predicate_type(word, word) {
+            PredicateType::Neither if self.in_category(LexiconCategory::InternalPredicates, word, reading) => {
+                PredicateType::Internal
+            }
+            PredicateType::Neither if self.in_category(LexiconCategory::ExternalPredicates, word, reading) => {
+                PredicateType::External
+            }
+            form_type => form_type,
+        }
+    }
+
+    /// Analyze a single word out of context, using its most probable parse
     #[must_use]
     pub fn analyze(&self, word: &str) -> WordAnalysis {
         let word_lower = word.to_lowercase();
-        let mut analysis = WordAnalysis::new(&word_lower);
-
-        // Check special categories first (using our dictionaries)
-        analysis.is_filler = FILLER_WORDS.contains(word_lower.as_str());
-        analysis.is_stop_word = STOP_WORDS.contains(word_lower.as_str());
-        analysis.is_emotion_word = EMOTION_WORDS.contains(word_lower.as_str());
-        analysis.is_egocentrism_marker = FIRST_PERSON_SINGULAR.contains(word_lower.as_str())
-            || POSSESSIVE_FIRST_PERSON.contains(word_lower.as_str());
+        let parses = self.analyzer.parse(&word_lower);
+        let reading = parses.first().map(|parse| self.reading(parse));
+        self.build_analysis(&word_lower, reading.as_ref(), false)
+    }
+
+    /// Analyze the words of one sentence, choosing every word's parse from
+    /// its neighbours instead of taking the most probable one
+    #[must_use]
+    pub fn analyze_sentence(&self, words: &[&str]) -> Vec<WordAnalysis> {
+        let lowered: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+        let parses: Vec<ParseResult> = lowered.iter().map(|w| self.analyzer.parse(w)).collect();
+        let readings: Vec<Vec<Reading>> = parses
+            .iter()
+            .map(|word_parses| word_parses.iter().map(|parse| self.reading(parse)).collect())
+            .collect();
+
+        let chosen = disambiguate(&readings);
+        lowered
+            .into_iter()
+            .zip(&readings)
+            .zip(chosen)
+            .map(|((word, word_readings), k)| {
+                self.build_analysis(&word, k.map(|k| &word_readings[k]), true)
+            })
+            .collect()
+    }
+
+    fn reading<'m>(&'m self, parse: &Parsed) -> Reading<'m> {
+        Reading {
+            lemma: parse.lex.get_normal_form(&self.analyzer).into_owned(),
+            grammemes: &parse.lex.get_tag(&self.analyzer).grammemes,
+            score: parse.score.value(),
+        }
+    }
+
+    /// Build the word analysis from the selected parse and the lexicon
+    ///
+    /// `from_context` tells whether the parse was chosen by sentence-level
+    /// disambiguation rather than by dictionary probability alone.
+    fn build_analysis(&self, word_lower: &str, reading: Option<&Reading>, from_context: bool) -> WordAnalysis {
+        let mut analysis = WordAnalysis::new(word_lower);
+        let lexicon = &self.lexicon;
+
+        // Check special categories first (using the lexicon)
+        analysis.is_filler = self.in_category(LexiconCategory::FillerWords, word_lower, reading);
+        analysis.is_stop_word = self.in_category(LexiconCategory::StopWords, word_lower, reading);
+        analysis.is_emotion_word = self.in_category(LexiconCategory::EmotionWords, word_lower, reading);
+        analysis.emotion = lexicon.emotion(word_lower).or_else(|| {
+            reading
+                .filter(|_| lexicon.matching().lemmas)
+                .and_then(|r| lexicon.emotion(&r.lemma))
+        });
+        analysis.is_egocentrism_marker = FIRST_PERSON_SINGULAR.contains(word_lower)
+            || POSSESSIVE_FIRST_PERSON.contains(word_lower);
         // Check for social/family words
-        if SOCIAL_FAMILY_WORDS.contains(word_lower.as_str()) {
+        if self.in_category(LexiconCategory::SocialFamilyWords, word_lower, reading) {
             analysis.is_social_interaction = true;
         }
 
-        // Check pronouns using our dictionaries (more reliable for this purpose)
-        if let Some((person, number)) = Self::check_pronoun_dictionaries(&word_lower) {
+        // Check pronouns using our dictionaries (more reliable for this purpose),
+        // unless the context selected a finite verb ("я мою руки")
+        let selected_finite_verb =
+            from_context && reading.is_some_and(|r| Self::has_grammeme(r.grammemes, "VERB"));
+        if let Some((person, number)) = Self::check_pronoun_dictionaries(word_lower)
+            && !selected_finite_verb
+        {
             analysis.pos = PartOfSpeech::Pronoun;
             analysis.pronoun_person = Some(person);
             analysis.pronoun_number = Some(number);
@@ -150,14 +228,17 @@ impl RsMorphAnalyzer {
                 && matches!(number, PronounNumber::Plural);
             return analysis;
         }
-
-        // Parse with rsmorphy
-        let parses = self.analyzer.parse(&word_lower);
+        if selected_finite_verb {
+            analysis.is_egocentrism_marker = false;
+        }
 
         // Always check predicate type using our dictionaries first
-        // (more reliable than rsmorphy for this specific use case)
-        let predicate_type = Self::check_predicate_type(&word_lower, &word_lower);
-        if predicate_type != PredicateType::Neither {
+        // (more reliable than rsmorphy for this specific use case),
+        // unless the context selected a non-verbal reading ("русская печь")
+        let non_verbal_in_context = from_context
+            && reading.is_some_and(|r| Self::extract_pos(r.grammemes) != PartOfSpeech::Verb);
+        let predicate_type = self.predicate_type(word_lower, reading);
+        if predicate_type != PredicateType::Neither && !non_verbal_in_context {
             analysis.predicate_type = Some(predicate_type);
             // If it's a predicate, it's effectively a verb for our purposes
             if analysis.pos == PartOfSpeech::Unknown || analysis.pos == PartOfSpeech::Conjunction {
@@ -165,29 +246,27 @@ impl RsMorphAnalyzer {
             }
         }
 
-        if let Some(parse) = parses.first() {
-            // Get lemma (normal form)
-            let normal_form = parse.lex.get_normal_form(&self.analyzer);
-            analysis.lemma = Some(normal_form.to_string());
-
-            // Extract POS and other info from grammemes
-            let tag = parse.lex.get_tag(&self.analyzer);
-            let grammemes = &tag.grammemes;
+        if let Some(reading) = reading {
+            analysis.lemma = Some(reading.lemma.clone());
+            let grammemes = reading.grammemes;
 
             // Only override POS if we didn't already set it from predicate check
             if analysis.predicate_type.is_none() {
                 analysis.pos = Self::extract_pos(grammemes);
             }
+            analysis.case = Self::extract_case(grammemes);
 
             // If it's a verb, extract tense and form
             if analysis.pos == PartOfSpeech::Verb {
                 analysis.verb_tense = Some(Self::extract_verb_tense(grammemes));
                 analysis.verb_form = Some(Self::extract_verb_form(grammemes));
+                analysis.voice = Some(Self::extract_voice(grammemes));
+                analysis.aspect = Self::extract_aspect(grammemes);
 
                 // Check predicate type using lemma if not already set
                 if analysis.predicate_type.is_none() {
-                    let lemma = analysis.lemma.as_deref().unwrap_or(&word_lower);
-                    let pred_type = Self::check_predicate_type(&word_lower, lemma);
+                    let lemma = analysis.lemma.as_deref().unwrap_or(word_lower);
+                    let pred_type = lexicon.predicate_type(word_lower, lemma);
                     if pred_type != PredicateType::Neither {
                         analysis.predicate_type = Some(pred_type);
                     }
@@ -200,6 +279,11 @@ impl RsMorphAnalyzer {
             }
         }
 
+        if analysis.pos == PartOfSpeech::Verb {
+            analysis.voice.get_or_insert(Voice::Active);
+            analysis.is_reflexive = is_reflexive_form(word_lower);
+        }
+
         analysis
     }
 
@@ -300,27 +384,48 @@ impl RsMorphAnalyzer {
         }
     }
 
-    /// Check if verb is 1st person plural
-    fn is_first_person_plural(grammemes: &GrammemeSet) -> bool {
-        Self::has_grammeme(grammemes, "1per") && Self::has_grammeme(grammemes, "plur")
+    /// Extract voice from grammemes; only participles are marked passive in `OpenCorpora`
+    fn extract_voice(grammemes: &GrammemeSet) -> Voice {
+        if Self::has_grammeme(grammemes, "pssv") {
+            Voice::Passive
+        } else {
+            Voice::Active
+        }
     }
 
-    /// Check predicate type (external/internal) using lemma
-    fn check_predicate_type(word: &str, lemma: &str) -> PredicateType {
-        // Check both word and lemma in our predicate dictionaries
-        if INTERNAL_PREDICATES.contains(word) || INTERNAL_PREDICATES.contains(lemma) {
-            PredicateType::Internal
-        } else if EXTERNAL_PREDICATES.contains(word) || EXTERNAL_PREDICATES.contains(lemma) {
-            PredicateType::External
+    /// Extract case from grammemes; the second genitive, accusative and
+    /// locative count as their main case
+    fn extract_case(grammemes: &GrammemeSet) -> Option<Case> {
+        [
+            ("nomn", Case::Nominative),
+            ("gent", Case::Genitive),
+            ("gen2", Case::Genitive),
+            ("datv", Case::Dative),
+            ("accs", Case::Accusative),
+            ("acc2", Case::Accusative),
+            ("ablt", Case::Instrumental),
+            ("loct", Case::Prepositional),
+            ("loc2", Case::Prepositional),
+        ]
+        .into_iter()
+        .find(|(tag, _)| Self::has_grammeme(grammemes, tag))
+        .map(|(_, case)| case)
+    }
+
+    /// Extract aspect from grammemes
+    fn extract_aspect(grammemes: &GrammemeSet) -> Option<Aspect> {
+        if Self::has_grammeme(grammemes, "perf") {
+            Some(Aspect::Perfective)
+        } else if Self::has_grammeme(grammemes, "impf") {
+            Some(Aspect::Imperfective)
         } else {
-            PredicateType::Neither
+            None
         }
     }
 
-    /// Check if a word is a verb in active voice (approximation)
-    #[must_use]
-    pub fn is_active_voice(word: &str) -> bool {
-        !word.ends_with("ся") && !word.ends_with("сь")
+    /// Check if verb is 1st person plural
+    fn is_first_person_plural(grammemes: &GrammemeSet) -> bool {
+        Self::has_grammeme(grammemes, "1per") && Self::has_grammeme(grammemes, "plur")
     }
 
     /// Check if a word is a coordinating conjunction
@@ -336,6 +441,24 @@ impl RsMorphAnalyzer {
     }
 }
 
+impl MorphologyBackend for RsMorphAnalyzer {
+    fn name(&self) -> &'static str {
+        "rsmorphy"
+    }
+
+    fn analyze(&self, word: &str) -> WordAnalysis {
+        RsMorphAnalyzer::analyze(self, word)
+    }
+
+    fn lexicon(&self) -> &Lexicon {
+        &self.lexicon
+    }
+
+    fn analyze_sentence(&self, words: &[&str]) -> Vec<WordAnalysis> {
+        RsMorphAnalyzer::analyze_sentence(self, words)
+    }
+}
+
 impl Default for RsMorphAnalyzer {
     fn default() -> Self {
         Self::new()
@@ -367,6 +490,19 @@ mod tests {
         let analysis = analyzer.analyze("катался");
         assert_eq!(analysis.pos, PartOfSpeech::Verb);
         assert_eq!(analysis.verb_tense, Some(VerbTense::Past));
+        assert_eq!(analysis.voice, Some(Voice::Active));
+        assert!(analysis.is_reflexive);
+    }
+
+    #[test]
+    fn test_passive_participle_voice() {
+        let analyzer = RsMorphAnalyzer::new();
+
+        let analysis = analyzer.analyze("отделана");
+        assert_eq!(analysis.verb_form, Some(VerbForm::Participle));
+        assert_eq!(analysis.voice, Some(Voice::Passive));
+        assert!(!analysis.is_reflexive);
+        assert_eq!(analyzer.analyze("читающий").voice, Some(Voice::Active));
     }
 
     #[test]
@@ -434,4 +570,53 @@ mod tests {
                 word, analysis.pos, analysis.predicate_type, analysis.lemma);
         }
     }
+
+    #[test]
+    fn test_lemma_lexicon_matching() {
+        use crate::lexicon::{LexiconFormat, MatchOptions};
+
+        let analyzer = RsMorphAnalyzer::new();
+        assert!(analyzer.analyze("рыдали").is_emotion_word);
+        assert!(analyzer.analyze("этому").is_stop_word);
+        assert!(analyzer.analyze("типа").is_filler);
+        assert!(!analyzer.analyze("тип").is_filler);
+        assert!(!analyzer.analyze("ела").is_stop_word);
+        assert!(!analyzer.analyze("заплакала").is_emotion_word);
+
+        let forms_only = RsMorphAnalyzer::with_lexicon(Lexicon::builtin().with_matching(MatchOptions::FORMS_ONLY));
+        assert!(!forms_only.analyze("рыдали").is_emotion_word);
+
+        let derived = RsMorphAnalyzer::with_lexicon(Lexicon::builtin().with_matching(MatchOptions {
+            lemmas: true,
+            aspect_pairs: true,
+            prefixes: true,
+        }));
+        assert!(derived.analyze("заплакала").is_emotion_word);
+        assert_eq!(derived.analyze("обрадовалась").predicate_type, Some(PredicateType::Internal));
+
+        let lexicon = Lexicon::builtin()
+            .extended("mode = replace\naspect_pairs = true\n[internal_predicates]\nрешать\n", LexiconFormat::Text)
+            .unwrap();
+        let aspect = RsMorphAnalyzer::with_lexicon(lexicon);
+        assert_eq!(aspect.analyze("решила").predicate_type, Some(PredicateType::Internal));
+        assert_eq!(aspect.analyze("решали").predicate_type, Some(PredicateType::Internal));
+        assert_eq!(aspect.analyze("думала").predicate_type, None);
+    }
+
+    #[test]
+    fn test_context_disambiguation() {
+        let analyzer = RsMorphAnalyzer::new();
+        let pos_of = |sentence: &str, word: usize| {
+            let words: Vec<&str> = sentence.split(' ').collect();
+            analyzer.analyze_sentence(&words)[word].pos
+        };
+
+        assert_eq!(pos_of("дом из стали", 2), PartOfSpeech::Noun);
+        assert_eq!(pos_of("мы стали играть", 1), PartOfSpeech::Verb);
+        assert_eq!(pos_of("русская печь стояла", 1), PartOfSpeech::Noun);
+        assert_eq!(pos_of("мама хотела печь пироги", 2), PartOfSpeech::Verb);
+        assert_eq!(pos_of("мне было три года", 2), PartOfSpeech::Numeral);
+        assert_eq!(pos_of("я мою руки", 1), PartOfSpeech::Verb);
+        assert_eq!(pos_of("в моем доме", 1), PartOfSpeech::Pronoun);
+    }
 }
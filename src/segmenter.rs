@@ -0,0 +1,319 @@
+//! Tokenization and sentence segmentation of Russian text
+//! Splits text into words and sentences with byte spans into the original
+//! text; periods of abbreviations ("т.е.", "г."), initials ("А. С. Пушкин")
+//! and decimals do not end a sentence, nor does punctuation inside quoted
+//! direct speech or a bracketed aside
+
+use crate::dictionaries::{ABBREVIATIONS, SENTENCE_FINAL_ABBREVIATIONS};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Year abbreviations: "в 1990 г." may end a sentence, "г. Москва" may not
+const YEAR_ABBREVIATIONS: [&str; 2] = ["г", "гг"];
+
+/// Split text into words with their byte offsets, using Unicode word
+/// segmentation and skipping tokens without letters (numbers, symbols)
+#[must_use]
+pub fn words(text: &str) -> Vec<(usize, &str)> {
+    text.unicode_word_indices()
+        .filter(|(_, s)| s.chars().any(char::is_alphabetic))
+        .collect()
+}
+
+/// Split text into sentences, returning trimmed `(start, end)` byte spans
+///
+/// Sentence-final punctuation is left out of the span, except inside quoted
+/// speech or an aside that closes the sentence («Не плачь!»). A sentence also
+/// ends at a blank line, and at a line break when the line has no final
+/// punctuation and the next one starts like a new sentence (a heading or a
+/// dialogue line). Pieces without letters or digits are not sentences.
+#[must_use]
+pub fn sentence_spans(text: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let offset = |i: usize| chars.get(i).map_or(text.len(), |&(pos, _)| pos);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut nesting = Nesting::default();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        if c == '\n' {
+            let next = chars[i..]
+                .iter()
+                .position(|&(_, c)| !c.is_whitespace())
+                .map_or(chars.len(), |n| i + n);
+            if is_line_break_boundary(&chars, i, next) {
+                push_span(text, &mut spans, start, pos);
+                start = offset(next);
+                nesting = Nesting::default();
+            }
+            i = next;
+        } else if is_terminal(c) && !nesting.is_open() {
+            let run_end = chars[i..]
+                .iter()
+                .position(|&(_, c)| !is_terminal(c))
+                .map_or(chars.len(), |n| i + n);
+            if is_terminal_boundary(&chars, i, run_end) {
+                push_span(text, &mut spans, start, pos);
+                start = offset(run_end);
+            }
+            i = run_end;
+        } else {
+            let was_open = nesting.is_open();
+            nesting.update(c);
+            i += 1;
+            // Quoted speech or an aside ending with its own punctuation closes the sentence
+            if was_open
+                && !nesting.is_open()
+                && i >= 2
+                && is_terminal(chars[i - 2].1)
+                && starts_sentence(&chars, i)
+            {
+                push_span(text, &mut spans, start, offset(i));
+                start = offset(i);
+            }
+        }
+    }
+    push_span(text, &mut spans, start, text.len());
+
+    spans
+}
+
+/// Open quotes and brackets; sentence punctuation inside them belongs to
+/// the quoted speech or the aside, not to the enclosing sentence
+#[derive(Default)]
+struct Nesting {
+    quotes: usize,
+    straight_quote: bool,
+    brackets: usize,
+}
+
+impl Nesting {
+    fn is_open(&self) -> bool {
+        self.quotes > 0 || self.straight_quote || self.brackets > 0
+    }
+
+    fn update(&mut self, c: char) {
+        match c {
+            '«' | '„' => self.quotes += 1,
+            // "“" closes „…“ but opens “…”
+            '“' if self.quotes == 0 => self.quotes += 1,
+            '»' | '“' | '”' => self.quotes = self.quotes.saturating_sub(1),
+            '"' => self.straight_quote = !self.straight_quote,
+            '(' | '[' => self.brackets += 1,
+            ')' | ']' => self.brackets = self.brackets.saturating_sub(1),
+            _ => {}
+        }
+    }
+}
+
+/// Sentence-final punctuation mark
+fn is_terminal(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '…')
+}
+
+fn is_dash(c: char) -> bool {
+    matches!(c, '-' | '–' | '—')
+}
+
+fn is_opening(c: char) -> bool {
+    matches!(c, '«' | '„' | '“' | '"' | '(' | '[')
+}
+
+/// Characters from `from` on, skipping whitespace
+fn non_space(chars: &[(usize, char)], from: usize) -> impl Iterator<Item = char> + '_ {
+    chars[from..].iter().map(|&(_, c)| c).filter(|c| !c.is_whitespace())
+}
+
+/// Whether the text from `from` is the author's remark after direct speech:
+/// a dash followed by a lowercase word ("Не плачь! — сказала мама")
+fn is_author_remark(chars: &[(usize, char)], from: usize) -> bool {
+    let mut rest = non_space(chars, from);
+    rest.next().is_some_and(is_dash) && rest.next().is_some_and(char::is_lowercase)
+}
+
+/// Whether the text from `from` starts a new sentence: nothing follows, or it
+/// starts with a capital letter, a number, a quote or a dialogue dash
+fn starts_sentence(chars: &[(usize, char)], from: usize) -> bool {
+    match non_space(chars, from).next() {
+        None => true,
+        Some(c) if is_dash(c) => !is_author_remark(chars, from),
+        Some(c) => c.is_uppercase() || c.is_numeric() || is_opening(c),
+    }
+}
+
+/// Whether the run of sentence punctuation `chars[i..run_end]` ends the sentence
+fn is_terminal_boundary(chars: &[(usize, char)], i: usize, run_end: usize) -> bool {
+    let run = &chars[i..run_end];
+    if run.iter().any(|&(_, c)| c == '…') || (run.len() > 1 && run.iter().all(|&(_, c)| c == '.')) {
+        // An ellipsis may trail off inside a sentence: "я думал... и вдруг"
+        return starts_sentence(chars, run_end);
+    }
+    if run.len() == 1 && run[0].1 == '.' {
+        return is_period_boundary(chars, i);
+    }
+    !is_author_remark(chars, run_end)
+}
+
+/// Whether a single period at `i` ends the sentence, i.e. it is not a
+/// decimal point, an initial or an abbreviation
+fn is_period_boundary(chars: &[(usize, char)], i: usize) -> bool {
+    let before = i.checked_sub(1).map(|j| chars[j].1);
+    let after = chars.get(i + 1).map(|&(_, c)| c);
+    if before.is_some_and(|c| c.is_ascii_digit()) && after.is_some_and(|c| c.is_ascii_digit()) {
+        // "3.5", "12.03.1990"
+        return false;
+    }
+
+    // The word before the period with its inner periods: "т.е", "г", "А.С"
+    let key_start = chars[..i]
+        .iter()
+        .rposition(|&(_, c)| !(c.is_alphabetic() || c == '.' || c == '-'))
+        .map_or(0, |j| j + 1);
+    let key: String = chars[key_start..i].iter().map(|&(_, c)| c).collect();
+    if is_initials(&key) {
+        return false;
+    }
+    let key = key.to_lowercase();
+    if YEAR_ABBREVIATIONS.contains(&key.as_str()) {
+        let after_number = chars[..key_start]
+            .iter()
+            .rev()
+            .find(|(_, c)| !c.is_whitespace())
+            .is_some_and(|&(_, c)| c.is_numeric());
+        return after_number && starts_sentence(chars, i + 1);
+    }
+    if ABBREVIATIONS.contains(key.as_str()) {
+        return false;
+    }
+    if SENTENCE_FINAL_ABBREVIATIONS.contains(key.as_str()) {
+        return starts_sentence(chars, i + 1);
+    }
+    !is_author_remark(chars, i + 1)
+}
+
+/// Initials before a period ("А", "А.С"); a lone "Я" is the pronoun
+fn is_initials(key: &str) -> bool {
+    key != "Я"
+        && key.split('.').all(|part| {
+            let mut letters = part.chars();
+            letters.next().is_some_and(char::is_uppercase) && letters.next().is_none()
+        })
+}
+
+/// Whether the line break at `i` ends the sentence; `next` is the first
+/// non-whitespace character after it
+fn is_line_break_boundary(chars: &[(usize, char)], i: usize, next: usize) -> bool {
+    if chars[i..next].iter().filter(|&&(_, c)| c == '\n').count() > 1 {
+        return true;
+    }
+    let bare_line_end = chars[..i]
+        .iter()
+        .rev()
+        .find(|(_, c)| !c.is_whitespace())
+        .is_some_and(|&(_, c)| c.is_alphanumeric() || matches!(c, '»' | '“' | '”' | '"' | ')'));
+    bare_line_end && starts_sentence(chars, next)
+}
+
+/// Push the trimmed span `start..end` if it contains a letter or a digit
+fn push_span(text: &str, spans: &mut Vec<(usize, usize)>, start: usize, end: usize) {
+    let piece = &text[start..end];
+    if !piece.chars().any(char::is_alphanumeric) {
+        return;
+    }
+    let trimmed_start = start + (piece.len() - piece.trim_start().len());
+    let trimmed_end = start + piece.trim_end().len();
+    spans.push((trimmed_start, trimmed_end));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(text: &str) -> Vec<&str> {
+        sentence_spans(text)
+            .into_iter()
+            .map(|(start, end)| &text[start..end])
+            .collect()
+    }
+
+    #[test]
+    fn test_words() {
+        let text = "Мне было 3–4 года, т.е. давно.";
+        let found: Vec<&str> = words(text).into_iter().map(|(_, w)| w).collect();
+        assert_eq!(found, vec!["Мне", "было", "года", "т.е", "давно"]);
+        assert_eq!(words(text)[1].0, "Мне ".len());
+    }
+
+    #[test]
+    fn test_abbreviations_and_initials() {
+        assert_eq!(
+            sentences("Мы читали стихи, т.е. А. С. Пушкина, Лермонтова и т. д. Потом спали."),
+            vec!["Мы читали стихи, т.е. А. С. Пушкина, Лермонтова и т. д", "Потом спали"]
+        );
+        assert_eq!(
+            sentences("Мы жили в г. Москве на ул. Ленина. Это было в 1990 г. Я был мал."),
+            vec!["Мы жили в г. Москве на ул. Ленина", "Это было в 1990 г", "Я был мал"]
+        );
+        assert_eq!(sentences("Это был я. Потом мама."), vec!["Это был я", "Потом мама"]);
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(
+            sentences("Мне было 2.5 года. 3–4 года"),
+            vec!["Мне было 2.5 года", "3–4 года"]
+        );
+        assert_eq!(sentences("Это было 12.03.1990 утром."), vec!["Это было 12.03.1990 утром"]);
+    }
+
+    #[test]
+    fn test_ellipsis() {
+        assert_eq!(
+            sentences("Я думал... и вдруг упал… Потом встал."),
+            vec!["Я думал... и вдруг упал", "Потом встал"]
+        );
+    }
+
+    #[test]
+    fn test_direct_speech_and_asides() {
+        assert_eq!(
+            sentences("Мама сказала: «Не плачь. Всё пройдёт». Я перестал."),
+            vec!["Мама сказала: «Не плачь. Всё пройдёт»", "Я перестал"]
+        );
+        assert_eq!(
+            sentences("«Не плачь!» — сказала мама. «Хорошо!» Я перестал."),
+            vec!["«Не плачь!» — сказала мама", "«Хорошо!»", "Я перестал"]
+        );
+        assert_eq!(
+            sentences("Не плачь! — сказала мама. Я перестал?! Да."),
+            vec!["Не плачь! — сказала мама", "Я перестал", "Да"]
+        );
+        assert_eq!(
+            sentences("Я упал (было больно! очень.) и заплакал. (Мне было три года.) Всё."),
+            vec!["Я упал (было больно! очень.) и заплакал", "(Мне было три года.)", "Всё"]
+        );
+    }
+
+    #[test]
+    fn test_line_breaks() {
+        assert_eq!(
+            sentences("Первое воспоминание\nЯ сижу на полу\n\nмама рядом"),
+            vec!["Первое воспоминание", "Я сижу на полу", "мама рядом"]
+        );
+        // A wrapped line continues the sentence
+        assert_eq!(
+            sentences("Я сижу на полу,\nа мама\nрядом."),
+            vec!["Я сижу на полу,\nа мама\nрядом"]
+        );
+        // An unclosed quote does not run past the paragraph
+        assert_eq!(sentences("Он сказал «нет\n\nПотом ушёл."), vec!["Он сказал «нет", "Потом ушёл"]);
+    }
+
+    #[test]
+    fn test_spans_point_into_text() {
+        let text = "  Первое предложение.  Второе!\nТретье";
+        assert_eq!(sentences(text), vec!["Первое предложение", "Второе", "Третье"]);
+        assert!(sentences("... !").is_empty());
+    }
+}
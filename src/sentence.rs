@@ -1,18 +1,16 @@
 //! Sentence structure analysis
-//! Determines sentence types: simple, compound, complex, run-on
+//! Determines sentence types: simple, compound, complex, run-on, mixed
 
-use crate::morphology::MorphAnalyzer;
-use regex::Regex;
-use std::sync::LazyLock;
-
-static SENTENCE_SPLITTER: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"[.!?]+\s*").unwrap());
-
-static CLAUSE_BOUNDARY: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"[,;:\-–—]").unwrap());
+use crate::analytic;
+use crate::backend::MorphologyBackend;
+use crate::clause::{self, Clause};
+use crate::rsmorph::RsMorphAnalyzer;
+use crate::segmenter;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Sentence type classification
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SentenceType {
     /// Simple sentence (простое предложение) - one independent clause
     Simple,
@@ -22,175 +20,158 @@ pub enum SentenceType {
     Complex,
     /// Run-on sentence (бессоюзное) - clauses without conjunctions
     RunOn,
+    /// Mixed sentence (сложное с разными видами связи) - clauses linked in more than one way
+    Mixed,
 }
 
 /// Sentence analysis result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SentenceAnalysis {
     pub text: String,
+    /// Byte offset of the sentence start in the analyzed text
+    pub start: usize,
+    /// Byte offset of the sentence end in the analyzed text
+    pub end: usize,
     pub sentence_type: SentenceType,
     pub clause_count: usize,
+    /// Clauses with their connectors, subjects and predicates
+    pub clauses: Vec<Clause>,
     pub word_count: usize,
     pub has_coordinating_conjunction: bool,
     pub has_subordinating_conjunction: bool,
 }
 
 /// Sentence analyzer
+///
+/// Predicates are detected with the same morphology backend that drives
+/// the word-level metrics.
+#[derive(Clone)]
 pub struct SentenceAnalyzer {
-    morph: MorphAnalyzer,
+    morph: Arc<dyn MorphologyBackend>,
 }
 
 impl SentenceAnalyzer {
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            morph: MorphAnalyzer::new(),
-        }
+        Self::with_backend(Arc::new(RsMorphAnalyzer::new()))
+    }
+
+    /// Create a sentence analyzer using a shared morphology backend
+    #[must_use]
+    pub fn with_backend(morph: Arc<dyn MorphologyBackend>) -> Self {
+        Self { morph }
     }
 
     /// Split text into sentences
     #[must_use]
     pub fn split_into_sentences(&self, text: &str) -> Vec<String> {
-        let cleaned = text.trim();
-        if cleaned.is_empty() {
-            return Vec::new();
-        }
-
-        SENTENCE_SPLITTER
-            .split(cleaned)
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
+        self.split_into_sentence_spans(text)
+            .into_iter()
+            .map(|(start, end)| text[start..end].to_string())
             .collect()
     }
 
+    /// Split text into sentences, returning trimmed `(start, end)` byte spans
+    ///
+    /// Abbreviations, initials, decimals, ellipsis inside a sentence, quoted
+    /// speech and bracketed asides do not break sentences (see `segmenter`).
+    #[must_use]
+    pub fn split_into_sentence_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        segmenter::sentence_spans(text)
+    }
+
     /// Analyze a single sentence
     #[must_use]
     pub fn analyze_sentence(&self, sentence: &str) -> SentenceAnalysis {
-        let words = Self::extract_words(sentence);
+        let (words, punctuation) = Self::extract_words(sentence);
         let word_count = words.len();
+        let units = self.merge_phrases(&words);
 
-        let has_coordinating = Self::has_coordinating_conjunction(&words);
-        let has_subordinating = Self::has_subordinating_conjunction(&words);
-
-        // Count potential clause boundaries
-        let clause_boundaries = CLAUSE_BOUNDARY.find_iter(sentence).count();
-
-        // Estimate clause count based on commas, conjunctions, and sentence length
-        let clause_count = self.estimate_clause_count(&words, clause_boundaries);
+        let has_coordinating = Self::has_coordinating_conjunction(&units);
+        let has_subordinating = Self::has_subordinating_conjunction(&units);
 
-        // Determine sentence type
-        let sentence_type =
-            Self::determine_sentence_type(clause_count, has_coordinating, has_subordinating);
+        // Segment into clauses around the finite predicates
+        let word_refs: Vec<&str> = words.iter().map(String::as_str).collect();
+        let mut analyses = self.morph.analyze_sentence(&word_refs);
+        analytic::mark_analytic_forms(&mut analyses);
+        let clauses = clause::segment(&words, &analyses, &punctuation, self.morph.lexicon().phrases());
+        let sentence_type = clause::sentence_type(&clauses);
 
         SentenceAnalysis {
             text: sentence.to_string(),
+            start: 0,
+            end: sentence.len(),
             sentence_type,
-            clause_count,
+            clause_count: clauses.len().max(1),
+            clauses,
             word_count,
             has_coordinating_conjunction: has_coordinating,
             has_subordinating_conjunction: has_subordinating,
         }
     }
 
-    /// Extract words from sentence
-    fn extract_words(sentence: &str) -> Vec<String> {
-        sentence
-            .split(|c: char| !c.is_alphanumeric())
-            .map(str::to_lowercase)
-            .filter(|s| !s.is_empty())
-            .collect()
+    /// Extract words from sentence, segmented the same way as in `TextAnalyzer`,
+    /// with whether clause punctuation precedes each word
+    fn extract_words(sentence: &str) -> (Vec<String>, Vec<bool>) {
+        let mut words = Vec::new();
+        let mut punctuation = Vec::new();
+        let mut previous_end = 0;
+        for (start, word) in segmenter::words(sentence) {
+            punctuation.push(Self::is_clause_punctuation(&sentence[previous_end..start]));
+            words.push(word.to_lowercase());
+            previous_end = start + word.len();
+        }
+        (words, punctuation)
+    }
+
+    /// Whether the text between two words separates clauses: a comma,
+    /// semicolon, colon, bracket or a dash (not a hyphen inside "кто-то")
+    fn is_clause_punctuation(gap: &str) -> bool {
+        gap.contains([',', ';', ':', '–', '—', '(', ')'])
+            || (gap.contains('-') && gap.trim() != "-")
+            || gap.contains(" -")
+            || gap.contains("- ")
+    }
+
+    /// Join multi-word fillers and compound conjunctions into single units,
+    /// so "потому что" is one conjunction and "как бы" is not a conjunction
+    fn merge_phrases(&self, words: &[String]) -> Vec<String> {
+        let mut units = Vec::with_capacity(words.len());
+        let mut next = 0;
+        for found in self.morph.lexicon().phrases().find(words) {
+            units.extend_from_slice(&words[next..found.start]);
+            units.push(found.phrase);
+            next = found.start + found.len;
+        }
+        units.extend_from_slice(&words[next..]);
+        units
     }
 
     /// Check for coordinating conjunctions
     fn has_coordinating_conjunction(words: &[String]) -> bool {
         words
             .iter()
-            .any(|w| MorphAnalyzer::is_coordinating_conjunction(w))
+            .any(|w| RsMorphAnalyzer::is_coordinating_conjunction(w))
     }
 
     /// Check for subordinating conjunctions
     fn has_subordinating_conjunction(words: &[String]) -> bool {
         words
             .iter()
-            .any(|w| MorphAnalyzer::is_subordinating_conjunction(w))
-    }
-
-    /// Estimate the number of clauses in a sentence
-    fn estimate_clause_count(&self, words: &[String], clause_boundaries: usize) -> usize {
-        // A clause typically needs at least a subject and predicate
-        // We use several heuristics:
-
-        // 1. Count verbs (potential predicates)
-        let verb_count = self.count_potential_verbs(words);
-
-        // 2. Consider punctuation boundaries
-        let punct_estimate = if clause_boundaries > 0 {
-            clause_boundaries + 1
-        } else {
-            1
-        };
-
-        // 3. Consider conjunctions
-        let conj_count = words
-            .iter()
-            .filter(|w| {
-                MorphAnalyzer::is_coordinating_conjunction(w)
-                    || MorphAnalyzer::is_subordinating_conjunction(w)
-            })
-            .count();
-
-        // Take the minimum of verb count and punctuation estimate
-        // but at least 1, and consider conjunctions
-        let base_estimate = verb_count.min(punct_estimate).max(1);
-
-        // If we have conjunctions, we likely have multiple clauses
-        if conj_count > 0 && base_estimate == 1 {
-            conj_count + 1
-        } else {
-            base_estimate
-        }
-    }
-
-    /// Count potential verbs in the word list
-    fn count_potential_verbs(&self, words: &[String]) -> usize {
-        let mut count = 0;
-        for word in words {
-            let analysis = self.morph.analyze(word);
-            if analysis.pos == crate::morphology::PartOfSpeech::Verb {
-                count += 1;
-            }
-        }
-        // Each clause should have at least one verb
-        count.max(1)
-    }
-
-    /// Determine sentence type based on clause count and conjunctions
-    fn determine_sentence_type(
-        clause_count: usize,
-        has_coordinating: bool,
-        has_subordinating: bool,
-    ) -> SentenceType {
-        if clause_count <= 1 {
-            // Single clause = simple sentence
-            SentenceType::Simple
-        } else if has_subordinating {
-            // Has subordinating conjunction = complex sentence
-            SentenceType::Complex
-        } else if has_coordinating {
-            // Has coordinating conjunction = compound sentence
-            SentenceType::Compound
-        } else {
-            // Multiple clauses without conjunctions = run-on sentence
-            SentenceType::RunOn
-        }
+            .any(|w| RsMorphAnalyzer::is_subordinating_conjunction(w))
     }
 
     /// Analyze all sentences in a text
     #[must_use]
     pub fn analyze_text(&self, text: &str) -> Vec<SentenceAnalysis> {
-        self.split_into_sentences(text)
-            .iter()
-            .map(|s| self.analyze_sentence(s))
+        self.split_into_sentence_spans(text)
+            .into_iter()
+            .map(|(start, end)| {
+                let mut analysis = self.analyze_sentence(&text[start..end]);
+                analysis.start = start;
+                analysis.end = end;
+                analysis
+            })
             .collect()
     }
 }
@@ -204,6 +185,7 @@ impl Default for SentenceAnalyzer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clause::ClauseConnector;
 
     #[test]
     fn test_sentence_splitting() {
@@ -215,6 +197,30 @@ mod tests {
         assert_eq!(sentences.len(), 3);
     }
 
+    #[test]
+    fn test_sentence_spans() {
+        let analyzer = SentenceAnalyzer::new();
+
+        let text = "  Первое предложение.  Второе!\nТретье";
+        let spans = analyzer.split_into_sentence_spans(text);
+
+        assert_eq!(spans.len(), 3);
+        assert_eq!(&text[spans[0].0..spans[0].1], "Первое предложение");
+        assert_eq!(&text[spans[1].0..spans[1].1], "Второе");
+        assert_eq!(&text[spans[2].0..spans[2].1], "Третье");
+    }
+
+    #[test]
+    fn test_sentence_splitting_abbreviations() {
+        let analyzer = SentenceAnalyzer::new();
+
+        let text = "Мы жили в г. Москве, т.е. у бабушки. Мне было 2.5 года… Мама сказала: «Не плачь!»";
+        let sentences = analyzer.split_into_sentences(text);
+
+        assert_eq!(sentences.len(), 3);
+        assert_eq!(sentences[2], "Мама сказала: «Не плачь!»");
+    }
+
     #[test]
     fn test_simple_sentence() {
         let analyzer = SentenceAnalyzer::new();
@@ -232,6 +238,36 @@ mod tests {
         assert!(analysis.has_coordinating_conjunction);
     }
 
+    #[test]
+    fn test_clause_count_uses_finite_verbs() {
+        let analyzer = SentenceAnalyzer::new();
+
+        // "совет", "привет" end like verbs but are nouns; "бегущий" is non-finite
+        let analysis = analyzer.analyze_sentence("Совет, привет, бегущий кот");
+        assert_eq!(analysis.clause_count, 1);
+        assert_eq!(analysis.sentence_type, SentenceType::Simple);
+
+        let analysis = analyzer.analyze_sentence("Я пришёл, мама спала");
+        assert_eq!(analysis.clause_count, 2);
+        assert_eq!(analysis.sentence_type, SentenceType::RunOn);
+    }
+
+    #[test]
+    fn test_compound_conjunctions_and_filler_phrases() {
+        let analyzer = SentenceAnalyzer::new();
+
+        // "как бы" is a filler, not the conjunction "как"
+        let analysis = analyzer.analyze_sentence("Я как бы устал");
+        assert!(!analysis.has_subordinating_conjunction);
+        assert_eq!(analysis.sentence_type, SentenceType::Simple);
+
+        // "несмотря на то что" is one subordinating conjunction, not "то" + "что"
+        let analysis = analyzer.analyze_sentence("Я пошёл гулять несмотря на то что шёл дождь");
+        assert!(analysis.has_subordinating_conjunction);
+        assert!(!analysis.has_coordinating_conjunction);
+        assert_eq!(analysis.sentence_type, SentenceType::Complex);
+    }
+
     #[test]
     fn test_complex_sentence() {
         let analyzer = SentenceAnalyzer::new();
@@ -240,4 +276,44 @@ mod tests {
         // Should detect subordinating conjunction "что"
         assert!(analysis.has_subordinating_conjunction);
     }
+
+    #[test]
+    fn test_clause_segmentation() {
+        let analyzer = SentenceAnalyzer::new();
+
+        // Homogeneous predicates share one grammatical base
+        let analysis = analyzer.analyze_sentence("Я пришёл и сел на стул");
+        assert_eq!(analysis.clause_count, 1);
+        assert_eq!(analysis.clauses[0].predicates.len(), 2);
+        assert_eq!(analysis.sentence_type, SentenceType::Simple);
+
+        // The relative clause is embedded into the main one
+        let analysis = analyzer.analyze_sentence("Дом, который построил Джек, стоит на холме");
+        assert_eq!(analysis.clause_count, 2);
+        assert_eq!(analysis.clauses[1].connector, ClauseConnector::Relative);
+        assert_eq!(analysis.clauses[0].words, vec![0, 4, 5, 6]);
+        assert_eq!(analysis.sentence_type, SentenceType::Complex);
+    }
+
+    #[test]
+    fn test_mixed_sentence() {
+        let analyzer = SentenceAnalyzer::new();
+
+        let analysis = analyzer.analyze_sentence("Я знаю, что он придёт, и я обрадовался");
+        assert_eq!(analysis.clause_count, 3);
+        let connectors: Vec<_> = analysis.clauses.iter().map(|c| c.connector).collect();
+        assert_eq!(
+            connectors,
+            vec![
+                ClauseConnector::None,
+                ClauseConnector::Subordinating,
+                ClauseConnector::Coordinating
+            ]
+        );
+        assert_eq!(analysis.sentence_type, SentenceType::Mixed);
+
+        // The main clause resuming after a subordinate one is not an asyndetic link
+        let analysis = analyzer.analyze_sentence("Когда я пришёл, мама спала");
+        assert_eq!(analysis.sentence_type, SentenceType::Complex);
+    }
 }
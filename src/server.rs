@@ -0,0 +1,613 @@
+//! HTTP JSON API behind `mdpredict serve`
+//! A small HTTP/1.1 server on the standard library: one shared `TextAnalyzer`
+//! and `Classifier` answer `/analyze`, `/classify`, `/batch` and `/health`
+
+use crate::analyzer::TextAnalyzer;
+use crate::batch::default_threads;
+use crate::classifier::Classifier;
+use crate::error::{Error, Result};
+use crate::metrics::TextMetrics;
+use crate::profile::ProfileComparison;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::Duration;
+
+/// Default limit on a request body: 1 MiB
+pub const DEFAULT_MAX_BODY: usize = 1 << 20;
+
+/// Default limit on the number of texts in one `/batch` request
+pub const DEFAULT_MAX_BATCH: usize = 1000;
+
+/// Limit on the request line and headers together
+const MAX_HEAD: usize = 16 * 1024;
+
+/// How long a connection may stay silent before it is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// JSON report of one text, the same as printed by `mdpredict --json`
+///
+/// # Errors
+///
+/// Returns the input errors of [`TextAnalyzer::try_analyze`].
+pub fn analysis_report(analyzer: &TextAnalyzer, classifier: &Classifier, text: &str) -> Result<Value> {
+    Ok(metrics_report(analyzer, classifier, &analyzer.try_analyze(text)?))
+}
+
+/// JSON report of metrics already computed by `analyzer`
+#[must_use]
+pub fn metrics_report(analyzer: &TextAnalyzer, classifier: &Classifier, metrics: &TextMetrics) -> Value {
+    let mut report = classification_report(classifier, metrics);
+    report["lexicon"] = json!(analyzer.lexicon().info());
+    report["metrics"] = json!(metrics);
+    report
+}
+
+/// Classification part of the report: groups, explanation and z-profile
+#[must_use]
+pub fn classification_report(classifier: &Classifier, metrics: &TextMetrics) -> Value {
+    json!({
+        "classification": classifier.classify(metrics),
+        "explanation": classifier.explain(metrics),
+        "profile": ProfileComparison::compare(metrics)
+    })
+}
+
+/// Error answered with a non-2xx status and a JSON body
+/// `{"error": {"code": ..., "message": ...}}`
+#[derive(Debug)]
+pub enum ApiError {
+    /// Malformed HTTP request or JSON body
+    BadRequest(String),
+    /// Text to analyze is empty or whitespace only
+    EmptyText,
+    /// Text to analyze is not Russian; share of Cyrillic letters, 0-1
+    NotRussian(f64),
+    /// Text to analyze has fewer words than the minimum
+    TooShort { words: usize, min_words: usize },
+    /// No such endpoint
+    NotFound(String),
+    /// Endpoint exists but not for this method
+    MethodNotAllowed(String),
+    /// POST without `Content-Length` (chunked bodies are not supported)
+    LengthRequired,
+    /// Body larger than the limit, in bytes
+    PayloadTooLarge(usize),
+    /// More texts in `/batch` than the limit
+    TooManyTexts(usize),
+    /// Request line and headers larger than the limit
+    HeadTooLarge,
+    /// Analysis failed unexpectedly
+    Internal,
+}
+
+impl ApiError {
+    /// HTTP status code
+    #[must_use]
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiError::BadRequest(_) => 400,
+            ApiError::NotFound(_) => 404,
+            ApiError::MethodNotAllowed(_) => 405,
+            ApiError::LengthRequired => 411,
+            ApiError::PayloadTooLarge(_) | ApiError::TooManyTexts(_) => 413,
+            ApiError::EmptyText | ApiError::NotRussian(_) | ApiError::TooShort { .. } => 422,
+            ApiError::HeadTooLarge => 431,
+            ApiError::Internal => 500,
+        }
+    }
+
+    /// `{"code": ..., "message": ...}` object of an error body
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({ "code": self.code(), "message": self.to_string() })
+    }
+
+    /// Machine-readable error code
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::EmptyText => "empty_text",
+            ApiError::NotRussian(_) => "not_russian",
+            ApiError::TooShort { .. } => "too_short",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::MethodNotAllowed(_) => "method_not_allowed",
+            ApiError::LengthRequired => "length_required",
+            ApiError::PayloadTooLarge(_) => "payload_too_large",
+            ApiError::TooManyTexts(_) => "too_many_texts",
+            ApiError::HeadTooLarge => "header_too_large",
+            ApiError::Internal => "internal_error",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::BadRequest(reason) => write!(f, "bad request: {reason}"),
+            ApiError::EmptyText => Error::EmptyText.fmt(f),
+            ApiError::NotRussian(share) => Error::NotRussian { cyrillic_share: *share }.fmt(f),
+            ApiError::TooShort { words, min_words } => Error::TooShort {
+                words: *words,
+                min_words: *min_words,
+            }
+            .fmt(f),
+            ApiError::NotFound(path) => write!(f, "no endpoint '{path}'"),
+            ApiError::MethodNotAllowed(method) => write!(f, "method {method} is not allowed here"),
+            ApiError::LengthRequired => write!(f, "Content-Length is required"),
+            ApiError::PayloadTooLarge(limit) => write!(f, "body exceeds {limit} bytes"),
+            ApiError::TooManyTexts(limit) => write!(f, "batch exceeds {limit} texts"),
+            ApiError::HeadTooLarge => write!(f, "request headers exceed {MAX_HEAD} bytes"),
+            ApiError::Internal => write!(f, "internal error during analysis"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::EmptyText => ApiError::EmptyText,
+            Error::NotRussian { cyrillic_share } => ApiError::NotRussian(cyrillic_share),
+            Error::TooShort { words, min_words } => ApiError::TooShort { words, min_words },
+            _ => ApiError::Internal,
+        }
+    }
+}
+
+/// A parsed HTTP request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+/// A JSON response
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(error: &ApiError) -> Self {
+        Self {
+            status: error.status(),
+            body: json!({ "error": error.to_json() }),
+        }
+    }
+
+    /// Write the response with `Connection: close`
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if writing fails.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let body = self.body.to_string();
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            self.status,
+            reason_phrase(self.status),
+            body.len()
+        )?;
+        writer.flush()
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Read one request, rejecting bodies larger than `max_body` before reading them
+///
+/// # Errors
+///
+/// Returns [`ApiError`] for malformed or oversized requests.
+pub fn read_request(reader: &mut impl BufRead, max_body: usize) -> Result<Request, ApiError> {
+    let mut head = String::new();
+    loop {
+        let before = head.len();
+        let limit = (MAX_HEAD + 1).saturating_sub(before) as u64;
+        let read = reader
+            .by_ref()
+            .take(limit)
+            .read_line(&mut head)
+            .map_err(|e| ApiError::BadRequest(format!("cannot read request: {e}")))?;
+        if head.len() > MAX_HEAD {
+            return Err(ApiError::HeadTooLarge);
+        }
+        if read == 0 {
+            return Err(ApiError::BadRequest("incomplete request".to_string()));
+        }
+        if head[before..].trim().is_empty() {
+            // Blank lines before the request line are ignored
+            if head.trim().is_empty() {
+                head.clear();
+                continue;
+            }
+            break;
+        }
+    }
+
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target), Some(_version)) =
+        (request_line.next(), request_line.next(), request_line.next())
+    else {
+        return Err(ApiError::BadRequest("malformed request line".to_string()));
+    };
+
+    let mut content_length = None;
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(ApiError::BadRequest(format!("malformed header '{}'", line.trim())));
+        };
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            let length = value
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| ApiError::BadRequest("invalid Content-Length".to_string()))?;
+            content_length = Some(length);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err(ApiError::LengthRequired);
+        }
+    }
+
+    let body = match content_length {
+        Some(length) if length > max_body => return Err(ApiError::PayloadTooLarge(max_body)),
+        Some(length) => {
+            let mut body = vec![0; length];
+            reader
+                .read_exact(&mut body)
+                .map_err(|e| ApiError::BadRequest(format!("cannot read body: {e}")))?;
+            body
+        }
+        None if method == "POST" => return Err(ApiError::LengthRequired),
+        None => Vec::new(),
+    };
+
+    let path = target.split_once('?').map_or(target, |(path, _)| path);
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        body,
+    })
+}
+
+/// Body of `/analyze`
+#[derive(Deserialize)]
+struct TextRequest {
+    text: String,
+}
+
+/// Body of `/classify`: a text or precomputed metrics
+#[derive(Deserialize)]
+struct ClassifyRequest {
+    text: Option<String>,
+    metrics: Option<TextMetrics>,
+}
+
+/// Body of `/batch`
+#[derive(Deserialize)]
+struct BatchRequest {
+    texts: Vec<BatchText>,
+}
+
+/// One text of a batch: a bare string or `{"id": ..., "text": ...}`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BatchText {
+    Plain(String),
+    Identified { id: String, text: String },
+}
+
+impl BatchText {
+    fn text(&self) -> &str {
+        match self {
+            BatchText::Plain(text) | BatchText::Identified { text, .. } => text,
+        }
+    }
+}
+
+fn parse_body<'a, T: Deserialize<'a>>(body: &'a [u8]) -> Result<T, ApiError> {
+    serde_json::from_slice(body).map_err(|e| ApiError::BadRequest(format!("invalid JSON: {e}")))
+}
+
+/// HTTP server sharing one analyzer and classifier across requests
+pub struct Server {
+    analyzer: TextAnalyzer,
+    classifier: Classifier,
+    max_body: usize,
+    max_batch: usize,
+    threads: usize,
+}
+
+impl Server {
+    /// Create a server with the default limits, one worker per core
+    #[must_use]
+    pub fn new(analyzer: TextAnalyzer, classifier: Classifier) -> Self {
+        Self {
+            analyzer,
+            classifier,
+            max_body: DEFAULT_MAX_BODY,
+            max_batch: DEFAULT_MAX_BATCH,
+            threads: default_threads(),
+        }
+    }
+
+    /// Limit the request body size, in bytes
+    #[must_use]
+    pub fn with_max_body(mut self, bytes: usize) -> Self {
+        self.max_body = bytes;
+        self
+    }
+
+    /// Limit the number of texts in one `/batch` request
+    #[must_use]
+    pub fn with_max_batch(mut self, texts: usize) -> Self {
+        self.max_batch = texts;
+        self
+    }
+
+    /// Number of connections served at once
+    ///
+    /// Each worker analyzes a `/batch` on its own thread, so at most `threads`
+    /// texts are analyzed at any time.
+    #[must_use]
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Bind to `addr` and serve until the process exits
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the address cannot be bound.
+    pub fn serve(&self, addr: impl ToSocketAddrs) -> io::Result<()> {
+        let listener = TcpListener::bind(addr)?;
+        self.run(&listener);
+        Ok(())
+    }
+
+    /// Accept and answer connections on a bound listener, one request per connection
+    pub fn run(&self, listener: &TcpListener) {
+        thread::scope(|scope| {
+            for _ in 0..self.threads {
+                scope.spawn(|| {
+                    for stream in listener.incoming().flatten() {
+                        self.serve_connection(stream);
+                    }
+                });
+            }
+        });
+    }
+
+    fn serve_connection(&self, mut stream: TcpStream) {
+        // A client that stops sending or reading must not hold a worker
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+        let _ = stream.set_write_timeout(Some(READ_TIMEOUT));
+        let request = read_request(&mut BufReader::new(&stream), self.max_body);
+        let response = match request {
+            Ok(request) => panic::catch_unwind(AssertUnwindSafe(|| self.handle(&request)))
+                .unwrap_or_else(|_| Response::error(&ApiError::Internal)),
+            Err(e) => Response::error(&e),
+        };
+        let _ = response.write_to(&mut stream);
+    }
+
+    /// Answer one request
+    #[must_use]
+    pub fn handle(&self, request: &Request) -> Response {
+        let result = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/health") => Ok(self.health()),
+            ("POST", "/analyze") => self.analyze(&request.body),
+            ("POST", "/classify") => self.classify(&request.body),
+            ("POST", "/batch") => self.batch(&request.body),
+            (method, "/health" | "/analyze" | "/classify" | "/batch") => {
+                Err(ApiError::MethodNotAllowed(method.to_string()))
+            }
+            (_, path) => Err(ApiError::NotFound(path.to_string())),
+        };
+        result.map_or_else(|e| Response::error(&e), Response::ok)
+    }
+
+    fn health(&self) -> Value {
+        json!({
+            "status": "ok",
+            "version": env!("CARGO_PKG_VERSION"),
+            "lexicon": self.analyzer.lexicon().info(),
+            "narrative_only": self.analyzer.narrative_only()
+        })
+    }
+
+    /// `{"text": ...}` → the `--json` report
+    fn analyze(&self, body: &[u8]) -> Result<Value, ApiError> {
+        let request: TextRequest = parse_body(body)?;
+        Ok(analysis_report(&self.analyzer, &self.classifier, &request.text)?)
+    }
+
+    /// `{"text": ...}` or `{"metrics": {...}}` → classification, explanation and profile
+    fn classify(&self, body: &[u8]) -> Result<Value, ApiError> {
+        let request: ClassifyRequest = parse_body(body)?;
+        let metrics = match (request.text, request.metrics) {
+            (Some(text), None) => self.analyzer.try_analyze(&text)?,
+            (None, Some(metrics)) => metrics,
+            _ => {
+                return Err(ApiError::BadRequest(
+                    "expected exactly one of \"text\" and \"metrics\"".to_string(),
+                ));
+            }
+        };
+        Ok(classification_report(&self.classifier, &metrics))
+    }
+
+    /// `{"texts": [...]}` → `{"results": [...]}`, one `--json` report per text, in order
+    fn batch(&self, body: &[u8]) -> Result<Value, ApiError> {
+        let request: BatchRequest = parse_body(body)?;
+        if request.texts.len() > self.max_batch {
+            return Err(ApiError::TooManyTexts(self.max_batch));
+        }
+        // A rejected text gets an error entry in place of its report,
+        // so one bad text does not fail the whole batch
+        let results: Vec<Value> = request
+            .texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| {
+                match analysis_report(&self.analyzer, &self.classifier, text.text()).map_err(ApiError::from) {
+                    Ok(mut report) => {
+                        if let BatchText::Identified { id, .. } = text {
+                            report["id"] = json!(id);
+                        }
+                        report
+                    }
+                    Err(error) => match text {
+                        BatchText::Identified { id, .. } => {
+                            json!({ "id": id, "error": error.to_json() })
+                        }
+                        BatchText::Plain(_) => json!({ "index": index, "error": error.to_json() }),
+                    },
+                }
+            })
+            .collect();
+        Ok(json!({ "results": results }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::morphology::MorphAnalyzer;
+    use std::io::Cursor;
+
+    fn server() -> Server {
+        let analyzer = TextAnalyzer::with_backend(MorphAnalyzer::new()).with_min_words(2);
+        Server::new(analyzer, Classifier::new()).with_threads(2).with_max_batch(2)
+    }
+
+    fn post(path: &str, body: &str) -> Request {
+        Request {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /analyze?x=1 HTTP/1.1\r\nHost: localhost\r\ncontent-length: 4\r\n\r\nbodyrest";
+        let request = read_request(&mut Cursor::new(raw), 16).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/analyze");
+        assert_eq!(request.body, b"body");
+
+        let raw = "POST /analyze HTTP/1.1\r\nContent-Length: 100\r\n\r\n";
+        let error = read_request(&mut Cursor::new(raw), 16).unwrap_err();
+        assert_eq!(error.status(), 413);
+
+        let raw = "POST /analyze HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n";
+        assert_eq!(read_request(&mut Cursor::new(raw), 16).unwrap_err().status(), 411);
+
+        assert_eq!(read_request(&mut Cursor::new("GARBAGE\r\n\r\n"), 16).unwrap_err().status(), 400);
+    }
+
+    #[test]
+    fn test_endpoints() {
+        let server = server();
+        let text = r#"{"text": "Я помню как катался на велосипеде и упал."}"#;
+
+        let response = server.handle(&post("/analyze", text));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["metrics"]["total_words"], 8);
+        for key in ["lexicon", "classification", "explanation", "profile"] {
+            assert!(response.body.get(key).is_some(), "{key}");
+        }
+
+        let response = server.handle(&post("/classify", text));
+        assert_eq!(response.status, 200);
+        assert!(response.body["classification"]["primary_diagnosis"].is_string());
+        let metrics = json!({ "metrics": TextMetrics::default() }).to_string();
+        assert_eq!(server.handle(&post("/classify", &metrics)).status, 200);
+        // Only the classifier features are needed, the other metrics default to zero
+        let partial = r#"{"metrics": {"total_words": 40, "past_tense_verbs": 12.5, "external_predicates": 3.0}}"#;
+        let response = server.handle(&post("/classify", partial));
+        assert_eq!(response.status, 200);
+        assert!(response.body["classification"]["primary_diagnosis"].is_string());
+
+        let batch = r#"{"texts": ["Я упал.", {"id": "s2", "text": "Мы пошли гулять."}]}"#;
+        let response = server.handle(&post("/batch", batch));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["results"][0]["metrics"]["total_words"], 2);
+        assert_eq!(response.body["results"][1]["id"], "s2");
+
+        // A rejected text gets an error entry, the rest of the batch is analyzed
+        let batch = r#"{"texts": ["Упал.", {"id": "s2", "text": "  "}]}"#;
+        let response = server.handle(&post("/batch", batch));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["results"][0]["index"], 0);
+        assert_eq!(response.body["results"][0]["error"]["code"], "too_short");
+        assert_eq!(response.body["results"][1]["id"], "s2");
+        assert_eq!(response.body["results"][1]["error"]["code"], "empty_text");
+
+        let health = Request {
+            method: "GET".to_string(),
+            path: "/health".to_string(),
+            body: Vec::new(),
+        };
+        assert_eq!(server.handle(&health).body["status"], "ok");
+    }
+
+    #[test]
+    fn test_error_responses() {
+        let server = server();
+        let error = |request: &Request| {
+            let response = server.handle(request);
+            (response.status, response.body["error"]["code"].as_str().unwrap().to_string())
+        };
+
+        assert_eq!(error(&post("/analyze", "{")), (400, "bad_request".to_string()));
+        assert_eq!(error(&post("/analyze", r#"{"text": "  "}"#)), (422, "empty_text".to_string()));
+        assert_eq!(
+            error(&post("/analyze", r#"{"text": "I remember my first bicycle."}"#)),
+            (422, "not_russian".to_string())
+        );
+        assert_eq!(error(&post("/classify", r#"{"text": "Упал."}"#)), (422, "too_short".to_string()));
+        assert_eq!(error(&post("/classify", "{}")), (400, "bad_request".to_string()));
+        assert_eq!(
+            error(&post("/batch", r#"{"texts": ["а", "б", "в"]}"#)),
+            (413, "too_many_texts".to_string())
+        );
+        assert_eq!(error(&post("/nowhere", "{}")), (404, "not_found".to_string()));
+        let get = Request {
+            method: "GET".to_string(),
+            path: "/analyze".to_string(),
+            body: Vec::new(),
+        };
+        assert_eq!(error(&get), (405, "method_not_allowed".to_string()));
+    }
+}
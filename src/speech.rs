@@ -0,0 +1,237 @@
+//! Direct and reported speech detection
+//! Finds quoted speech («Не плачь»), dialogue lines opened by a dash and
+//! clauses of reported speech ("сказала, что ..."), so that other people's
+//! words can be measured separately from the author's narrative
+
+use crate::backend::WordAnalysis;
+use crate::dictionaries::SPEECH_VERBS;
+use crate::sentence::SentenceAnalysis;
+use serde::{Deserialize, Serialize};
+
+/// Conjunctions introducing reported speech after a speech verb
+const REPORTED_SPEECH_CONJUNCTIONS: [&str; 4] = ["что", "чтобы", "будто", "как"];
+
+/// Whose words a token belongs to, if not the narrator's
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpeechKind {
+    /// Direct speech: quoted («Не плачь») or a dialogue line ("— Не плачь")
+    Direct,
+    /// Reported speech: a clause after a speech verb ("сказала, что я вырасту")
+    Reported,
+}
+
+/// Byte spans of direct speech in the text, without the quotes and dashes
+///
+/// A quotation is direct speech when it follows a colon, is followed by the
+/// author's remark after a dash, or ends with its own punctuation; quoted
+/// titles ("фильм «Буратино»") are not. On a line opened by a dash, the
+/// author's remarks between dashes ("— Не плачь, — сказала мама. — Всё
+/// пройдёт.") are left out.
+#[must_use]
+pub fn direct_speech_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = quoted_speech_spans(text);
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        spans.extend(dialogue_spans(line).into_iter().map(|(s, e)| (line_start + s, line_start + e)));
+        line_start += line.len();
+    }
+    spans.sort_unstable();
+    spans
+}
+
+/// Whether the byte offset lies inside one of the spans
+#[must_use]
+pub fn in_spans(spans: &[(usize, usize)], offset: usize) -> bool {
+    spans.iter().any(|&(start, end)| start <= offset && offset < end)
+}
+
+/// Whether the word is a speech verb, by form or by lemma
+#[must_use]
+pub fn is_speech_verb(analysis: &WordAnalysis) -> bool {
+    SPEECH_VERBS.contains(analysis.word.as_str())
+        || analysis.lemma.as_deref().is_some_and(|lemma| SPEECH_VERBS.contains(lemma))
+}
+
+/// Sentence words in reported speech: the words of a subordinate clause
+/// introduced by "что", "чтобы", "будто" or "как" right after a clause whose
+/// predicate is a speech verb
+///
+/// `analyses` are the analyses of the sentence words, in sentence order.
+#[must_use]
+pub fn reported_speech_words(sentence: &SentenceAnalysis, analyses: &[&WordAnalysis]) -> Vec<usize> {
+    let mut words = Vec::new();
+    for pair in sentence.clauses.windows(2) {
+        let (main, clause) = (&pair[0], &pair[1]);
+        let reported = clause.connector.is_subordinate()
+            && clause
+                .conjunction
+                .as_deref()
+                .is_some_and(|c| REPORTED_SPEECH_CONJUNCTIONS.contains(&c))
+            && main
+                .predicates
+                .iter()
+                .any(|&p| analyses.get(p).is_some_and(|a| is_speech_verb(a)));
+        if reported {
+            words.extend_from_slice(&clause.words);
+        }
+    }
+    words
+}
+
+/// Closing quote for an opening one
+fn closing_quote(c: char) -> Option<char> {
+    match c {
+        '«' => Some('»'),
+        '„' => Some('“'),
+        '“' => Some('”'),
+        '"' => Some('"'),
+        _ => None,
+    }
+}
+
+fn is_dash(c: char) -> bool {
+    matches!(c, '–' | '—')
+}
+
+/// Quotations that read as direct speech
+fn quoted_speech_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while let Some((open, quote, close)) = text[pos..]
+        .char_indices()
+        .find_map(|(i, c)| closing_quote(c).map(|close| (pos + i, c, close)))
+    {
+        let content_start = open + quote.len_utf8();
+        let Some(end) = find_closing(text, content_start, quote, close) else {
+            break;
+        };
+        pos = end + close.len_utf8();
+
+        let content = text[content_start..end].trim_end();
+        let after_colon = text[..open].trim_end().ends_with(':');
+        let own_punctuation = content.ends_with(['.', '!', '?', '…', ',']);
+        let remark_follows = starts_author_remark(text[pos..].trim_start_matches(','));
+        if after_colon || own_punctuation || remark_follows {
+            spans.push((content_start, end));
+        }
+    }
+    spans
+}
+
+/// Byte offset of the quote closing the one opened just before `from`;
+/// nested quotes of the same kind are skipped («Он сказал «нет»»)
+fn find_closing(text: &str, from: usize, open: char, close: char) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text[from..].char_indices() {
+        if c == close {
+            if depth == 0 {
+                return Some(from + i);
+            }
+            depth -= 1;
+        } else if c == open {
+            depth += 1;
+        }
+    }
+    None
+}
+
+/// Whether the text starts with a dash followed by a lowercase word
+fn starts_author_remark(text: &str) -> bool {
+    let mut chars = text.chars().filter(|c| !c.is_whitespace());
+    chars.next().is_some_and(|c| is_dash(c) || c == '-')
+        && chars.next().is_some_and(char::is_lowercase)
+}
+
+/// Speech parts of a line opened by a dash, as byte spans within the line
+///
+/// Dashes between spaces separate the speech from the author's remarks: a
+/// remark starts after ",", "!", "?" or "…" with a lowercase word and ends
+/// at the next dash after ".", "," or ":". Other dashes stay in the speech.
+fn dialogue_spans(line: &str) -> Vec<(usize, usize)> {
+    let body = line.trim_start();
+    let mut chars = body.chars();
+    let opened = match chars.next() {
+        Some(c) if is_dash(c) => true,
+        Some('-') => chars.next().is_some_and(char::is_whitespace),
+        _ => false,
+    };
+    if !opened {
+        return Vec::new();
+    }
+
+    let first = line.len() - body.len() + body.chars().next().map_or(0, char::len_utf8);
+    let separators = line[first..].char_indices().filter_map(|(i, c)| {
+        let (start, end) = (first + i, first + i + c.len_utf8());
+        let spaced = line[..start].ends_with(char::is_whitespace)
+            && line[end..].starts_with(char::is_whitespace);
+        ((is_dash(c) || c == '-') && spaced).then_some((start, end))
+    });
+
+    let mut spans = Vec::new();
+    let mut speaking = true;
+    let mut start = first;
+    for (separator_start, separator_end) in separators {
+        let before = line[start..separator_start].trim_end();
+        let lowercase_follows = line[separator_end..]
+            .trim_start()
+            .starts_with(char::is_lowercase);
+        let switch = if speaking {
+            before.ends_with([',', '!', '?', '…']) && lowercase_follows
+        } else {
+            before.ends_with(['.', ',', ':'])
+        };
+        if switch {
+            if speaking {
+                push_trimmed(line, &mut spans, start, separator_start);
+            }
+            speaking = !speaking;
+            start = separator_end;
+        }
+    }
+    if speaking {
+        push_trimmed(line, &mut spans, start, line.len());
+    }
+    spans
+}
+
+/// Push the trimmed span `start..end` unless it is empty
+fn push_trimmed(text: &str, spans: &mut Vec<(usize, usize)>, start: usize, end: usize) {
+    let piece = &text[start..end];
+    let trimmed_start = start + (piece.len() - piece.trim_start().len());
+    let trimmed_end = start + piece.trim_end().len();
+    if trimmed_start < trimmed_end {
+        spans.push((trimmed_start, trimmed_end));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speech(text: &str) -> Vec<&str> {
+        direct_speech_spans(text)
+            .into_iter()
+            .map(|(start, end)| &text[start..end])
+            .collect()
+    }
+
+    #[test]
+    fn test_quoted_speech() {
+        assert_eq!(speech("Мама сказала: «Не плачь». Я перестал."), vec!["Не плачь"]);
+        assert_eq!(speech("«Не плачь!» — сказала мама."), vec!["Не плачь!"]);
+        assert_eq!(speech("«Пойдём», — позвал папа."), vec!["Пойдём"]);
+        // Quoted titles are not speech
+        assert!(speech("Мы смотрели фильм «Буратино» вместе.").is_empty());
+    }
+
+    #[test]
+    fn test_dialogue_lines() {
+        let text = "Я упал.
+— Не плачь, — сказала мама. — Всё пройдёт.
+- Больно! — крикнул я.";
+        assert_eq!(speech(text), vec!["Не плачь,", "Всё пройдёт.", "Больно!"]);
+
+        // A dash inside the speech is not a remark
+        assert_eq!(speech("— Мама — врач."), vec!["Мама — врач."]);
+    }
+}
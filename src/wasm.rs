@@ -0,0 +1,208 @@
+//! WebAssembly bindings (feature `wasm`)
+//! Exposes `TextAnalyzer` and `Classifier` to JavaScript so a static page can
+//! compute metrics in the browser; the rsmorphy dictionary is passed in as bytes
+
+use crate::analyzer::TextAnalyzer;
+use crate::bundle;
+use crate::classifier::Classifier;
+use crate::lexicon::{Lexicon, LexiconFormat};
+use crate::metrics::TextMetrics;
+use crate::model::ClassifierModel;
+use crate::morphology::MorphAnalyzer;
+use crate::rsmorph::RsMorphAnalyzer;
+use crate::server;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_wasm_bindgen::Serializer;
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+
+/// Convert to a plain JS object (maps as objects, not `Map`)
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    value
+        .serialize(&Serializer::json_compatible())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn from_js<T: DeserializeOwned>(value: JsValue) -> Result<T, JsError> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Options of `new TextAnalyzer(dictionary, options)`
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct AnalyzerOptions {
+    /// Lexicon source applied to the built-in lists
+    lexicon: Option<String>,
+    /// Format of `lexicon`: "toml" (default), "json" or "text"
+    lexicon_format: Option<String>,
+    narrative_only: bool,
+    /// Minimum number of words; `None` keeps the default
+    min_words: Option<usize>,
+}
+
+impl AnalyzerOptions {
+    fn lexicon(&self) -> Result<Arc<Lexicon>, JsError> {
+        let Some(source) = &self.lexicon else {
+            return Ok(Lexicon::shared_builtin());
+        };
+        let format = match self.lexicon_format.as_deref() {
+            None | Some("toml") => LexiconFormat::Toml,
+            Some("json") => LexiconFormat::Json,
+            Some("text") => LexiconFormat::Text,
+            Some(other) => {
+                return Err(JsError::new(&format!(
+                    "unknown lexicon format '{other}' (use toml, json or text)"
+                )));
+            }
+        };
+        let lexicon = Lexicon::builtin()
+            .extended(source, format)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(Arc::new(lexicon))
+    }
+}
+
+/// Text analyzer computing the metrics of the paper
+///
+/// `new TextAnalyzer(dictionary?, options?)`: `dictionary` is the rsmorphy
+/// dictionary bundle as a `Uint8Array` (see `mdpredict dictionary`); without
+/// it the rule-based backend is used. `options` is
+/// `{lexicon?, lexiconFormat?, narrativeOnly?, minWords?}`.
+#[wasm_bindgen(js_name = TextAnalyzer)]
+pub struct WasmTextAnalyzer {
+    inner: TextAnalyzer,
+}
+
+#[wasm_bindgen(js_class = TextAnalyzer)]
+impl WasmTextAnalyzer {
+    /// # Errors
+    ///
+    /// Fails if the bundle, the options or the lexicon cannot be read.
+    #[wasm_bindgen(constructor)]
+    pub fn new(dictionary: Option<Vec<u8>>, options: JsValue) -> Result<WasmTextAnalyzer, JsError> {
+        let options: AnalyzerOptions = if options.is_undefined() || options.is_null() {
+            AnalyzerOptions::default()
+        } else {
+            from_js(options)?
+        };
+        let lexicon = options.lexicon()?;
+        let inner = match dictionary {
+            Some(bytes) => {
+                let dictionary = bundle::load_dictionary(&bytes).map_err(|e| JsError::new(&e.to_string()))?;
+                TextAnalyzer::with_backend(RsMorphAnalyzer::with_dictionary(Arc::new(dictionary), lexicon))
+            }
+            None => TextAnalyzer::with_backend(MorphAnalyzer::with_lexicon(lexicon)),
+        };
+        let inner = inner.with_narrative_only(options.narrative_only);
+        Ok(Self {
+            inner: match options.min_words {
+                Some(words) => inner.with_min_words(words),
+                None => inner,
+            },
+        })
+    }
+
+    /// Name of the morphology backend: "rsmorphy" or "rules"
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn backend(&self) -> String {
+        self.inner.backend_name().to_string()
+    }
+
+    /// Metrics of one text
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text is empty, not Russian or too short, or if the
+    /// metrics cannot be converted to a JS object.
+    pub fn analyze(&self, text: &str) -> Result<JsValue, JsError> {
+        to_js(&self.inner.try_analyze(text).map_err(|e| JsError::new(&e.to_string()))?)
+    }
+
+    /// Token and sentence annotation of one text, as `mdpredict --tokens --json`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text is empty, not Russian or too short, or if the
+    /// annotation cannot be converted to a JS object.
+    #[wasm_bindgen(js_name = analyzeDetailed)]
+    pub fn analyze_detailed(&self, text: &str) -> Result<JsValue, JsError> {
+        to_js(&self.inner.try_analyze_detailed(text).map_err(|e| JsError::new(&e.to_string()))?)
+    }
+
+    /// Metrics, classification, explanation and profile, as `mdpredict --json`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text is empty, not Russian or too short, or if the
+    /// report cannot be converted to a JS object.
+    pub fn report(&self, text: &str, classifier: &WasmClassifier) -> Result<JsValue, JsError> {
+        let report = server::analysis_report(&self.inner, &classifier.inner, text)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        to_js(&report)
+    }
+
+    /// Name and version of the word lists in use
+    ///
+    /// # Errors
+    ///
+    /// Fails only if the result cannot be converted to a JS object.
+    pub fn lexicon(&self) -> Result<JsValue, JsError> {
+        to_js(&self.inner.lexicon().info())
+    }
+}
+
+/// LDA classifier into the four diagnostic groups
+///
+/// `new Classifier(model?)`: `model` is the JSON text of a model saved by
+/// `mdpredict`; without it the paper's coefficients are used.
+#[wasm_bindgen(js_name = Classifier)]
+pub struct WasmClassifier {
+    inner: Classifier,
+}
+
+#[wasm_bindgen(js_class = Classifier)]
+impl WasmClassifier {
+    /// # Errors
+    ///
+    /// Fails if the model is not valid JSON or does not fit this library.
+    #[wasm_bindgen(constructor)]
+    pub fn new(model: Option<String>) -> Result<WasmClassifier, JsError> {
+        let inner = match model {
+            Some(json) => {
+                let model: ClassifierModel = serde_json::from_str(&json).map_err(|e| JsError::new(&e.to_string()))?;
+                Classifier::from_model(model).map_err(|e| JsError::new(&e.to_string()))?
+            }
+            None => Classifier::new(),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Classification of metrics as returned by `TextAnalyzer.analyze`
+    ///
+    /// # Errors
+    ///
+    /// Fails if `metrics` is not a metrics object.
+    pub fn classify(&self, metrics: JsValue) -> Result<JsValue, JsError> {
+        let metrics: TextMetrics = from_js(metrics)?;
+        to_js(&self.inner.classify(&metrics))
+    }
+
+    /// Per-feature explanation of the classification of metrics
+    ///
+    /// # Errors
+    ///
+    /// Fails if `metrics` is not a metrics object.
+    pub fn explain(&self, metrics: JsValue) -> Result<JsValue, JsError> {
+        let metrics: TextMetrics = from_js(metrics)?;
+        to_js(&self.inner.explain(&metrics))
+    }
+}
+
+/// Library version
+#[wasm_bindgen]
+#[must_use]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}